@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, net::SocketAddr};
+use std::{marker::PhantomData, net::SocketAddr, time::Duration};
 
 use bevy_ecs::{
     entity::Entity,
@@ -7,8 +7,8 @@ use bevy_ecs::{
 };
 
 use naia_client::{
-    shared::{ChannelIndex, Protocolize, ReplicateSafe},
-    Client as NaiaClient, EntityRef,
+    shared::{ChannelIndex, Protocolize, Replicate, ReplicateSafe, Tick},
+    Client as NaiaClient, EntityRef, Interpolate, ServerQuery,
 };
 
 use naia_bevy_shared::{WorldProxy, WorldRef};
@@ -55,6 +55,10 @@ impl<'a, P: Protocolize, C: ChannelIndex> Client<'a, P, C> {
         self.client.disconnect();
     }
 
+    pub fn query_server(&self, server_address: &str) -> ServerQuery<P> {
+        self.client.query_server(server_address)
+    }
+
     pub fn is_connected(&self) -> bool {
         self.client.is_connected()
     }
@@ -81,11 +85,38 @@ impl<'a, P: Protocolize, C: ChannelIndex> Client<'a, P, C> {
         self.client.interpolation()
     }
 
+    pub fn enable_interpolation<R: Interpolate<P>>(&mut self) {
+        self.client.enable_interpolation::<R>();
+    }
+
+    pub fn interpolated_component<R: Interpolate<P>>(&self, entity: &Entity) -> Option<R> {
+        self.client.interpolated_component::<R>(entity)
+    }
+
     //// Messages ////
     pub fn send_message<R: ReplicateSafe<P>>(&mut self, channel: C, message: &R) {
         self.client.send_message(channel, message)
     }
 
+    pub fn send_message_with_ttl<R: ReplicateSafe<P>>(
+        &mut self,
+        channel: C,
+        message: &R,
+        ttl: Duration,
+    ) {
+        self.client.send_message_with_ttl(channel, message, ttl)
+    }
+
+    pub fn send_message_until_tick<R: ReplicateSafe<P>>(
+        &mut self,
+        channel: C,
+        message: &R,
+        deadline_tick: Tick,
+    ) {
+        self.client
+            .send_message_until_tick(channel, message, deadline_tick)
+    }
+
     //// Entities ////
 
     pub fn entity(&self, entity: &Entity) -> EntityRef<P, Entity, WorldRef> {
@@ -96,6 +127,32 @@ impl<'a, P: Protocolize, C: ChannelIndex> Client<'a, P, C> {
         return self.client.entities(&self.world.proxy());
     }
 
+    pub fn entity_parent(&self, entity: &Entity) -> Option<Entity> {
+        self.client.entity_parent(entity)
+    }
+
+    pub fn entity_children(&self, entity: &Entity) -> Vec<Entity> {
+        self.client.entity_children(entity)
+    }
+
+    pub fn track_previous_state<R: Replicate<P>>(&mut self) {
+        self.client.track_previous_state::<R>();
+    }
+
+    //// Authority ////
+
+    pub fn request_authority(&mut self, entity: &Entity) {
+        self.client.request_authority(entity);
+    }
+
+    pub fn release_authority(&mut self, entity: &Entity) {
+        self.client.release_authority(entity);
+    }
+
+    pub fn has_authority(&self, entity: &Entity) -> bool {
+        self.client.has_authority(entity)
+    }
+
     //// Ticks ////
 
     pub fn client_tick(&self) -> Option<u16> {
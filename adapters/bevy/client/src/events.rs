@@ -1,10 +1,22 @@
 use bevy_ecs::entity::Entity;
 
-use naia_client::shared::{ChannelIndex, ProtocolKindType, Protocolize, Tick};
+use naia_client::{
+    shared::{ChannelIndex, ProtocolKindType, Protocolize, Tick},
+    ComponentChanges, ConnectionFailure,
+};
 
-pub struct SpawnEntityEvent(pub Entity);
+pub struct ConnectionFailedEvent(pub ConnectionFailure);
+pub struct SpawnEntityEvent<K: ProtocolKindType>(pub Entity, pub Vec<K>);
 pub struct DespawnEntityEvent(pub Entity);
 pub struct InsertComponentEvent<K: ProtocolKindType>(pub Entity, pub K);
-pub struct UpdateComponentEvent<K: ProtocolKindType>(pub Tick, pub Entity, pub K);
+pub struct UpdateComponentEvent<P: Protocolize>(
+    pub Tick,
+    pub Entity,
+    pub P::Kind,
+    pub ComponentChanges<P>,
+);
 pub struct RemoveComponentEvent<P: Protocolize>(pub Entity, pub P);
+pub struct AuthorityGrantedEvent(pub Entity);
+pub struct AuthorityRevokedEvent(pub Entity);
 pub struct MessageEvent<P: Protocolize, C: ChannelIndex>(pub C, pub P);
+pub struct MessageExpiredEvent<P: Protocolize, C: ChannelIndex>(pub C, pub P);
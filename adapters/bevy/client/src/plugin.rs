@@ -14,7 +14,8 @@ use crate::systems::{finish_reject, should_receive, should_reject};
 
 use super::{
     events::{
-        DespawnEntityEvent, InsertComponentEvent, MessageEvent, RemoveComponentEvent,
+        AuthorityGrantedEvent, AuthorityRevokedEvent, ConnectionFailedEvent, DespawnEntityEvent,
+        InsertComponentEvent, MessageEvent, MessageExpiredEvent, RemoveComponentEvent,
         SpawnEntityEvent, UpdateComponentEvent,
     },
     resource::ClientResource,
@@ -65,12 +66,16 @@ impl<P: Protocolize, C: ChannelIndex> PluginType for Plugin<P, C> {
             .init_resource::<ClientResource>()
             .init_resource::<WorldData<P>>()
             // EVENTS //
-            .add_event::<SpawnEntityEvent>()
+            .add_event::<ConnectionFailedEvent>()
+            .add_event::<SpawnEntityEvent<P::Kind>>()
             .add_event::<DespawnEntityEvent>()
             .add_event::<InsertComponentEvent<P::Kind>>()
-            .add_event::<UpdateComponentEvent<P::Kind>>()
+            .add_event::<UpdateComponentEvent<P>>()
             .add_event::<RemoveComponentEvent<P>>()
+            .add_event::<AuthorityGrantedEvent>()
+            .add_event::<AuthorityRevokedEvent>()
             .add_event::<MessageEvent<P, C>>()
+            .add_event::<MessageExpiredEvent<P, C>>()
             // STAGES //
             // events //
             .add_stage_before(
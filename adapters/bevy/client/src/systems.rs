@@ -14,8 +14,9 @@ use naia_client::{
 use naia_bevy_shared::WorldProxyMut;
 
 use crate::events::{
-    DespawnEntityEvent, InsertComponentEvent, MessageEvent, RemoveComponentEvent, SpawnEntityEvent,
-    UpdateComponentEvent,
+    AuthorityGrantedEvent, AuthorityRevokedEvent, ConnectionFailedEvent, DespawnEntityEvent,
+    InsertComponentEvent, MessageEvent, MessageExpiredEvent, RemoveComponentEvent,
+    SpawnEntityEvent, UpdateComponentEvent,
 };
 
 use super::resource::ClientResource;
@@ -26,8 +27,11 @@ pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World)
             let event_results = client.receive(world.proxy_mut());
 
             unsafe {
+                let mut connection_failed_event_writer = world
+                    .get_resource_unchecked_mut::<Events<ConnectionFailedEvent>>()
+                    .unwrap();
                 let mut spawn_entity_event_writer = world
-                    .get_resource_unchecked_mut::<Events<SpawnEntityEvent>>()
+                    .get_resource_unchecked_mut::<Events<SpawnEntityEvent<P::Kind>>>()
                     .unwrap();
                 let mut despawn_entity_event_writer = world
                     .get_resource_unchecked_mut::<Events<DespawnEntityEvent>>()
@@ -36,14 +40,23 @@ pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World)
                     .get_resource_unchecked_mut::<Events<InsertComponentEvent<P::Kind>>>()
                     .unwrap();
                 let mut update_component_event_writer = world
-                    .get_resource_unchecked_mut::<Events<UpdateComponentEvent<P::Kind>>>()
+                    .get_resource_unchecked_mut::<Events<UpdateComponentEvent<P>>>()
                     .unwrap();
                 let mut remove_component_event_writer = world
                     .get_resource_unchecked_mut::<Events<RemoveComponentEvent<P>>>()
                     .unwrap();
+                let mut authority_granted_event_writer = world
+                    .get_resource_unchecked_mut::<Events<AuthorityGrantedEvent>>()
+                    .unwrap();
+                let mut authority_revoked_event_writer = world
+                    .get_resource_unchecked_mut::<Events<AuthorityRevokedEvent>>()
+                    .unwrap();
                 let mut message_event_writer = world
                     .get_resource_unchecked_mut::<Events<MessageEvent<P, C>>>()
                     .unwrap();
+                let mut message_expired_event_writer = world
+                    .get_resource_unchecked_mut::<Events<MessageExpiredEvent<P, C>>>()
+                    .unwrap();
 
                 for event_result in event_results {
                     match event_result {
@@ -59,12 +72,16 @@ pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World)
                             client_resource.rejector.set();
                             continue;
                         }
+                        Ok(Event::ConnectionFailed(failure)) => {
+                            connection_failed_event_writer.send(ConnectionFailedEvent(failure));
+                        }
                         Ok(Event::Tick) => {
                             client_resource.ticker.set();
                             continue;
                         }
-                        Ok(Event::SpawnEntity(entity)) => {
-                            spawn_entity_event_writer.send(SpawnEntityEvent(entity));
+                        Ok(Event::SpawnEntity(entity, component_kinds)) => {
+                            spawn_entity_event_writer
+                                .send(SpawnEntityEvent(entity, component_kinds));
                         }
                         Ok(Event::DespawnEntity(entity)) => {
                             despawn_entity_event_writer.send(DespawnEntityEvent(entity));
@@ -77,12 +94,22 @@ pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World)
                             remove_component_event_writer
                                 .send(RemoveComponentEvent(entity, component));
                         }
+                        Ok(Event::AuthorityGranted(entity)) => {
+                            authority_granted_event_writer.send(AuthorityGrantedEvent(entity));
+                        }
+                        Ok(Event::AuthorityRevoked(entity)) => {
+                            authority_revoked_event_writer.send(AuthorityRevokedEvent(entity));
+                        }
                         Ok(Event::Message(channel, message)) => {
                             message_event_writer.send(MessageEvent(channel, message));
                         }
-                        Ok(Event::UpdateComponent(tick, entity, component)) => {
+                        Ok(Event::MessageExpired(channel, message)) => {
+                            message_expired_event_writer
+                                .send(MessageExpiredEvent(channel, message));
+                        }
+                        Ok(Event::UpdateComponent(tick, entity, component, changes)) => {
                             update_component_event_writer
-                                .send(UpdateComponentEvent(tick, entity, component));
+                                .send(UpdateComponentEvent(tick, entity, component, changes));
                         }
                         Err(_) => {}
                     }
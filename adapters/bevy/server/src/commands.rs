@@ -4,7 +4,7 @@ use bevy_ecs::entity::Entity;
 
 use naia_server::{
     shared::{ChannelIndex, Protocolize, Replicate, ReplicateSafe},
-    Server,
+    Server, UserKey,
 };
 
 use naia_bevy_shared::WorldMut;
@@ -84,3 +84,71 @@ impl<P: Protocolize, R: Replicate<P>, C: ChannelIndex> Command<P, C> for RemoveC
             .remove_component::<R>();
     }
 }
+
+//// Set Parent ////
+
+pub(crate) struct SetParent {
+    entity: Entity,
+    parent: Option<Entity>,
+}
+
+impl SetParent {
+    pub fn new(entity: &Entity, parent: Option<Entity>) -> Self {
+        SetParent {
+            entity: *entity,
+            parent,
+        }
+    }
+}
+
+impl<P: Protocolize, C: ChannelIndex> Command<P, C> for SetParent {
+    fn write(self: Box<Self>, server: &mut Server<P, Entity, C>, world: WorldMut) {
+        let mut entity_mut = server.entity_mut(world, &self.entity);
+        match self.parent {
+            Some(parent) => entity_mut.set_parent(&parent),
+            None => entity_mut.remove_parent(),
+        };
+    }
+}
+
+//// Give Authority ////
+
+pub(crate) struct GiveAuthority {
+    entity: Entity,
+    user_key: UserKey,
+}
+
+impl GiveAuthority {
+    pub fn new(entity: &Entity, user_key: &UserKey) -> Self {
+        GiveAuthority {
+            entity: *entity,
+            user_key: *user_key,
+        }
+    }
+}
+
+impl<P: Protocolize, C: ChannelIndex> Command<P, C> for GiveAuthority {
+    fn write(self: Box<Self>, server: &mut Server<P, Entity, C>, world: WorldMut) {
+        server
+            .entity_mut(world, &self.entity)
+            .give_authority(&self.user_key);
+    }
+}
+
+//// Take Authority ////
+
+pub(crate) struct TakeAuthority {
+    entity: Entity,
+}
+
+impl TakeAuthority {
+    pub fn new(entity: &Entity) -> Self {
+        TakeAuthority { entity: *entity }
+    }
+}
+
+impl<P: Protocolize, C: ChannelIndex> Command<P, C> for TakeAuthority {
+    fn write(self: Box<Self>, server: &mut Server<P, Entity, C>, world: WorldMut) {
+        server.entity_mut(world, &self.entity).take_authority();
+    }
+}
@@ -2,11 +2,13 @@ use bevy_ecs::entity::Entity;
 
 use naia_server::{
     shared::{ChannelIndex, Protocolize, Replicate, ReplicateSafe},
-    RoomKey,
+    RoomKey, UserKey,
 };
 
 use super::{
-    commands::{DespawnEntity, InsertComponent, RemoveComponent},
+    commands::{
+        DespawnEntity, GiveAuthority, InsertComponent, RemoveComponent, SetParent, TakeAuthority,
+    },
     server::Server,
 };
 
@@ -47,6 +49,33 @@ impl<'s, 'world, 'state, P: Protocolize, C: ChannelIndex> EntityMut<'s, 'world,
         self
     }
 
+    // Hierarchy
+
+    pub fn set_parent(&mut self, parent: &Entity) -> &mut Self {
+        self.server
+            .queue_command(SetParent::new(&self.entity, Some(*parent)));
+        self
+    }
+
+    pub fn remove_parent(&mut self) -> &mut Self {
+        self.server
+            .queue_command(SetParent::new(&self.entity, None));
+        self
+    }
+
+    // Authority
+
+    pub fn give_authority(&mut self, user_key: &UserKey) -> &mut Self {
+        self.server
+            .queue_command(GiveAuthority::new(&self.entity, user_key));
+        self
+    }
+
+    pub fn take_authority(&mut self) -> &mut Self {
+        self.server.queue_command(TakeAuthority::new(&self.entity));
+        self
+    }
+
     // Rooms
 
     pub fn enter_room(&mut self, room_key: &RoomKey) -> &mut Self {
@@ -1,5 +1,7 @@
+use bevy_ecs::entity::Entity;
+
 use naia_server::{
-    shared::{ChannelIndex, Protocolize},
+    shared::{ChannelIndex, ProtocolKindType, Protocolize},
     User, UserKey,
 };
 
@@ -7,3 +9,13 @@ pub struct AuthorizationEvent<P: Protocolize>(pub UserKey, pub P);
 pub struct ConnectionEvent(pub UserKey);
 pub struct DisconnectionEvent(pub UserKey, pub User);
 pub struct MessageEvent<P: Protocolize, C: ChannelIndex>(pub UserKey, pub C, pub P);
+pub struct MessageExpiredEvent<P: Protocolize, C: ChannelIndex>(pub UserKey, pub C, pub P);
+pub struct SpawnEntityEvent<K: ProtocolKindType>(pub UserKey, pub Entity, pub Vec<K>);
+pub struct DespawnEntityEvent(pub UserKey, pub Entity);
+pub struct InsertComponentEvent<K: ProtocolKindType>(pub UserKey, pub Entity, pub K);
+pub struct UpdateComponentEvent<K: ProtocolKindType>(pub UserKey, pub Entity, pub K);
+pub struct RemoveComponentEvent<P: Protocolize>(pub UserKey, pub Entity, pub P);
+pub struct AuthorityRequestedEvent(pub UserKey, pub Entity);
+pub struct AuthorityGrantedEvent(pub UserKey, pub Entity);
+pub struct AuthorityRevokedEvent(pub UserKey, pub Entity);
+pub struct AuthorityLostEvent(pub UserKey, pub Entity);
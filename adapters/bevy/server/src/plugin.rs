@@ -11,7 +11,12 @@ use naia_server::{
 use naia_bevy_shared::WorldData;
 
 use super::{
-    events::{AuthorizationEvent, ConnectionEvent, DisconnectionEvent, MessageEvent},
+    events::{
+        AuthorityGrantedEvent, AuthorityLostEvent, AuthorityRequestedEvent, AuthorityRevokedEvent,
+        AuthorizationEvent, ConnectionEvent, DespawnEntityEvent, DisconnectionEvent,
+        InsertComponentEvent, MessageEvent, MessageExpiredEvent, RemoveComponentEvent,
+        SpawnEntityEvent, UpdateComponentEvent,
+    },
     resource::ServerResource,
     stage::{PrivateStage, Stage},
     systems::{before_receive_events, finish_tick, should_receive, should_tick},
@@ -64,6 +69,16 @@ impl<P: Protocolize, C: ChannelIndex> PluginType for Plugin<P, C> {
             .add_event::<ConnectionEvent>()
             .add_event::<DisconnectionEvent>()
             .add_event::<MessageEvent<P, C>>()
+            .add_event::<MessageExpiredEvent<P, C>>()
+            .add_event::<SpawnEntityEvent<P::Kind>>()
+            .add_event::<DespawnEntityEvent>()
+            .add_event::<InsertComponentEvent<P::Kind>>()
+            .add_event::<UpdateComponentEvent<P::Kind>>()
+            .add_event::<RemoveComponentEvent<P>>()
+            .add_event::<AuthorityRequestedEvent>()
+            .add_event::<AuthorityGrantedEvent>()
+            .add_event::<AuthorityRevokedEvent>()
+            .add_event::<AuthorityLostEvent>()
             // STAGES //
             .add_stage_before(
                 CoreStage::PreUpdate,
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, marker::PhantomData};
+use std::{marker::PhantomData, time::Duration};
 
 use bevy_ecs::{
     entity::Entity,
@@ -7,9 +7,9 @@ use bevy_ecs::{
 };
 
 use naia_server::{
-    shared::{ChannelIndex, EntityHandleConverter, Protocolize, ReplicateSafe},
-    EntityRef, Event, NaiaServerError, RoomKey, RoomMut, RoomRef, Server as NaiaServer,
-    ServerAddrs, UserKey, UserMut, UserRef, UserScopeMut,
+    shared::{ChannelIndex, EntityHandleConverter, Protocolize, Replicate, ReplicateSafe, Tick},
+    EntityRef, RoomKey, RoomMut, RoomRef, Server as NaiaServer, ServerAddrs, UserKey, UserMut,
+    UserRef, UserScopeMut,
 };
 
 use crate::shared::EntityHandle;
@@ -44,10 +44,6 @@ impl<'world, 'state, P: Protocolize, C: ChannelIndex> Server<'world, 'state, P,
         }
     }
 
-    pub fn receive(&mut self) -> VecDeque<Result<Event<P, C>, NaiaServerError>> {
-        self.server.receive()
-    }
-
     //// Connections ////
 
     pub fn listen(&mut self, server_addrs: &ServerAddrs) {
@@ -62,6 +58,10 @@ impl<'world, 'state, P: Protocolize, C: ChannelIndex> Server<'world, 'state, P,
         self.server.reject_connection(user_key);
     }
 
+    pub fn set_server_info<R: ReplicateSafe<P>>(&mut self, info: R) {
+        self.server.set_server_info(info);
+    }
+
     //// Messages ////
     pub fn send_message<R: ReplicateSafe<P>>(
         &mut self,
@@ -72,12 +72,46 @@ impl<'world, 'state, P: Protocolize, C: ChannelIndex> Server<'world, 'state, P,
         self.server.send_message(user_key, channel, message)
     }
 
+    pub fn send_message_with_ttl<R: ReplicateSafe<P>>(
+        &mut self,
+        user_key: &UserKey,
+        channel: C,
+        message: &R,
+        ttl: Duration,
+    ) {
+        self.server
+            .send_message_with_ttl(user_key, channel, message, ttl)
+    }
+
+    pub fn send_message_until_tick<R: ReplicateSafe<P>>(
+        &mut self,
+        user_key: &UserKey,
+        channel: C,
+        message: &R,
+        deadline_tick: Tick,
+    ) {
+        self.server
+            .send_message_until_tick(user_key, channel, message, deadline_tick)
+    }
+
     //// Updates ////
 
-    pub fn scope_checks(&self) -> Vec<(RoomKey, UserKey, Entity)> {
+    pub fn scope_checks(&mut self) -> Vec<(RoomKey, UserKey, Entity)> {
         self.server.scope_checks()
     }
 
+    pub fn all_scope_checks(&self) -> Vec<(RoomKey, UserKey, Entity)> {
+        self.server.all_scope_checks()
+    }
+
+    pub fn mark_entity_scope_dirty(&mut self, entity: &Entity) {
+        self.server.mark_entity_scope_dirty(entity);
+    }
+
+    pub fn mark_user_scope_dirty(&mut self, user_key: &UserKey) {
+        self.server.mark_user_scope_dirty(user_key);
+    }
+
     pub fn send_all_updates(&mut self) {
         return self.server.send_all_updates(self.world.proxy());
     }
@@ -128,6 +162,44 @@ impl<'world, 'state, P: Protocolize, C: ChannelIndex> Server<'world, 'state, P,
         self.server.user_scope(user_key)
     }
 
+    pub fn set_entity_position(&mut self, entity: &Entity, x: f32, y: f32) {
+        self.server.set_entity_position(entity, x, y);
+    }
+
+    pub fn remove_entity_position(&mut self, entity: &Entity) {
+        self.server.remove_entity_position(entity);
+    }
+
+    pub fn set_user_view(&mut self, user_key: &UserKey, x: f32, y: f32, radius: f32) {
+        self.server.set_user_view(user_key, x, y, radius);
+    }
+
+    pub fn remove_user_view(&mut self, user_key: &UserKey) {
+        self.server.remove_user_view(user_key);
+    }
+
+    pub fn set_component_scope<R: Replicate<P>, F>(&mut self, predicate: F)
+    where
+        F: Fn(&UserKey, &Entity) -> bool + Send + Sync + 'static,
+    {
+        self.server.set_component_scope::<R, F>(predicate);
+    }
+
+    pub fn clear_component_scope<R: Replicate<P>>(&mut self) {
+        self.server.clear_component_scope::<R>();
+    }
+
+    pub fn set_property_scope<R: Replicate<P>, F>(&mut self, predicate: F)
+    where
+        F: Fn(&UserKey, &Entity) -> bool + Send + Sync + 'static,
+    {
+        self.server.set_property_scope::<R, F>(predicate);
+    }
+
+    pub fn clear_property_scope<R: Replicate<P>>(&mut self) {
+        self.server.clear_property_scope::<R>();
+    }
+
     //// Rooms ////
 
     pub fn make_room(&mut self) -> RoomMut<P, Entity, C> {
@@ -164,6 +236,35 @@ impl<'world, 'state, P: Protocolize, C: ChannelIndex> Server<'world, 'state, P,
         self.server.server_tick()
     }
 
+    //// Lag Compensation ////
+
+    pub fn enable_lag_compensation<R: Replicate<P>>(&mut self) {
+        self.server.enable_lag_compensation::<R>();
+    }
+
+    pub fn lag_compensated_tick(&self, user_key: &UserKey, client_tick: Tick) -> Option<Tick> {
+        self.server.lag_compensated_tick(user_key, client_tick)
+    }
+
+    pub fn lag_compensated_component<R: Replicate<P>>(
+        &self,
+        user_key: &UserKey,
+        client_tick: Tick,
+        entity: &Entity,
+    ) -> Option<R> {
+        self.server
+            .lag_compensated_component::<R>(user_key, client_tick, entity)
+    }
+
+    pub fn lag_compensated_components<R: Replicate<P>>(
+        &self,
+        user_key: &UserKey,
+        client_tick: Tick,
+    ) -> Vec<(Entity, R)> {
+        self.server
+            .lag_compensated_components::<R>(user_key, client_tick)
+    }
+
     // Crate-public methods
 
     pub(crate) fn queue_command<COMMAND: Command<P, C>>(&mut self, command: COMMAND) {
@@ -10,15 +10,22 @@ use naia_server::{
     Event, Server,
 };
 
+use naia_bevy_shared::WorldProxyMut;
+
 use super::{
-    events::{AuthorizationEvent, ConnectionEvent, DisconnectionEvent, MessageEvent},
+    events::{
+        AuthorityGrantedEvent, AuthorityLostEvent, AuthorityRequestedEvent, AuthorityRevokedEvent,
+        AuthorizationEvent, ConnectionEvent, DespawnEntityEvent, DisconnectionEvent,
+        InsertComponentEvent, MessageEvent, MessageExpiredEvent, RemoveComponentEvent,
+        SpawnEntityEvent, UpdateComponentEvent,
+    },
     resource::ServerResource,
 };
 
 pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World) {
     world.resource_scope(|world, mut server: Mut<Server<P, Entity, C>>| {
         world.resource_scope(|world, mut server_resource: Mut<ServerResource>| {
-            let events = server.receive();
+            let events = server.receive(world.proxy_mut());
             if events.is_empty() {
                 // In the future, may want to stall the system if we don't receive any events
                 // to keep from the system running empty and using up CPU.
@@ -36,6 +43,36 @@ pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World)
                     let mut message_event_writer = world
                         .get_resource_unchecked_mut::<Events<MessageEvent<P, C>>>()
                         .unwrap();
+                    let mut message_expired_event_writer = world
+                        .get_resource_unchecked_mut::<Events<MessageExpiredEvent<P, C>>>()
+                        .unwrap();
+                    let mut spawn_entity_event_writer = world
+                        .get_resource_unchecked_mut::<Events<SpawnEntityEvent<P::Kind>>>()
+                        .unwrap();
+                    let mut despawn_entity_event_writer = world
+                        .get_resource_unchecked_mut::<Events<DespawnEntityEvent>>()
+                        .unwrap();
+                    let mut insert_component_event_writer = world
+                        .get_resource_unchecked_mut::<Events<InsertComponentEvent<P::Kind>>>()
+                        .unwrap();
+                    let mut update_component_event_writer = world
+                        .get_resource_unchecked_mut::<Events<UpdateComponentEvent<P::Kind>>>()
+                        .unwrap();
+                    let mut remove_component_event_writer = world
+                        .get_resource_unchecked_mut::<Events<RemoveComponentEvent<P>>>()
+                        .unwrap();
+                    let mut authority_requested_event_writer = world
+                        .get_resource_unchecked_mut::<Events<AuthorityRequestedEvent>>()
+                        .unwrap();
+                    let mut authority_granted_event_writer = world
+                        .get_resource_unchecked_mut::<Events<AuthorityGrantedEvent>>()
+                        .unwrap();
+                    let mut authority_revoked_event_writer = world
+                        .get_resource_unchecked_mut::<Events<AuthorityRevokedEvent>>()
+                        .unwrap();
+                    let mut authority_lost_event_writer = world
+                        .get_resource_unchecked_mut::<Events<AuthorityLostEvent>>()
+                        .unwrap();
 
                     for event in events {
                         match event {
@@ -55,6 +92,55 @@ pub fn before_receive_events<P: Protocolize, C: ChannelIndex>(world: &mut World)
                             Ok(Event::Message(user_key, channel, message)) => {
                                 message_event_writer.send(MessageEvent(user_key, channel, message));
                             }
+                            Ok(Event::MessageExpired(user_key, channel, message)) => {
+                                message_expired_event_writer
+                                    .send(MessageExpiredEvent(user_key, channel, message));
+                            }
+                            Ok(Event::SpawnEntity(user_key, entity, component_kinds)) => {
+                                spawn_entity_event_writer.send(SpawnEntityEvent(
+                                    user_key,
+                                    entity,
+                                    component_kinds,
+                                ));
+                            }
+                            Ok(Event::DespawnEntity(user_key, entity)) => {
+                                despawn_entity_event_writer
+                                    .send(DespawnEntityEvent(user_key, entity));
+                            }
+                            Ok(Event::InsertComponent(user_key, entity, component_kind)) => {
+                                insert_component_event_writer.send(InsertComponentEvent(
+                                    user_key,
+                                    entity,
+                                    component_kind,
+                                ));
+                            }
+                            Ok(Event::UpdateComponent(user_key, entity, component_kind)) => {
+                                update_component_event_writer.send(UpdateComponentEvent(
+                                    user_key,
+                                    entity,
+                                    component_kind,
+                                ));
+                            }
+                            Ok(Event::RemoveComponent(user_key, entity, component)) => {
+                                remove_component_event_writer
+                                    .send(RemoveComponentEvent(user_key, entity, component));
+                            }
+                            Ok(Event::AuthorityRequested(user_key, entity)) => {
+                                authority_requested_event_writer
+                                    .send(AuthorityRequestedEvent(user_key, entity));
+                            }
+                            Ok(Event::AuthorityGranted(user_key, entity)) => {
+                                authority_granted_event_writer
+                                    .send(AuthorityGrantedEvent(user_key, entity));
+                            }
+                            Ok(Event::AuthorityRevoked(user_key, entity)) => {
+                                authority_revoked_event_writer
+                                    .send(AuthorityRevokedEvent(user_key, entity));
+                            }
+                            Ok(Event::AuthorityLost(user_key, entity)) => {
+                                authority_lost_event_writer
+                                    .send(AuthorityLostEvent(user_key, entity));
+                            }
                             Err(_) => {}
                         }
                     }
@@ -4,9 +4,9 @@ use bevy_ecs::{
 };
 
 use naia_shared::{
-    serde::SerdeErr, ComponentUpdate, NetEntityHandleConverter, ProtocolInserter, ProtocolKindType,
-    Protocolize, ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper, ReplicateSafe,
-    WorldMutType, WorldRefType,
+    serde::SerdeErr, ComponentUpdate, NetEntityHandleConverter, PropertyMutator, ProtocolInserter,
+    ProtocolKindType, Protocolize, ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper,
+    ReplicateSafe, WorldMutType, WorldRefType,
 };
 
 use super::{
@@ -209,6 +209,22 @@ impl<'w, P: Protocolize> WorldMutType<P, Entity> for WorldMut<'w> {
         Ok(())
     }
 
+    fn component_set_mutator(
+        &mut self,
+        entity: &Entity,
+        component_kind: &P::Kind,
+        mutator: &PropertyMutator,
+    ) {
+        self.world
+            .resource_scope(|world: &mut World, data: Mut<WorldData<P>>| {
+                if let Some(accessor) = data.component_access(component_kind) {
+                    if let Some(mut component) = accessor.component_mut(world, entity) {
+                        component.set_mutator(mutator);
+                    }
+                }
+            });
+    }
+
     fn mirror_entities(&mut self, new_entity: &Entity, old_entity: &Entity) {
         for component_kind in WorldMutType::<P, Entity>::component_kinds(self, old_entity) {
             WorldMutType::<P, Entity>::mirror_components(
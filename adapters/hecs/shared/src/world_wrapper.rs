@@ -6,9 +6,9 @@ use std::{
 use hecs::{Entity, World};
 
 use naia_shared::{
-    serde::SerdeErr, ComponentUpdate, NetEntityHandleConverter, ProtocolInserter, Protocolize,
-    ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper, Replicate, ReplicateSafe,
-    WorldMutType, WorldRefType,
+    serde::SerdeErr, ComponentUpdate, NetEntityHandleConverter, PropertyMutator, ProtocolInserter,
+    Protocolize, ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper, Replicate,
+    ReplicateSafe, WorldMutType, WorldRefType,
 };
 
 use crate::{
@@ -184,6 +184,19 @@ impl<P: Protocolize> WorldMutType<P, Entity> for &mut WorldWrapper<P> {
         Ok(())
     }
 
+    fn component_set_mutator(
+        &mut self,
+        entity: &Entity,
+        component_kind: &P::Kind,
+        mutator: &PropertyMutator,
+    ) {
+        if let Some(access) = self.data.component_access(component_kind) {
+            if let Some(mut component) = access.component_mut(&mut self.inner, entity) {
+                component.set_mutator(mutator);
+            }
+        }
+    }
+
     fn mirror_entities(&mut self, new_entity: &Entity, old_entity: &Entity) {
         for component_kind in WorldMutType::<P, Entity>::component_kinds(self, old_entity) {
             WorldMutType::<P, Entity>::mirror_components(
@@ -1,15 +1,22 @@
-use std::{collections::VecDeque, hash::Hash, marker::PhantomData, net::SocketAddr};
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+    net::SocketAddr,
+    time::Duration,
+};
 
 #[cfg(feature = "bevy_support")]
 use bevy_ecs::prelude::Resource;
 
 use naia_client_socket::Socket;
+use naia_shared::{MtuManager, PropertyMutator, RejectReason, Replicate};
 
 pub use naia_shared::{
     serde::{BitReader, BitWriter, Serde},
-    ChannelIndex, ConnectionConfig, EntityHandle, EntityHandleConverter, PacketType, PingConfig,
-    PingIndex, ProtocolKindType, Protocolize, ReplicateSafe, SharedConfig, SocketConfig,
-    StandardHeader, Tick, Timer, Timestamp, WorldMutType, WorldRefType,
+    wrapping_diff, ChannelIndex, ConnectionConfig, EntityHandle, EntityHandleConverter, Instant,
+    PacketType, PingConfig, PingIndex, ProtocolKindType, Protocolize, ReplicateSafe, SharedConfig,
+    SocketConfig, StandardHeader, Tick, Timer, Timestamp, WorldMutType, WorldRefType,
 };
 
 use crate::{
@@ -18,16 +25,22 @@ use crate::{
         handshake_manager::{HandshakeManager, HandshakeResult},
         io::Io,
     },
-    protocol::entity_ref::EntityRef,
+    protocol::entity_ref::{EntityMut, EntityRef},
+    server_query::ServerQuery,
     tick::tick_manager::TickManager,
 };
 
-use super::{client_config::ClientConfig, error::NaiaClientError, event::Event};
+use super::{
+    client_config::ClientConfig,
+    error::NaiaClientError,
+    event::{ConnectionFailure, Event},
+    interpolation::{interpolate::Interpolate, interpolation_buffer::InterpolationBuffer},
+};
 
 /// Client can send/receive messages to/from a server, and has a pool of
 /// in-scope entities/components that are synced with the server
 #[cfg_attr(feature = "bevy_support", derive(Resource))]
-pub struct Client<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> {
+pub struct Client<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> {
     // Config
     client_config: ClientConfig,
     shared_config: SharedConfig<C>,
@@ -37,16 +50,24 @@ pub struct Client<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> {
     handshake_manager: HandshakeManager<P>,
     // Events
     incoming_events: VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
+    // Components whose state before each update is given in its event
+    previous_state_kinds: HashSet<P::Kind>,
     // Ticks
     tick_manager: Option<TickManager>,
+    // Interpolation
+    interpolation_buffer: InterpolationBuffer<P, E>,
     // Phantom
     phantom_k: PhantomData<E>,
 }
 
-impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
+impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Client<P, E, C> {
     /// Create a new Client
     pub fn new(client_config: &ClientConfig, shared_config: &SharedConfig<C>) -> Self {
-        let handshake_manager = HandshakeManager::new(client_config.send_handshake_interval);
+        let handshake_manager = HandshakeManager::new(
+            client_config.send_handshake_interval,
+            client_config.connect_timeout,
+            client_config.max_connect_attempts,
+        );
 
         let tick_manager = shared_config
             .tick_interval
@@ -65,8 +86,11 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
             handshake_manager,
             // Events
             incoming_events: VecDeque::new(),
+            previous_state_kinds: HashSet::new(),
             // Ticks
             tick_manager,
+            // Interpolation
+            interpolation_buffer: InterpolationBuffer::new(&client_config.interpolation),
             // Phantom
             phantom_k: PhantomData,
         }
@@ -89,6 +113,23 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
             .load(socket.packet_sender(), socket.packet_receiver());
     }
 
+    /// Queries the Server at the given address for its info, without
+    /// connecting to it. The returned ServerQuery must be polled for the
+    /// result, and gives up after the configured connect timeout.
+    pub fn query_server(&self, server_session_url: &str) -> ServerQuery<P> {
+        let mut socket = Socket::new(&self.shared_config.socket);
+        socket.connect(server_session_url);
+
+        let mut io = Io::new(&None, &self.shared_config.compression);
+        io.load(socket.packet_sender(), socket.packet_receiver());
+
+        ServerQuery::new(
+            io,
+            self.client_config.send_handshake_interval,
+            self.client_config.connect_timeout,
+        )
+    }
+
     /// Returns whether or not the client is disconnected
     pub fn is_disconnected(&self) -> bool {
         !self.io.is_loaded()
@@ -147,16 +188,26 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
 
                     // apply updates on tick boundary
                     let receiving_tick = tick_manager.client_receiving_tick();
+                    let first_new_event = self.incoming_events.len();
                     server_connection.process_buffered_packets(
                         &mut world,
                         receiving_tick,
+                        &self.previous_state_kinds,
                         &mut self.incoming_events,
                     );
+
+                    // keep the received states of interpolated Components
+                    self.interpolation_buffer.record_events(
+                        &world,
+                        &self.incoming_events.make_contiguous()[first_new_event..],
+                        receiving_tick,
+                    );
                 }
             } else {
                 server_connection.process_buffered_packets(
                     &mut world,
                     0,
+                    &self.previous_state_kinds,
                     &mut self.incoming_events,
                 );
             }
@@ -168,15 +219,25 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
                     .push_back(Ok(Event::Message(channel, message)));
             }
 
+            // report messages which expired before being delivered
+            let expired_messages = server_connection
+                .base
+                .message_manager
+                .take_expired_messages();
+            for (channel, message) in expired_messages {
+                self.incoming_events
+                    .push_back(Ok(Event::MessageExpired(channel, message)));
+            }
+
             // send outgoing packets
-            server_connection.send_outgoing_packets(&mut self.io, &self.tick_manager);
+            server_connection.send_outgoing_packets(&mut self.io, &world, &self.tick_manager);
 
             // tick event
             if did_tick {
                 self.incoming_events.push_back(Ok(Event::Tick));
             }
-        } else {
-            self.handshake_manager.send(&mut self.io);
+        } else if let Some(failure) = self.handshake_manager.send(&mut self.io) {
+            self.connection_failed(failure);
         }
 
         std::mem::take(&mut self.incoming_events)
@@ -186,6 +247,45 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
 
     /// Queues up an Message to be sent to the Server
     pub fn send_message<R: ReplicateSafe<P>>(&mut self, channel: C, message: &R) {
+        self.send_message_inner(channel, message, None);
+    }
+
+    /// Queues up an Message to be sent to the Server, which will be dropped if
+    /// it has not been delivered within the given time-to-live. Only reliable
+    /// Channels will drop a Message, which is then reported back with an
+    /// `Event::MessageExpired`
+    pub fn send_message_with_ttl<R: ReplicateSafe<P>>(
+        &mut self,
+        channel: C,
+        message: &R,
+        ttl: Duration,
+    ) {
+        let mut expires_at = Instant::now();
+        expires_at.add_millis(ttl.as_millis() as u32);
+        self.send_message_inner(channel, message, Some(expires_at));
+    }
+
+    /// Queues up an Message to be sent to the Server, which will be dropped if
+    /// it has not been delivered by the given Client Tick. Only reliable
+    /// Channels will drop a Message, which is then reported back with an
+    /// `Event::MessageExpired`.
+    /// Panics if `SharedConfig::tick_interval` is None
+    pub fn send_message_until_tick<R: ReplicateSafe<P>>(
+        &mut self,
+        channel: C,
+        message: &R,
+        deadline_tick: Tick,
+    ) {
+        let expires_at = self.tick_to_instant(deadline_tick);
+        self.send_message_inner(channel, message, Some(expires_at));
+    }
+
+    fn send_message_inner<R: ReplicateSafe<P>>(
+        &mut self,
+        channel: C,
+        message: &R,
+        expires_at: Option<Instant>,
+    ) {
         let channel_settings = self.shared_config.channel.channel(&channel);
 
         if !channel_settings.can_send_to_server() {
@@ -208,7 +308,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
             connection
                 .base
                 .message_manager
-                .send_message(channel, message.protocol_copy());
+                .send_message_with_expiry_opt(channel, message.protocol_copy(), expires_at);
         }
     }
 
@@ -226,6 +326,181 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
         world.entities()
     }
 
+    /// Returns the parent of an Entity replicated from the Server, if it has
+    /// one
+    pub fn entity_parent(&self, entity: &E) -> Option<E> {
+        self.server_connection
+            .as_ref()
+            .and_then(|connection| connection.entity_manager.entity_parent(entity))
+    }
+
+    /// Returns the children of an Entity replicated from the Server
+    pub fn entity_children(&self, entity: &E) -> Vec<E> {
+        match &self.server_connection {
+            Some(connection) => connection.entity_manager.entity_children(entity),
+            None => Vec::new(),
+        }
+    }
+
+    /// Creates a new Entity owned by the Client, which is replicated to the
+    /// Server, and returns an EntityMut which can be used for further
+    /// operations on the Entity.
+    /// EntityProperties of Components on a Client-owned Entity may only refer
+    /// to other Client-owned Entities.
+    /// Panics if the Client is not connected.
+    pub fn spawn_entity<W: WorldMutType<P, E>>(&mut self, mut world: W) -> EntityMut<P, E, W, C> {
+        let entity = world.spawn_entity();
+
+        let connection = self.server_connection_mut();
+        connection.entity_manager.host_spawn_entity(&entity);
+        connection.host_world_manager.spawn_entity(&entity);
+
+        EntityMut::new(self, world, &entity)
+    }
+
+    /// Retrieves an EntityMut that exposes read and write operations for an
+    /// Entity owned by the Client.
+    /// Panics if the Entity was not spawned by the Client.
+    pub fn entity_mut<W: WorldMutType<P, E>>(
+        &mut self,
+        world: W,
+        entity: &E,
+    ) -> EntityMut<P, E, W, C> {
+        if world.has_entity(entity)
+            && self
+                .server_connection_mut()
+                .entity_manager
+                .is_host_entity(entity)
+        {
+            return EntityMut::new(self, world, entity);
+        }
+        panic!("No Entity owned by the Client exists for given Key!");
+    }
+
+    /// Includes the state of Components of the given type from before each
+    /// update in their `Event::UpdateComponent`, so that changes can be
+    /// reacted to without keeping a copy of every Component
+    pub fn track_previous_state<R: Replicate<P>>(&mut self) {
+        self.previous_state_kinds.insert(P::kind_of::<R>());
+    }
+
+    // Authority
+
+    /// Asks the Server for authority over one of its Entities. If the Server
+    /// gives it, an `Event::AuthorityGranted` is emitted, after which changes
+    /// to the Entity's Properties are replicated to the Server.
+    /// Panics if the Client is not connected.
+    pub fn request_authority(&mut self, entity: &E) {
+        let connection = self.server_connection_mut();
+        connection
+            .authority_manager
+            .request_authority(&connection.entity_manager, entity);
+    }
+
+    /// Hands authority over an Entity back to the Server. Changes to the
+    /// Entity's Properties will no longer be replicated to the Server.
+    /// Panics if the Client is not connected.
+    pub fn release_authority(&mut self, entity: &E) {
+        let connection = self.server_connection_mut();
+        connection
+            .authority_manager
+            .release_authority(&connection.entity_manager, entity);
+    }
+
+    /// Returns whether or not the Client has authority over the given Entity
+    pub fn has_authority(&self, entity: &E) -> bool {
+        match &self.server_connection {
+            Some(connection) => connection.authority_manager.has_authority(entity),
+            None => false,
+        }
+    }
+
+    // Crate-Public methods
+
+    //// Entities
+
+    pub(crate) fn despawn_entity<W: WorldMutType<P, E>>(&mut self, world: &mut W, entity: &E) {
+        if !world.has_entity(entity) {
+            panic!("attempted to de-spawn nonexistent entity");
+        }
+
+        let connection = self.server_connection_mut();
+        connection.host_world_manager.despawn_entity(entity);
+        {
+            let mut diff_handler = connection
+                .diff_handler
+                .as_ref()
+                .write()
+                .expect("DiffHandler should be initialized");
+            for component_kind in world.component_kinds(entity) {
+                diff_handler.deregister_component(entity, &component_kind);
+            }
+        }
+        connection.entity_manager.host_despawn_entity(entity);
+
+        world.despawn_entity(entity);
+    }
+
+    //// Components
+
+    pub(crate) fn insert_component<R: ReplicateSafe<P>, W: WorldMutType<P, E>>(
+        &mut self,
+        world: &mut W,
+        entity: &E,
+        mut component_ref: R,
+    ) {
+        if !world.has_entity(entity) {
+            panic!("attempted to add component to non-existent entity");
+        }
+
+        let component_kind = component_ref.kind();
+
+        if world.has_component_of_kind(entity, &component_kind) {
+            panic!(
+                "attempted to add component to entity which already has one of that type! \
+                   an entity is not allowed to have more than 1 type of component at a time."
+            )
+        }
+
+        let connection = self.server_connection_mut();
+
+        let mut_sender = connection
+            .diff_handler
+            .as_ref()
+            .write()
+            .expect("DiffHandler should be initialized")
+            .register_component(entity, &component_kind, component_ref.diff_mask_size());
+        component_ref.set_mutator(&PropertyMutator::new(mut_sender));
+
+        // actually insert component into world
+        world.insert_component(entity, component_ref);
+
+        connection
+            .host_world_manager
+            .insert_component(entity, &component_kind);
+    }
+
+    pub(crate) fn remove_component<R: Replicate<P>, W: WorldMutType<P, E>>(
+        &mut self,
+        world: &mut W,
+        entity: &E,
+    ) -> Option<R> {
+        let component_kind = P::kind_of::<R>();
+
+        let connection = self.server_connection_mut();
+        connection
+            .host_world_manager
+            .remove_component(entity, &component_kind);
+        connection
+            .diff_handler
+            .as_ref()
+            .write()
+            .expect("DiffHandler should be initialized")
+            .deregister_component(entity, &component_kind);
+
+        world.remove_component::<R>(entity)
+    }
+
     // Connection
 
     /// Get the address currently associated with the Server
@@ -268,6 +543,26 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
             .map(|tick_manager| tick_manager.interpolation())
     }
 
+    /// Keeps the states of the given Component received from the Server, so
+    /// that they can be rendered in between with `interpolated_component()`.
+    /// Requires a tick interval to be set in the SharedConfig
+    pub fn enable_interpolation<R: Interpolate<P>>(&mut self) {
+        self.interpolation_buffer.enable::<R>();
+    }
+
+    /// Gets the state of an Entity's Component as of the configured delay
+    /// behind the Server, interpolated from the states received. Returns None
+    /// if interpolation is not enabled for the Component or no state has been
+    /// received yet
+    pub fn interpolated_component<R: Interpolate<P>>(&self, entity: &E) -> Option<R> {
+        let tick_manager = self.tick_manager.as_ref()?;
+        self.interpolation_buffer.interpolated::<R>(
+            entity,
+            tick_manager.client_receiving_tick(),
+            tick_manager.interpolation(),
+        )
+    }
+
     // Bandwidth monitoring
     pub fn outgoing_bandwidth(&mut self) -> f32 {
         self.io.outgoing_bandwidth()
@@ -277,6 +572,24 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
         self.io.incoming_bandwidth()
     }
 
+    /// Returns the dictionary trained with `CompressionMode::Training` on
+    /// outgoing packets, once enough samples have been collected
+    pub fn take_trained_dictionary(&mut self) -> Option<Vec<u8>> {
+        self.io.take_trained_dictionary()
+    }
+
+    /// Ratio of compressed to uncompressed bytes sent to the Server, over the
+    /// configured bandwidth measure duration
+    pub fn outgoing_compression_ratio(&mut self) -> f32 {
+        self.io.outgoing_compression_ratio()
+    }
+
+    /// Ratio of compressed to uncompressed bytes received from the Server,
+    /// over the configured bandwidth measure duration
+    pub fn incoming_compression_ratio(&mut self) -> f32 {
+        self.io.incoming_compression_ratio()
+    }
+
     // internal functions
 
     fn maintain_socket(&mut self) {
@@ -325,6 +638,28 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
                 server_connection.base.mark_sent();
             }
 
+            // send path MTU probes
+            if let Some(probe_size) = server_connection.mtu_manager.next_probe_size() {
+                let mut writer = BitWriter::with_max_size(probe_size as usize);
+
+                // write header
+                server_connection
+                    .base
+                    .write_outgoing_header(PacketType::MtuProbe, &mut writer);
+
+                // write client tick
+                if let Some(tick_manager) = self.tick_manager.as_mut() {
+                    tick_manager.write_client_tick(&mut writer);
+                }
+
+                // write body
+                MtuManager::write_probe(probe_size, &mut writer);
+
+                // send packet
+                self.io.send_writer(&mut writer);
+                server_connection.base.mark_sent();
+            }
+
             // receive from socket
             loop {
                 match self.io.recv_reader() {
@@ -334,11 +669,27 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
                         let header = StandardHeader::de(&mut reader)
                             .expect("unable to parse header from incoming packet");
 
+                        // Server has a new compression dictionary
+                        if header.packet_type == PacketType::ServerDictionaryChunk {
+                            if let Some(dictionary_opt) =
+                                self.handshake_manager.recv_dictionary_chunk(&mut reader)
+                            {
+                                if let Some(dictionary) = dictionary_opt {
+                                    self.io.set_dictionary(&dictionary);
+                                }
+                                let mut writer = self.handshake_manager.write_dictionary_request();
+                                self.io.send_writer(&mut writer);
+                            }
+                            continue;
+                        }
+
                         match header.packet_type {
                             PacketType::Data
                             | PacketType::Heartbeat
                             | PacketType::Ping
-                            | PacketType::Pong => {
+                            | PacketType::Pong
+                            | PacketType::MtuProbe
+                            | PacketType::MtuProbeAck => {
                                 // continue, these packet types are allowed when
                                 // connection is established
                             }
@@ -399,6 +750,38 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
                             PacketType::Pong => {
                                 server_connection.ping_manager.process_pong(&mut reader);
                             }
+                            PacketType::MtuProbe => {
+                                // read incoming probe size
+                                let probe_size_result = MtuManager::read_probe(&mut reader);
+                                if probe_size_result.is_err() {
+                                    // Received a malformed packet
+                                    continue;
+                                }
+                                let probe_size = probe_size_result.unwrap();
+
+                                // write probe ack payload
+                                let mut writer = BitWriter::new();
+
+                                // write header
+                                server_connection
+                                    .base
+                                    .write_outgoing_header(PacketType::MtuProbeAck, &mut writer);
+
+                                // write client tick
+                                if let Some(tick_manager) = self.tick_manager.as_ref() {
+                                    tick_manager.write_client_tick(&mut writer);
+                                }
+
+                                // write size
+                                probe_size.ser(&mut writer);
+
+                                // send packet
+                                self.io.send_writer(&mut writer);
+                                server_connection.base.mark_sent();
+                            }
+                            PacketType::MtuProbeAck => {
+                                server_connection.mtu_manager.process_probe_ack(&mut reader);
+                            }
                             _ => {
                                 // no other packet types matter when connection
                                 // is established
@@ -430,18 +813,40 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
                                         &self.client_config.connection,
                                         &self.shared_config.channel,
                                         &self.shared_config.tick_interval,
+                                        &self.shared_config.mtu,
+                                        self.shared_config.delta_compression,
                                     ));
                                     self.incoming_events
                                         .push_back(Ok(Event::Connection(server_addr)));
                                 }
-                                Some(HandshakeResult::Rejected) => {
+                                Some(HandshakeResult::Rejected(reason)) => {
                                     let server_addr = self.server_address_unwrapped();
-                                    self.incoming_events.clear();
-                                    self.incoming_events
-                                        .push_back(Ok(Event::Rejection(server_addr)));
-                                    self.disconnect_cleanup();
+                                    match reason {
+                                        RejectReason::Auth => {
+                                            self.connection_failed(ConnectionFailure::Rejected);
+                                            self.incoming_events
+                                                .push_front(Ok(Event::Rejection(server_addr)));
+                                        }
+                                        RejectReason::VersionMismatch => {
+                                            self.connection_failed(
+                                                ConnectionFailure::VersionMismatch,
+                                            );
+                                        }
+                                        RejectReason::ServerFull => {
+                                            self.connection_failed(ConnectionFailure::ServerFull);
+                                        }
+                                    }
                                     return;
                                 }
+                                Some(HandshakeResult::DictionaryChunk(dictionary_opt)) => {
+                                    if let Some(dictionary) = dictionary_opt {
+                                        self.io.set_dictionary(&dictionary);
+                                    }
+                                    // request the next chunk, or confirm we have them all
+                                    let mut writer =
+                                        self.handshake_manager.write_dictionary_request();
+                                    self.io.send_writer(&mut writer);
+                                }
                                 None => {}
                             }
                         }
@@ -449,8 +854,10 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
                             break;
                         }
                         Err(error) => {
-                            self.incoming_events
-                                .push_back(Err(NaiaClientError::Wrapped(Box::new(error))));
+                            self.connection_failed(ConnectionFailure::TransportError(
+                                error.to_string(),
+                            ));
+                            return;
                         }
                     }
                 }
@@ -458,6 +865,14 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
         }
     }
 
+    fn connection_failed(&mut self, failure: ConnectionFailure) {
+        self.disconnect_cleanup();
+
+        self.incoming_events.clear();
+        self.incoming_events
+            .push_back(Ok(Event::ConnectionFailed(failure)));
+    }
+
     fn disconnect_internal(&mut self) {
         let server_addr = self.server_address_unwrapped();
         self.disconnect_cleanup();
@@ -487,8 +902,33 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
             &self.shared_config.compression,
         );
         self.server_connection = None;
-        self.handshake_manager = HandshakeManager::new(self.client_config.send_handshake_interval);
+        self.handshake_manager = HandshakeManager::new(
+            self.client_config.send_handshake_interval,
+            self.client_config.connect_timeout,
+            self.client_config.max_connect_attempts,
+        );
         self.tick_manager = tick_manager;
+        self.interpolation_buffer.clear();
+    }
+
+    fn tick_to_instant(&self, tick: Tick) -> Instant {
+        let tick_manager = self
+            .tick_manager
+            .as_ref()
+            .expect("Client must be configured with a tick interval to use Tick deadlines");
+        let tick_interval = self.shared_config.tick_interval.unwrap();
+
+        let ticks_remaining = wrapping_diff(tick_manager.client_sending_tick(), tick).max(0) as u32;
+
+        let mut instant = Instant::now();
+        instant.add_millis(ticks_remaining * tick_interval.as_millis() as u32);
+        instant
+    }
+
+    fn server_connection_mut(&mut self) -> &mut Connection<P, E, C> {
+        self.server_connection
+            .as_mut()
+            .expect("Client must be connected to the Server to replicate Entities")
     }
 
     fn server_address_unwrapped(&self) -> SocketAddr {
@@ -497,7 +937,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Client<P, E, C> {
     }
 }
 
-impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> EntityHandleConverter<E>
+impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityHandleConverter<E>
     for Client<P, E, C>
 {
     fn handle_to_entity(&self, entity_handle: &EntityHandle) -> E {
@@ -2,6 +2,8 @@ use std::{default::Default, time::Duration};
 
 use naia_shared::ConnectionConfig;
 
+use crate::interpolation::interpolation_config::InterpolationConfig;
+
 /// Contains Config properties which will be used by a Server or Client
 #[derive(Clone)]
 pub struct ClientConfig {
@@ -9,6 +11,13 @@ pub struct ClientConfig {
     pub connection: ConnectionConfig,
     /// The duration between the resend of certain connection handshake messages
     pub send_handshake_interval: Duration,
+    /// The maximum duration to spend establishing a connection with the
+    /// Server before giving up. If None, the Client will keep trying
+    /// indefinitely
+    pub connect_timeout: Option<Duration>,
+    /// The maximum number of times a handshake message is sent to the Server
+    /// before giving up. If None, there is no limit
+    pub max_connect_attempts: Option<u32>,
     /// The minimum of measured latency to the Server that the Client use to
     /// ensure packets arrive in time. Should be fine if this is 0,
     /// but you'll increase the chance that packets always arrive to be
@@ -16,6 +25,9 @@ pub struct ClientConfig {
     /// helpful early on in the connection, when estimates of latency are
     /// less accurate.
     pub minimum_latency: Option<Duration>,
+    /// Used to configure how far behind the Server the states of
+    /// interpolated Components are rendered
+    pub interpolation: InterpolationConfig,
 }
 
 impl Default for ClientConfig {
@@ -23,7 +35,10 @@ impl Default for ClientConfig {
         Self {
             connection: ConnectionConfig::default(),
             send_handshake_interval: Duration::from_millis(250),
+            connect_timeout: Some(Duration::from_secs(10)),
+            max_connect_attempts: None,
             minimum_latency: None,
+            interpolation: InterpolationConfig::default(),
         }
     }
 }
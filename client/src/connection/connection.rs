@@ -1,15 +1,23 @@
-use std::{collections::VecDeque, hash::Hash, net::SocketAddr, time::Duration};
+use std::{
+    collections::{HashSet, VecDeque},
+    hash::Hash,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use naia_shared::{
-    serde::{BitReader, BitWriter, OwnedBitReader},
-    BaseConnection, ChannelConfig, ChannelIndex, ConnectionConfig, HostType, Instant, PacketType,
-    PingManager, ProtocolIo, Protocolize, StandardHeader, Tick, WorldMutType,
+    serde::{BitReader, OwnedBitReader},
+    BaseConnection, ChannelConfig, ChannelIndex, ConnectionConfig, EntityConverter,
+    GlobalDiffHandler, HostType, HostWorldManager, Instant, MtuConfig, MtuManager,
+    PacketNotifiable, PacketType, PingManager, ProtocolIo, Protocolize, StandardHeader, Tick,
+    WorldMutType, WorldRefType,
 };
 
 use crate::{
     error::NaiaClientError,
     event::Event,
-    protocol::entity_manager::EntityManager,
+    protocol::{authority_manager::AuthorityManager, entity_manager::EntityManager},
     tick::{
         tick_buffer_sender::TickBufferSender, tick_manager::TickManager, tick_queue::TickQueue,
     },
@@ -17,29 +25,41 @@ use crate::{
 
 use super::io::Io;
 
-pub struct Connection<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> {
+pub struct Connection<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> {
     pub base: BaseConnection<P, C>,
     pub entity_manager: EntityManager<P, E>,
+    pub host_world_manager: HostWorldManager<P, E, C>,
+    pub authority_manager: AuthorityManager<P, E>,
+    pub diff_handler: Arc<RwLock<GlobalDiffHandler<E, P::Kind>>>,
     pub ping_manager: PingManager,
+    pub mtu_manager: MtuManager,
     pub tick_buffer: Option<TickBufferSender<P, C>>,
     jitter_buffer: TickQueue<OwnedBitReader>,
 }
 
-impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
+impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connection<P, E, C> {
     pub fn new(
         address: SocketAddr,
         connection_config: &ConnectionConfig,
         channel_config: &ChannelConfig<C>,
         tick_duration: &Option<Duration>,
+        mtu_config: &MtuConfig,
+        delta_compression: bool,
     ) -> Self {
         let tick_buffer = tick_duration
             .as_ref()
             .map(|duration| TickBufferSender::new(channel_config, duration));
 
+        let diff_handler = Arc::new(RwLock::new(GlobalDiffHandler::default()));
+
         Connection {
             base: BaseConnection::new(address, HostType::Client, connection_config, channel_config),
-            entity_manager: EntityManager::default(),
+            entity_manager: EntityManager::new(delta_compression),
+            host_world_manager: HostWorldManager::new(address, &diff_handler, false),
+            authority_manager: AuthorityManager::new(address, &diff_handler),
+            diff_handler,
             ping_manager: PingManager::new(&connection_config.ping),
+            mtu_manager: MtuManager::new(mtu_config),
             tick_buffer,
             jitter_buffer: TickQueue::new(),
         }
@@ -48,12 +68,13 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
     // Incoming data
 
     pub fn process_incoming_header(&mut self, header: &StandardHeader) {
-        match &mut self.tick_buffer {
-            Some(tick_buffer) => self
-                .base
-                .process_incoming_header(header, &mut Some(tick_buffer)),
-            None => self.base.process_incoming_header(header, &mut None),
+        let mut packet_notifiables: Vec<&mut dyn PacketNotifiable> =
+            vec![&mut self.host_world_manager, &mut self.authority_manager];
+        if let Some(tick_buffer) = &mut self.tick_buffer {
+            packet_notifiables.push(tick_buffer);
         }
+        self.base
+            .process_incoming_header(header, &mut packet_notifiables);
     }
 
     pub fn buffer_data_packet(&mut self, incoming_tick: Tick, reader: &mut BitReader) {
@@ -65,12 +86,15 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
         &mut self,
         world: &mut W,
         receiving_tick: Tick,
+        previous_state_kinds: &HashSet<P::Kind>,
         incoming_events: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
     ) {
         while let Some((server_tick, owned_reader)) = self.jitter_buffer.pop_item(receiving_tick) {
             let mut reader = owned_reader.borrow();
 
-            let channel_reader = ProtocolIo::new(&self.entity_manager);
+            let converter = EntityConverter::new(&self.entity_manager, &self.host_world_manager)
+                .with_remote(&self.entity_manager);
+            let channel_reader = ProtocolIo::new(&converter);
 
             // Read Messages
             let messages_result = self
@@ -83,24 +107,56 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
             }
 
             // Read Entity Actions
-            let actions_result =
-                self.entity_manager
-                    .read_all(world, server_tick, &mut reader, incoming_events);
+            let events_before = incoming_events.len();
+            let actions_result = self.entity_manager.read_all(
+                world,
+                server_tick,
+                previous_state_kinds,
+                &mut reader,
+                incoming_events,
+            );
             if actions_result.is_err() {
                 // TODO: Except for cosmic radiation .. Server should never send a malformed packet .. handle this
                 continue;
             }
+
+            // Updates from the Server to Entities the Client has authority over
+            // should not be sent back
+            for event in incoming_events.iter().skip(events_before) {
+                if let Ok(Event::UpdateComponent(_, entity, component_kind, changes)) = event {
+                    self.authority_manager.clear_diff_mask_bits(
+                        entity,
+                        component_kind,
+                        &changes.changed_properties,
+                    );
+                }
+            }
+
+            // Read Authority Actions
+            if self.authority_manager.read_actions(&mut reader).is_err() {
+                // TODO: Except for cosmic radiation .. Server should never send a malformed packet .. handle this
+                continue;
+            }
+            self.authority_manager
+                .process_actions(world, &self.entity_manager, incoming_events);
         }
     }
 
     // Outgoing data
 
-    pub fn send_outgoing_packets(&mut self, io: &mut Io, tick_manager_opt: &Option<TickManager>) {
-        self.collect_outgoing_messages(tick_manager_opt);
+    pub fn send_outgoing_packets<W: WorldRefType<P, E>>(
+        &mut self,
+        io: &mut Io,
+        world: &W,
+        tick_manager_opt: &Option<TickManager>,
+    ) {
+        let now = Instant::now();
+
+        self.collect_outgoing_messages(&now, tick_manager_opt);
 
         let mut any_sent = false;
         loop {
-            if self.send_outgoing_packet(io, tick_manager_opt) {
+            if self.send_outgoing_packet(&now, io, world, tick_manager_opt) {
                 any_sent = true;
             } else {
                 break;
@@ -111,12 +167,17 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
         }
     }
 
-    fn collect_outgoing_messages(&mut self, tick_manager_opt: &Option<TickManager>) {
-        let now = Instant::now();
-
+    fn collect_outgoing_messages(&mut self, now: &Instant, tick_manager_opt: &Option<TickManager>) {
+        self.host_world_manager.collect_outgoing_messages(
+            now,
+            &self.ping_manager.rtt,
+            &mut self.base.message_manager,
+        );
         self.base
             .message_manager
-            .collect_outgoing_messages(&now, &self.ping_manager.rtt);
+            .collect_outgoing_messages(now, &self.ping_manager.rtt);
+        self.authority_manager
+            .collect_outgoing_actions(now, &self.ping_manager.rtt);
 
         if let Some(tick_manager) = tick_manager_opt {
             self.tick_buffer
@@ -130,9 +191,11 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
     }
 
     // Sends packet and returns whether or not a packet was sent
-    fn send_outgoing_packet(
+    fn send_outgoing_packet<W: WorldRefType<P, E>>(
         &mut self,
+        now: &Instant,
         io: &mut Io,
+        world: &W,
         tick_manager_opt: &Option<TickManager>,
     ) -> bool {
         let tick_buffer_has_outgoing_messages = match &self.tick_buffer {
@@ -140,16 +203,22 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
             None => false,
         };
 
-        if self.base.message_manager.has_outgoing_messages() || tick_buffer_has_outgoing_messages {
+        if self.base.message_manager.has_outgoing_messages()
+            || tick_buffer_has_outgoing_messages
+            || self.host_world_manager.has_outgoing_messages()
+            || self.authority_manager.has_outgoing_actions()
+        {
             let next_packet_index = self.base.next_packet_index();
 
-            let mut bit_writer = BitWriter::new();
+            let mut bit_writer = self.mtu_manager.packet_writer();
 
             // write header
             self.base
                 .write_outgoing_header(PacketType::Data, &mut bit_writer);
 
-            let channel_writer = ProtocolIo::new(&self.entity_manager);
+            let converter = EntityConverter::new(&self.entity_manager, &self.host_world_manager)
+                .with_remote(&self.entity_manager);
+            let channel_writer = ProtocolIo::new(&converter);
 
             if let Some(tick_manager) = tick_manager_opt {
                 // write tick
@@ -172,6 +241,26 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Connection<P, E, C> {
                 next_packet_index,
             );
 
+            // write entity actions
+            self.host_world_manager.write_all(
+                now,
+                &mut bit_writer,
+                &next_packet_index,
+                world,
+                &self.entity_manager,
+                &self.entity_manager,
+            );
+
+            // write authority actions, and updates to Entities the Client has
+            // authority over
+            self.authority_manager.write_all(
+                now,
+                &mut bit_writer,
+                &next_packet_index,
+                world,
+                &self.entity_manager,
+            );
+
             // send packet
             io.send_writer(&mut bit_writer);
 
@@ -1,14 +1,17 @@
 use std::time::Duration;
 
 use naia_shared::{
-    serde::{BitReader, BitWriter, Serde},
-    FakeEntityConverter,
+    serde::{BitReader, BitWrite, BitWriter, Serde},
+    CompressionDictionary, DictionaryReceiver, DictionaryVersion, FakeEntityConverter, Instant,
+    Random, RejectReason, DICTIONARY_REQUEST_BYTES, PROTOCOL_VERSION,
 };
 pub use naia_shared::{
     ConnectionConfig, PacketType, ProtocolKindType, Protocolize, ReplicateSafe, SharedConfig,
     StandardHeader, Timer, Timestamp as stamp_time, WorldMutType, WorldRefType,
 };
 
+use crate::event::ConnectionFailure;
+
 use super::io::Io;
 
 pub type Timestamp = u64;
@@ -16,25 +19,39 @@ pub type Timestamp = u64;
 #[derive(Debug, Eq, PartialEq)]
 pub enum HandshakeState {
     AwaitingChallengeResponse,
+    AwaitingDictionary,
     AwaitingConnectResponse,
     Connected,
 }
 
 pub enum HandshakeResult {
     Connected,
-    Rejected,
+    Rejected(RejectReason),
+    // A chunk of a compression dictionary was received, along with the whole
+    // dictionary if it is now complete
+    DictionaryChunk(Option<CompressionDictionary>),
 }
 
 pub struct HandshakeManager<P: Protocolize> {
     handshake_timer: Timer,
+    connect_timeout: Option<Duration>,
+    max_connect_attempts: Option<u32>,
+    connect_attempts: u32,
+    // when the first handshake message was sent
+    connect_start: Option<Instant>,
     pre_connection_timestamp: Timestamp,
     pre_connection_digest: Option<Vec<u8>>,
     pub connection_state: HandshakeState,
     auth_message: Option<P>,
+    dictionary_receiver: DictionaryReceiver,
 }
 
 impl<P: Protocolize> HandshakeManager<P> {
-    pub fn new(send_interval: Duration) -> Self {
+    pub fn new(
+        send_interval: Duration,
+        connect_timeout: Option<Duration>,
+        max_connect_attempts: Option<u32>,
+    ) -> Self {
         let mut handshake_timer = Timer::new(send_interval);
         handshake_timer.ring_manual();
 
@@ -42,10 +59,15 @@ impl<P: Protocolize> HandshakeManager<P> {
 
         Self {
             handshake_timer,
+            connect_timeout,
+            max_connect_attempts,
+            connect_attempts: 0,
+            connect_start: None,
             pre_connection_timestamp,
             pre_connection_digest: None,
             connection_state: HandshakeState::AwaitingChallengeResponse,
             auth_message: None,
+            dictionary_receiver: DictionaryReceiver::default(),
         }
     }
 
@@ -57,15 +79,32 @@ impl<P: Protocolize> HandshakeManager<P> {
         self.connection_state == HandshakeState::Connected
     }
 
-    // Give handshake manager the opportunity to send out messages to the server
-    pub fn send(&mut self, io: &mut Io) {
+    // Give handshake manager the opportunity to send out messages to the server.
+    // Returns the cause of failure once the connection attempt is given up on
+    pub fn send(&mut self, io: &mut Io) -> Option<ConnectionFailure> {
         if io.is_loaded() {
+            let connect_start = self.connect_start.get_or_insert_with(Instant::now);
+            if let Some(connect_timeout) = self.connect_timeout {
+                if connect_start.elapsed() > connect_timeout {
+                    return Some(ConnectionFailure::Timeout);
+                }
+            }
+
             if !self.handshake_timer.ringing() {
-                return;
+                return None;
             }
 
             self.handshake_timer.reset();
 
+            if self.connection_state != HandshakeState::Connected {
+                if let Some(max_connect_attempts) = self.max_connect_attempts {
+                    if self.connect_attempts >= max_connect_attempts {
+                        return Some(ConnectionFailure::Timeout);
+                    }
+                }
+                self.connect_attempts += 1;
+            }
+
             match self.connection_state {
                 HandshakeState::Connected => {
                     // do nothing, not necessary
@@ -74,12 +113,18 @@ impl<P: Protocolize> HandshakeManager<P> {
                     let mut writer = self.write_challenge_request();
                     io.send_writer(&mut writer);
                 }
+                HandshakeState::AwaitingDictionary => {
+                    let mut writer = self.write_dictionary_request();
+                    io.send_writer(&mut writer);
+                }
                 HandshakeState::AwaitingConnectResponse => {
                     let mut writer = self.write_connect_request();
                     io.send_writer(&mut writer);
                 }
             }
         }
+
+        None
     }
 
     // Call this regularly so handshake manager can process incoming requests
@@ -94,8 +139,13 @@ impl<P: Protocolize> HandshakeManager<P> {
                 self.recv_challenge_response(reader);
                 None
             }
+            PacketType::ServerDictionaryChunk => self
+                .recv_dictionary_chunk(reader)
+                .map(HandshakeResult::DictionaryChunk),
             PacketType::ServerConnectResponse => self.recv_connect_response(),
-            PacketType::ServerRejectResponse => Some(HandshakeResult::Rejected),
+            PacketType::ServerRejectResponse => {
+                RejectReason::de(reader).ok().map(HandshakeResult::Rejected)
+            }
             _ => None,
         }
     }
@@ -106,6 +156,7 @@ impl<P: Protocolize> HandshakeManager<P> {
         StandardHeader::new(PacketType::ClientChallengeRequest, 0, 0, 0).ser(&mut writer);
 
         self.pre_connection_timestamp.ser(&mut writer);
+        PROTOCOL_VERSION.ser(&mut writer);
 
         writer
     }
@@ -125,13 +176,66 @@ impl<P: Protocolize> HandshakeManager<P> {
                     return;
                 }
                 let digest_bytes = digest_bytes_result.unwrap();
+
+                let dictionary_version_result = DictionaryVersion::de(reader);
+                if dictionary_version_result.is_err() {
+                    return;
+                }
+                let dictionary_version = dictionary_version_result.unwrap().get() as u8;
+
                 self.pre_connection_digest = Some(digest_bytes);
 
-                self.connection_state = HandshakeState::AwaitingConnectResponse;
+                if dictionary_version == 0 {
+                    self.connection_state = HandshakeState::AwaitingConnectResponse;
+                } else {
+                    // Server compresses with a dictionary we need to fetch first
+                    self.dictionary_receiver.expect_version(dictionary_version);
+                    self.connection_state = HandshakeState::AwaitingDictionary;
+                    self.handshake_timer.ring_manual();
+                }
             }
         }
     }
 
+    // Optional step between 2 & 3 of Handshake, also used while connected.
+    // Padded with random bytes to `DICTIONARY_REQUEST_BYTES`, so that the
+    // padding can't be compressed away
+    pub fn write_dictionary_request(&self) -> BitWriter {
+        let mut writer = BitWriter::new();
+        StandardHeader::new(PacketType::ClientDictionaryRequest, 0, 0, 0).ser(&mut writer);
+
+        self.write_signed_timestamp(&mut writer);
+
+        let (version, next_chunk) = self.dictionary_receiver.next_request();
+        DictionaryVersion::new(version).ser(&mut writer);
+        next_chunk.ser(&mut writer);
+
+        while (writer.bit_count() as usize) < DICTIONARY_REQUEST_BYTES * 8 {
+            writer.write_bit(Random::gen_bool());
+        }
+
+        writer
+    }
+
+    /// Reads a chunk of a compression dictionary, returning the whole
+    /// dictionary once complete. Returns None if the packet is malformed.
+    pub fn recv_dictionary_chunk(
+        &mut self,
+        reader: &mut BitReader,
+    ) -> Option<Option<CompressionDictionary>> {
+        // only the Server we've completed the challenge with sends dictionaries
+        self.pre_connection_digest.as_ref()?;
+
+        let dictionary_opt = self.dictionary_receiver.read_chunk(reader).ok()?;
+
+        if dictionary_opt.is_some() && self.connection_state == HandshakeState::AwaitingDictionary {
+            self.connection_state = HandshakeState::AwaitingConnectResponse;
+            self.handshake_timer.ring_manual();
+        }
+
+        Some(dictionary_opt)
+    }
+
     // Step 3 of Handshake
     pub fn write_connect_request(&self) -> BitWriter {
         let mut writer = BitWriter::new();
@@ -3,9 +3,9 @@ use std::{net::SocketAddr, time::Duration};
 use naia_client_socket::{NaiaClientSocketError, PacketReceiver, PacketSender, ServerAddr};
 pub use naia_shared::{
     serde::{BitReader, BitWriter},
-    BandwidthMonitor, CompressionConfig, ConnectionConfig, Decoder, Encoder, PacketType,
-    ProtocolKindType, Protocolize, ReplicateSafe, SharedConfig, StandardHeader, Timer, Timestamp,
-    WorldMutType, WorldRefType,
+    BandwidthMonitor, CompressionConfig, CompressionDictionary, ConnectionConfig, Decoder, Encoder,
+    PacketType, ProtocolKindType, Protocolize, ReplicateSafe, SharedConfig, StandardHeader, Timer,
+    Timestamp, WorldMutType, WorldRefType,
 };
 
 pub struct Io {
@@ -73,7 +73,7 @@ impl Io {
 
         // Bandwidth monitoring
         if let Some(monitor) = &mut self.outgoing_bandwidth_monitor {
-            monitor.record_packet(payload.len());
+            monitor.record_compressed_packet(payload.len(), length);
         }
 
         self.packet_sender
@@ -90,22 +90,42 @@ impl Io {
             .receive();
 
         if let Ok(Some(mut payload)) = receive_result {
-            // Bandwidth monitoring
-            if let Some(monitor) = &mut self.incoming_bandwidth_monitor {
-                monitor.record_packet(payload.len());
-            }
+            let received_length = payload.len();
 
             // Decompression
             if let Some(decoder) = &mut self.incoming_decoder {
                 payload = decoder.decode(payload);
             }
 
+            // Bandwidth monitoring
+            if let Some(monitor) = &mut self.incoming_bandwidth_monitor {
+                monitor.record_compressed_packet(received_length, payload.len());
+            }
+
             Ok(Some(BitReader::new(payload)))
         } else {
             receive_result.map(|payload_opt| payload_opt.map(BitReader::new))
         }
     }
 
+    /// Starts using a compression dictionary received from the Server
+    pub fn set_dictionary(&mut self, dictionary: &CompressionDictionary) {
+        if let Some(encoder) = &mut self.outgoing_encoder {
+            encoder.set_dictionary(dictionary);
+        }
+        if let Some(decoder) = &mut self.incoming_decoder {
+            decoder.add_dictionary(dictionary);
+        }
+    }
+
+    /// Returns the dictionary trained while in `CompressionMode::Training`,
+    /// once enough outgoing packets have been sampled
+    pub fn take_trained_dictionary(&mut self) -> Option<Vec<u8>> {
+        self.outgoing_encoder
+            .as_mut()
+            .and_then(|encoder| encoder.take_trained_dictionary())
+    }
+
     pub fn server_addr_unwrapped(&self) -> SocketAddr {
         if let ServerAddr::Found(server_addr) = self
             .packet_sender
@@ -128,10 +148,23 @@ impl Io {
     }
 
     pub fn incoming_bandwidth(&mut self) -> f32 {
-        return self
-            .incoming_bandwidth_monitor
+        self.incoming_bandwidth_monitor
             .as_mut()
             .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
-            .bandwidth();
+            .bandwidth()
+    }
+
+    pub fn outgoing_compression_ratio(&mut self) -> f32 {
+        self.outgoing_bandwidth_monitor
+            .as_mut()
+            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
+            .compression_ratio()
+    }
+
+    pub fn incoming_compression_ratio(&mut self) -> f32 {
+        self.incoming_bandwidth_monitor
+            .as_mut()
+            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
+            .compression_ratio()
     }
 }
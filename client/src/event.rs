@@ -1,6 +1,6 @@
 use std::net::SocketAddr;
 
-use naia_shared::{ChannelIndex, Protocolize, Tick};
+use naia_shared::{ChannelIndex, DiffMask, Protocolize, Tick};
 
 /// An Event that is be emitted by the Client, usually as a result of some
 /// communication with the Server
@@ -10,14 +10,20 @@ pub enum Event<P: Protocolize, E: Copy, C: ChannelIndex> {
     Connection(SocketAddr),
     /// Occurs when the Client receives notice that it was unable to authenticate with the Server, and that new credentials should be given
     Rejection(SocketAddr),
+    /// Occurs when the Client has given up on establishing a connection with
+    /// the Server. This is always the last event of a failed attempt, and
+    /// follows a Rejection event if the Server rejected the Client's auth
+    ConnectionFailed(ConnectionFailure),
     /// Occurs when the Client has lost connection with the Server, usually as a
     /// result of a timeout
     Disconnection(SocketAddr),
     /// A Tick Event, the duration between Tick events is defined in the Config
     /// passed to the Client on initialization
     Tick,
-    /// Occurs when an Entity on the Server has come into scope for the Client
-    SpawnEntity(E),
+    /// Occurs when an Entity on the Server has come into scope for the Client.
+    /// The Entity already has every Component it was spawned with, the kinds
+    /// of which are given, and no InsertComponent Events are emitted for them
+    SpawnEntity(E, Vec<P::Kind>),
     /// Occurs when an Entity on the Server has been destroyed, or left the
     /// Client's scope
     DespawnEntity(E),
@@ -25,9 +31,52 @@ pub enum Event<P: Protocolize, E: Copy, C: ChannelIndex> {
     InsertComponent(E, P::Kind),
     /// Occurs when a Component has had a state change on the Server while
     /// the Entity it is attached to has come into scope for the Client
-    UpdateComponent(Tick, E, P::Kind),
+    UpdateComponent(Tick, E, P::Kind, ComponentChanges<P>),
     /// Occurs when a Component should be removed from the given Entity
     RemoveComponent(E, P),
+    /// Occurs when the Server has given the Client authority over an Entity,
+    /// whose Properties the Client may now change and replicate to the Server
+    AuthorityGranted(E),
+    /// Occurs when the Client no longer has authority over an Entity, because
+    /// the Server took it back, or the Entity left the Client's scope
+    AuthorityRevoked(E),
     /// A Message emitted to the Client from the Server
     Message(C, P),
+    /// Occurs when a Message sent with a time-to-live or deadline Tick could
+    /// not be delivered to the Server in time, and has been dropped
+    MessageExpired(C, P),
+}
+
+/// Describes the changes an update from the Server made to a Component
+pub struct ComponentChanges<P: Protocolize> {
+    /// Which Properties the update changed, indexed in the order they are
+    /// declared in. The derived `{Component}Property` enum names the index of
+    /// each, for example `PositionProperty::X as u8`
+    pub changed_properties: DiffMask,
+    /// The state of the Component before the update, if it was requested
+    /// with `Client::track_previous_state()`
+    pub previous: Option<P>,
+}
+
+impl<P: Protocolize> ComponentChanges<P> {
+    /// Returns whether the Property at the given index was changed
+    pub fn is_changed(&self, property_index: u8) -> bool {
+        self.changed_properties.bit(property_index) == Some(true)
+    }
+}
+
+/// The cause of a failed attempt to connect to the Server
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConnectionFailure {
+    /// The Server did not complete the handshake within the configured
+    /// connect timeout or maximum number of attempts
+    Timeout,
+    /// The Server rejected the Client's auth message
+    Rejected,
+    /// The underlying socket reported an error
+    TransportError(String),
+    /// The Server uses a different version of the naia protocol
+    VersionMismatch,
+    /// The Server has reached its maximum number of Users
+    ServerFull,
 }
@@ -0,0 +1,16 @@
+use naia_shared::{Protocolize, Replicate};
+
+/// A Component which the Client can render in between the states received
+/// from the Server, once enabled with `Client::enable_interpolation()`
+pub trait Interpolate<P: Protocolize>: Replicate<P> {
+    /// Returns the state at the given fraction of the way from self to
+    /// `next`. The fraction is above 1.0 when extrapolating past the most
+    /// recent state received
+    fn interpolate(&self, next: &Self, fraction: f32) -> Self;
+
+    /// Returns whether the change from self to `next` should be snapped to
+    /// rather than interpolated, for example when an Entity teleports
+    fn should_snap(&self, _next: &Self) -> bool {
+        false
+    }
+}
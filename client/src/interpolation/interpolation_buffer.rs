@@ -0,0 +1,281 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use naia_shared::{
+    sequence_greater_than, wrapping_diff, ChannelIndex, Protocolize, Tick, WorldRefType,
+};
+
+use crate::{error::NaiaClientError, event::Event};
+
+use super::{interpolate::Interpolate, interpolation_config::InterpolationConfig};
+
+// How many snapshots of each Component are kept
+const SNAPSHOT_BUFFER_LENGTH: usize = 32;
+
+struct InterpolationFns<P: Protocolize> {
+    interpolate: fn(&P, &P, f32) -> P,
+    should_snap: fn(&P, &P) -> bool,
+}
+
+fn interpolate_protocol<P: Protocolize, R: Interpolate<P>>(from: &P, to: &P, fraction: f32) -> P {
+    let from = from
+        .cast_ref::<R>()
+        .expect("snapshot should be of the registered type");
+    let to = to
+        .cast_ref::<R>()
+        .expect("snapshot should be of the registered type");
+    from.interpolate(to, fraction).into_protocol()
+}
+
+fn should_snap_protocol<P: Protocolize, R: Interpolate<P>>(from: &P, to: &P) -> bool {
+    let from = from
+        .cast_ref::<R>()
+        .expect("snapshot should be of the registered type");
+    let to = to
+        .cast_ref::<R>()
+        .expect("snapshot should be of the registered type");
+    from.should_snap(to)
+}
+
+/// Keeps timestamped snapshots of the Components which have interpolation
+/// enabled, keyed by the Server tick they were received at
+pub struct InterpolationBuffer<P: Protocolize, E: Copy + Eq + Hash> {
+    config: InterpolationConfig,
+    kinds: HashMap<P::Kind, InterpolationFns<P>>,
+    #[allow(clippy::type_complexity)]
+    snapshots: HashMap<(E, P::Kind), VecDeque<(Tick, P)>>,
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash> InterpolationBuffer<P, E> {
+    pub fn new(config: &InterpolationConfig) -> Self {
+        Self {
+            config: config.clone(),
+            kinds: HashMap::new(),
+            snapshots: HashMap::new(),
+        }
+    }
+
+    pub fn enable<R: Interpolate<P>>(&mut self) {
+        self.kinds.insert(
+            P::kind_of::<R>(),
+            InterpolationFns {
+                interpolate: interpolate_protocol::<P, R>,
+                should_snap: should_snap_protocol::<P, R>,
+            },
+        );
+    }
+
+    pub fn is_enabled(&self, component_kind: &P::Kind) -> bool {
+        self.kinds.contains_key(component_kind)
+    }
+
+    /// Records the state of a Component at the given Server tick. States
+    /// older than the last one recorded are ignored
+    pub fn record(&mut self, entity: &E, component_kind: &P::Kind, tick: Tick, state: P) {
+        let fns = match self.kinds.get(component_kind) {
+            Some(fns) => fns,
+            None => return,
+        };
+        let snapshots = self
+            .snapshots
+            .entry((*entity, *component_kind))
+            .or_default();
+
+        if let Some((last_tick, last_state)) = snapshots.back() {
+            if *last_tick == tick {
+                snapshots.pop_back();
+            } else if sequence_greater_than(*last_tick, tick) {
+                return;
+            } else if (fns.should_snap)(last_state, &state) {
+                snapshots.clear();
+            }
+        }
+
+        snapshots.push_back((tick, state));
+        if snapshots.len() > SNAPSHOT_BUFFER_LENGTH {
+            snapshots.pop_front();
+        }
+    }
+
+    /// Records the state of every Component of an enabled kind which was
+    /// spawned, inserted or updated by the given Events, and forgets the
+    /// Components which were removed
+    pub fn record_events<W: WorldRefType<P, E>, C: ChannelIndex>(
+        &mut self,
+        world: &W,
+        events: &[Result<Event<P, E, C>, NaiaClientError>],
+        receiving_tick: Tick,
+    ) {
+        if self.kinds.is_empty() {
+            return;
+        }
+        for event in events.iter().flatten() {
+            match event {
+                Event::SpawnEntity(entity, component_kinds) => {
+                    for component_kind in component_kinds {
+                        self.record_from_world(world, entity, component_kind, receiving_tick);
+                    }
+                }
+                Event::InsertComponent(entity, component_kind) => {
+                    self.record_from_world(world, entity, component_kind, receiving_tick);
+                }
+                Event::UpdateComponent(tick, entity, component_kind, _) => {
+                    self.record_from_world(world, entity, component_kind, *tick);
+                }
+                Event::RemoveComponent(entity, component) => {
+                    self.remove_component(entity, &component.dyn_ref().kind());
+                }
+                Event::DespawnEntity(entity) => {
+                    self.remove_entity(entity);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn record_from_world<W: WorldRefType<P, E>>(
+        &mut self,
+        world: &W,
+        entity: &E,
+        component_kind: &P::Kind,
+        tick: Tick,
+    ) {
+        if !self.is_enabled(component_kind) {
+            return;
+        }
+        if let Some(component) = world.component_of_kind(entity, component_kind) {
+            let state = component.protocol_copy();
+            self.record(entity, component_kind, tick, state);
+        }
+    }
+
+    pub fn remove_component(&mut self, entity: &E, component_kind: &P::Kind) {
+        self.snapshots.remove(&(*entity, *component_kind));
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        self.snapshots
+            .retain(|(snapshot_entity, _), _| snapshot_entity != entity);
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Returns the state of a Component at the configured delay behind the
+    /// given receiving tick, plus the fraction of the current tick elapsed
+    pub fn interpolated<R: Interpolate<P>>(
+        &self,
+        entity: &E,
+        receiving_tick: Tick,
+        tick_fraction: f32,
+    ) -> Option<R> {
+        let component_kind = P::kind_of::<R>();
+        let fns = self.kinds.get(&component_kind)?;
+        let snapshots = self.snapshots.get(&(*entity, component_kind))?;
+
+        let render_tick = receiving_tick.wrapping_sub(self.config.delay_ticks);
+        let ticks: Vec<Tick> = snapshots.iter().map(|(tick, _)| *tick).collect();
+
+        let state = match sample(
+            &ticks,
+            render_tick,
+            tick_fraction,
+            self.config.max_extrapolation_ticks,
+        )? {
+            Sample::At(index) => snapshots[index].1.clone(),
+            Sample::Between(index, fraction) => {
+                (fns.interpolate)(&snapshots[index].1, &snapshots[index + 1].1, fraction)
+            }
+        };
+
+        state.cast::<R>()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Sample {
+    // exactly the snapshot at the index
+    At(usize),
+    // the given fraction of the way from the snapshot at the index to the
+    // next one, extrapolating past it if above 1.0
+    Between(usize, f32),
+}
+
+// Finds where the render time falls among the ticks of the snapshots, which
+// are in order
+fn sample(
+    ticks: &[Tick],
+    render_tick: Tick,
+    tick_fraction: f32,
+    max_extrapolation_ticks: u16,
+) -> Option<Sample> {
+    if ticks.is_empty() {
+        return None;
+    }
+
+    // how many ticks each snapshot is after the render time
+    let offset = |tick: Tick| wrapping_diff(render_tick, tick) as f32 - tick_fraction;
+
+    // the last snapshot at or before the render time
+    let previous = match ticks.iter().rposition(|tick| offset(*tick) <= 0.0) {
+        Some(previous) => previous,
+        // nothing has been received from that far back, so show the oldest
+        None => return Some(Sample::At(0)),
+    };
+
+    if previous + 1 < ticks.len() {
+        let from = offset(ticks[previous]);
+        let to = offset(ticks[previous + 1]);
+        return Some(Sample::Between(previous, -from / (to - from)));
+    }
+
+    // no newer snapshot has arrived in time, so extrapolate from the last two
+    if previous == 0 || max_extrapolation_ticks == 0 {
+        return Some(Sample::At(previous));
+    }
+    let span = wrapping_diff(ticks[previous - 1], ticks[previous]) as f32;
+    let ahead = (-offset(ticks[previous])).min(max_extrapolation_ticks as f32);
+    Some(Sample::Between(previous - 1, 1.0 + ahead / span))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sample, Sample};
+
+    #[test]
+    fn interpolates_between_surrounding_snapshots() {
+        let ticks = [10, 12, 14];
+
+        assert_eq!(sample(&ticks, 12, 0.0, 2), Some(Sample::Between(1, 0.0)));
+        assert_eq!(sample(&ticks, 12, 0.5, 2), Some(Sample::Between(1, 0.25)));
+        assert_eq!(sample(&ticks, 13, 0.0, 2), Some(Sample::Between(1, 0.5)));
+    }
+
+    #[test]
+    fn shows_oldest_snapshot_before_any_were_received() {
+        let ticks = [10, 12];
+
+        assert_eq!(sample(&ticks, 8, 0.0, 2), Some(Sample::At(0)));
+        assert_eq!(sample(&[], 8, 0.0, 2), None);
+    }
+
+    #[test]
+    fn extrapolates_up_to_the_limit() {
+        let ticks = [10, 12];
+
+        assert_eq!(sample(&ticks, 13, 0.0, 2), Some(Sample::Between(0, 1.5)));
+        assert_eq!(sample(&ticks, 20, 0.0, 2), Some(Sample::Between(0, 2.0)));
+        assert_eq!(sample(&ticks, 20, 0.0, 0), Some(Sample::At(1)));
+        assert_eq!(sample(&[10], 20, 0.0, 2), Some(Sample::At(0)));
+    }
+
+    #[test]
+    fn handles_wrapping_ticks() {
+        let ticks = [65534, 0];
+
+        assert_eq!(sample(&ticks, 65535, 0.0, 2), Some(Sample::Between(0, 0.5)));
+    }
+}
@@ -0,0 +1,25 @@
+use std::default::Default;
+
+/// Contains Config properties which control how the Client interpolates
+/// Components
+#[derive(Clone)]
+pub struct InterpolationConfig {
+    /// How many ticks behind the Client's receiving tick that interpolated
+    /// Components are rendered at. A larger delay means that a state from
+    /// the Server is more likely to have arrived in time to interpolate
+    /// towards
+    pub delay_ticks: u16,
+    /// The maximum number of ticks that Components are extrapolated past the
+    /// most recent state received from the Server, when no newer state has
+    /// arrived in time
+    pub max_extrapolation_ticks: u16,
+}
+
+impl Default for InterpolationConfig {
+    fn default() -> Self {
+        Self {
+            delay_ticks: 2,
+            max_extrapolation_ticks: 2,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod interpolate;
+pub mod interpolation_buffer;
+pub mod interpolation_config;
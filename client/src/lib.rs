@@ -18,16 +18,26 @@ mod command_history;
 mod connection;
 mod error;
 mod event;
+mod interpolation;
+mod prediction;
 mod protocol;
+mod server_query;
 mod tick;
 
 pub use client::Client;
 pub use client_config::ClientConfig;
 pub use command_history::CommandHistory;
 pub use error::NaiaClientError;
-pub use event::Event;
+pub use event::{ComponentChanges, ConnectionFailure, Event};
+pub use interpolation::{interpolate::Interpolate, interpolation_config::InterpolationConfig};
+pub use prediction::{
+    predict::Predict, prediction::Prediction, prediction_config::PredictionConfig,
+};
 pub use protocol::entity_ref::EntityRef;
+pub use server_query::ServerQuery;
 
 pub mod internal {
-    pub use crate::connection::handshake_manager::{HandshakeManager, HandshakeState};
+    pub use crate::connection::handshake_manager::{
+        HandshakeManager, HandshakeResult, HandshakeState,
+    };
 }
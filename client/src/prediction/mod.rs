@@ -0,0 +1,4 @@
+pub mod predict;
+#[allow(clippy::module_inception)]
+pub mod prediction;
+pub mod prediction_config;
@@ -0,0 +1,13 @@
+use naia_shared::Protocolize;
+
+use crate::interpolation::interpolate::Interpolate;
+
+/// A Component whose state the Client can predict ahead of the Server, by
+/// simulating the Client's own commands before the Server has processed them.
+/// Corrections are smoothed over with `Interpolate::interpolate()`
+pub trait Predict<P: Protocolize>: Interpolate<P> {
+    /// Returns whether a predicted state is far enough from the authoritative
+    /// state the Server reached on the same tick that the prediction should
+    /// be rolled back and the commands since then replayed
+    fn mispredicted(&self, authoritative: &Self) -> bool;
+}
@@ -0,0 +1,190 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use naia_shared::{sequence_less_than, Protocolize, Tick};
+
+use crate::command_history::CommandHistory;
+
+use super::{predict::Predict, prediction_config::PredictionConfig};
+
+struct PredictedEntity<R> {
+    // the predicted state after the command of each tick was applied, oldest
+    // first
+    history: VecDeque<(Tick, R)>,
+    // the predicted state after the most recent command
+    current: R,
+    // the state rendered while a correction is blended in, and how many ticks
+    // remain until it has caught up with the current state
+    correction: Option<(R, u16)>,
+}
+
+impl<R: Clone> PredictedEntity<R> {
+    fn rendered(&self) -> &R {
+        match &self.correction {
+            Some((rendered, _)) => rendered,
+            None => &self.current,
+        }
+    }
+}
+
+/// Predicts the state of a Component on the Entities the Client controls,
+/// by applying the Client's commands to it as they are issued rather than
+/// waiting for the Server to process them. When an update from the Server
+/// shows that a prediction was wrong, the Component is rolled back to the
+/// Server's state and the commands issued since are replayed
+pub struct Prediction<P: Protocolize, E: Copy + Eq + Hash, R: Predict<P>, T: Clone> {
+    config: PredictionConfig,
+    command_history: CommandHistory<T>,
+    entities: HashMap<E, PredictedEntity<R>>,
+    phantom_p: PhantomData<P>,
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash, R: Predict<P>, T: Clone> Prediction<P, E, R, T> {
+    pub fn new(config: &PredictionConfig) -> Self {
+        Self {
+            config: config.clone(),
+            command_history: CommandHistory::default(),
+            entities: HashMap::new(),
+            phantom_p: PhantomData,
+        }
+    }
+
+    /// Starts predicting the Component of an Entity, from the given state
+    pub fn start_predicting(&mut self, entity: &E, state: R) {
+        self.entities.insert(
+            *entity,
+            PredictedEntity {
+                history: VecDeque::new(),
+                current: state,
+                correction: None,
+            },
+        );
+    }
+
+    pub fn stop_predicting(&mut self, entity: &E) {
+        self.entities.remove(entity);
+    }
+
+    pub fn is_predicting(&self, entity: &E) -> bool {
+        self.entities.contains_key(entity)
+    }
+
+    /// Returns whether a command can be predicted at the given tick, which
+    /// must be more recent than that of the last command predicted
+    pub fn can_predict(&self, tick: &Tick) -> bool {
+        self.command_history.can_insert(tick)
+    }
+
+    /// Applies a command issued at the given tick to every predicted Entity
+    /// with the simulation `step`, which should be the same one the Server
+    /// runs on receiving the command. Panics if the tick is not more recent
+    /// than that of the last command predicted
+    pub fn predict<F: FnMut(&E, &mut R, &T)>(&mut self, tick: Tick, command: T, mut step: F) {
+        self.command_history.insert(tick, command.clone());
+
+        for (entity, predicted) in self.entities.iter_mut() {
+            step(entity, &mut predicted.current, &command);
+            predicted
+                .history
+                .push_back((tick, predicted.current.clone()));
+
+            // blend the rendered state a step closer to the current one
+            if let Some((rendered, remaining)) = predicted.correction.take() {
+                if remaining > 1 {
+                    let blended = rendered.interpolate(&predicted.current, 1.0 / remaining as f32);
+                    predicted.correction = Some((blended, remaining - 1));
+                }
+            }
+        }
+    }
+
+    /// Checks the state of an Entity's Component that the Server reached on
+    /// the given tick against the state predicted for that tick. If it was
+    /// mispredicted, rolls the Component back to the Server's state and
+    /// replays every command issued since with the simulation `step`, then
+    /// returns true. Updates should be reconciled in the order of their ticks
+    pub fn reconcile<F: FnMut(&E, &mut R, &T)>(
+        &mut self,
+        entity: &E,
+        server_tick: Tick,
+        authoritative: &R,
+        mut step: F,
+    ) -> bool {
+        // commands up to and including the Server's tick are no longer needed
+        let replays = self.command_history.replays(&server_tick);
+
+        let predicted = match self.entities.get_mut(entity) {
+            Some(predicted) => predicted,
+            None => return false,
+        };
+
+        while let Some((tick, _)) = predicted.history.front() {
+            if !sequence_less_than(*tick, server_tick) {
+                break;
+            }
+            predicted.history.pop_front();
+        }
+
+        let mispredicted = match predicted.history.front() {
+            Some((tick, state)) if *tick == server_tick => {
+                let mispredicted = state.mispredicted(authoritative);
+                predicted.history.pop_front();
+                mispredicted
+            }
+            // without a command since, the current state is what was predicted
+            None => predicted.current.mispredicted(authoritative),
+            // the state predicted for the tick has been lost
+            Some(_) => true,
+        };
+
+        if !mispredicted {
+            return false;
+        }
+
+        // roll back to the authoritative state, and replay the commands since
+        let rendered = predicted.rendered().clone();
+        predicted.current = authoritative.clone();
+        predicted.history.clear();
+        // the command history gives the most recent commands first
+        for (tick, command) in replays.into_iter().rev() {
+            step(entity, &mut predicted.current, &command);
+            predicted
+                .history
+                .push_back((tick, predicted.current.clone()));
+        }
+
+        predicted.correction = if self.config.smoothing_ticks > 0 {
+            Some((rendered, self.config.smoothing_ticks))
+        } else {
+            None
+        };
+
+        true
+    }
+
+    /// Gets the predicted state of an Entity's Component, after the most
+    /// recent command
+    pub fn predicted(&self, entity: &E) -> Option<&R> {
+        self.entities
+            .get(entity)
+            .map(|predicted| &predicted.current)
+    }
+
+    /// Gets the state of an Entity's Component which should be rendered. This
+    /// is the predicted state, unless a correction is still being blended in
+    pub fn rendered(&self, entity: &E) -> Option<&R> {
+        self.entities
+            .get(entity)
+            .map(|predicted| predicted.rendered())
+    }
+
+    /// Forgets every predicted Entity and command, for example after a
+    /// disconnection
+    pub fn clear(&mut self) {
+        self.command_history = CommandHistory::default();
+        self.entities.clear();
+    }
+}
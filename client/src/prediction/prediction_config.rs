@@ -0,0 +1,16 @@
+use std::default::Default;
+
+/// Contains Config properties which control how a Prediction corrects
+/// mispredicted Components
+#[derive(Clone)]
+pub struct PredictionConfig {
+    /// The number of ticks over which a correction to the predicted state is
+    /// blended in, after a misprediction. If 0, corrections are snapped to
+    pub smoothing_ticks: u16,
+}
+
+impl Default for PredictionConfig {
+    fn default() -> Self {
+        Self { smoothing_ticks: 4 }
+    }
+}
@@ -0,0 +1,371 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use naia_shared::{
+    message_list_header,
+    serde::{BitCounter, BitReader, BitWrite, BitWriter, Serde, SerdeErr, UnsignedVariableInteger},
+    AuthorityAction, AuthorityChannel, ChannelIndex, DiffMask, GlobalDiffHandler, Instant,
+    PacketIndex, PacketNotifiable, PropertyMutator, Protocolize, UserDiffHandler, WorldMutType,
+    WorldRefType,
+};
+
+use crate::{error::NaiaClientError, event::Event};
+
+use super::entity_manager::EntityManager;
+
+const DROP_UPDATE_RTT_FACTOR: f32 = 1.5;
+
+/// Keeps track of the Server Entities which the Client has been given
+/// authority over, and replicates changes to their Components back to the
+/// Server
+pub struct AuthorityManager<P: Protocolize, E: Copy + Eq + Hash> {
+    address: SocketAddr,
+    channel: AuthorityChannel,
+    global_diff_handler: Arc<RwLock<GlobalDiffHandler<E, P::Kind>>>,
+    diff_handler: UserDiffHandler<E, P::Kind>,
+    entities: HashMap<E, HashSet<P::Kind>>,
+    #[allow(clippy::type_complexity)]
+    sent_updates: HashMap<PacketIndex, (Instant, HashMap<(E, P::Kind), DiffMask>)>,
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash> AuthorityManager<P, E> {
+    pub fn new(
+        address: SocketAddr,
+        global_diff_handler: &Arc<RwLock<GlobalDiffHandler<E, P::Kind>>>,
+    ) -> Self {
+        Self {
+            address,
+            channel: AuthorityChannel::default(),
+            global_diff_handler: global_diff_handler.clone(),
+            diff_handler: UserDiffHandler::new(global_diff_handler),
+            entities: HashMap::new(),
+            sent_updates: HashMap::new(),
+        }
+    }
+
+    // Authority
+
+    pub fn has_authority(&self, entity: &E) -> bool {
+        self.entities.contains_key(entity)
+    }
+
+    pub fn request_authority(&mut self, entity_manager: &EntityManager<P, E>, entity: &E) {
+        if let Some(net_entity) = entity_manager.net_entity(entity) {
+            self.channel
+                .send_action(net_entity, AuthorityAction::Request);
+        }
+    }
+
+    pub fn release_authority(&mut self, entity_manager: &EntityManager<P, E>, entity: &E) {
+        if !self.has_authority(entity) {
+            return;
+        }
+        if let Some(net_entity) = entity_manager.net_entity(entity) {
+            self.channel
+                .send_action(net_entity, AuthorityAction::Release);
+        }
+        self.deregister_entity(entity);
+    }
+
+    // Incoming
+
+    pub fn read_actions(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.channel.read_actions(reader)
+    }
+
+    /// Applies Grants & Revokes received from the Server, and keeps the set of
+    /// tracked Components in sync with the World
+    pub fn process_actions<W: WorldMutType<P, E>, C: ChannelIndex>(
+        &mut self,
+        world: &mut W,
+        entity_manager: &EntityManager<P, E>,
+        event_stream: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
+    ) {
+        for (net_entity, action) in self.channel.receive_actions() {
+            let entity = match entity_manager.entity(&net_entity) {
+                Some(entity) => entity,
+                None => continue,
+            };
+            match action {
+                AuthorityAction::Grant => {
+                    if !self.has_authority(&entity) && world.has_entity(&entity) {
+                        self.entities.insert(entity, HashSet::new());
+                        event_stream.push_back(Ok(Event::AuthorityGranted(entity)));
+                    }
+                }
+                AuthorityAction::Revoke => {
+                    if self.has_authority(&entity) {
+                        self.deregister_entity(&entity);
+                        event_stream.push_back(Ok(Event::AuthorityRevoked(entity)));
+                    }
+                }
+                AuthorityAction::Request | AuthorityAction::Release => {
+                    // only ever sent by the Client
+                }
+            }
+        }
+
+        self.sync_components(world, event_stream);
+    }
+
+    /// Marks the given Properties of a Component as being in sync with the
+    /// Server, because the Server is the one which changed them
+    pub fn clear_diff_mask_bits(
+        &mut self,
+        entity: &E,
+        component_kind: &P::Kind,
+        diff_mask: &DiffMask,
+    ) {
+        if self.diff_handler.has_component(entity, component_kind) {
+            self.diff_handler
+                .nand_diff_mask(entity, component_kind, diff_mask);
+        }
+    }
+
+    // Outgoing
+
+    pub fn collect_outgoing_actions(&mut self, now: &Instant, rtt_millis: &f32) {
+        self.channel.collect_outgoing_actions(now, rtt_millis);
+        self.collect_dropped_update_packets(rtt_millis);
+    }
+
+    pub fn has_outgoing_actions(&self) -> bool {
+        if self.channel.has_outgoing_actions() {
+            return true;
+        }
+        self.entities.iter().any(|(entity, component_kinds)| {
+            component_kinds.iter().any(|component_kind| {
+                self.diff_handler.diff_mask_is_clear(entity, component_kind) == Some(false)
+            })
+        })
+    }
+
+    pub fn write_all<W: WorldRefType<P, E>>(
+        &mut self,
+        now: &Instant,
+        writer: &mut BitWriter,
+        packet_index: &PacketIndex,
+        world: &W,
+        entity_manager: &EntityManager<P, E>,
+    ) {
+        self.channel.write_actions(writer, packet_index);
+        self.write_updates(now, writer, packet_index, world, entity_manager);
+    }
+
+    fn write_updates<W: WorldRefType<P, E>>(
+        &mut self,
+        now: &Instant,
+        writer: &mut BitWriter,
+        packet_index: &PacketIndex,
+        world: &W,
+        entity_manager: &EntityManager<P, E>,
+    ) {
+        let mut update_entities: Vec<(E, Vec<P::Kind>)> = Vec::new();
+
+        // Header
+        {
+            // Measure
+            let current_packet_size = writer.bit_count();
+            let mut counter = BitCounter::new();
+            message_list_header::write(&mut counter, 123);
+
+            // Check for overflow
+            if current_packet_size + counter.bit_count() > writer.max_bits() {
+                message_list_header::write(writer, 0);
+                return;
+            }
+
+            // Find how many updates will fit into the packet
+            for (entity, component_kinds) in &self.entities {
+                let changed_kinds: Vec<P::Kind> = component_kinds
+                    .iter()
+                    .filter(|component_kind| {
+                        self.diff_handler.diff_mask_is_clear(entity, component_kind) == Some(false)
+                    })
+                    .copied()
+                    .collect();
+                if changed_kinds.is_empty() || entity_manager.net_entity(entity).is_none() {
+                    continue;
+                }
+
+                self.write_update(world, entity_manager, &mut counter, entity, &changed_kinds);
+                if current_packet_size + counter.bit_count() <= writer.max_bits() {
+                    update_entities.push((*entity, changed_kinds));
+                } else {
+                    break;
+                }
+            }
+        }
+
+        // Write header
+        message_list_header::write(writer, update_entities.len() as u16);
+
+        if update_entities.is_empty() {
+            return;
+        }
+
+        // Updates
+        let mut sent_masks = HashMap::new();
+        for (entity, component_kinds) in update_entities {
+            self.write_update(world, entity_manager, writer, &entity, &component_kinds);
+
+            // having copied the diff masks for this update, clear them
+            for component_kind in component_kinds {
+                let diff_mask = self
+                    .diff_handler
+                    .diff_mask(&entity, &component_kind)
+                    .expect("DiffHandler does not have registered Component!")
+                    .clone();
+                sent_masks.insert((entity, component_kind), diff_mask);
+                self.diff_handler.clear_diff_mask(&entity, &component_kind);
+            }
+        }
+        self.sent_updates
+            .insert(*packet_index, (now.clone(), sent_masks));
+    }
+
+    fn write_update<W: WorldRefType<P, E>>(
+        &self,
+        world: &W,
+        entity_manager: &EntityManager<P, E>,
+        bit_writer: &mut dyn BitWrite,
+        entity: &E,
+        component_kinds: &[P::Kind],
+    ) {
+        // write net entity
+        entity_manager.net_entity(entity).unwrap().ser(bit_writer);
+
+        // write number of components
+        UnsignedVariableInteger::<3>::new(component_kinds.len() as u64).ser(bit_writer);
+
+        for component_kind in component_kinds {
+            // write component kind
+            component_kind.ser(bit_writer);
+
+            // write payload
+            let diff_mask = self
+                .diff_handler
+                .diff_mask(entity, component_kind)
+                .expect("DiffHandler does not have registered Component!")
+                .clone();
+            world
+                .component_of_kind(entity, component_kind)
+                .expect("Component does not exist in World")
+                .write_update(&diff_mask, bit_writer, entity_manager);
+        }
+    }
+
+    // Private methods
+
+    fn collect_dropped_update_packets(&mut self, rtt_millis: &f32) {
+        let drop_duration = Duration::from_millis((DROP_UPDATE_RTT_FACTOR * rtt_millis) as u64);
+
+        let dropped_packets: Vec<PacketIndex> = self
+            .sent_updates
+            .iter()
+            .filter(|(_, (time_sent, _))| time_sent.elapsed() > drop_duration)
+            .map(|(packet_index, _)| *packet_index)
+            .collect();
+
+        for packet_index in dropped_packets {
+            if let Some((_, diff_mask_map)) = self.sent_updates.remove(&packet_index) {
+                for ((entity, component_kind), diff_mask) in diff_mask_map {
+                    if self.diff_handler.has_component(&entity, &component_kind) {
+                        self.diff_handler
+                            .or_diff_mask(&entity, &component_kind, &diff_mask);
+                    }
+                }
+            }
+        }
+    }
+
+    // Register Components inserted into Entities the Client has authority
+    // over, and forget about those which were removed, along with Entities
+    // which have been despawned
+    fn sync_components<W: WorldMutType<P, E>, C: ChannelIndex>(
+        &mut self,
+        world: &mut W,
+        event_stream: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
+    ) {
+        let entities: Vec<E> = self.entities.keys().copied().collect();
+        for entity in entities {
+            if !world.has_entity(&entity) {
+                self.deregister_entity(&entity);
+                event_stream.push_back(Ok(Event::AuthorityRevoked(entity)));
+                continue;
+            }
+
+            let world_kinds: HashSet<P::Kind> =
+                world.component_kinds(&entity).into_iter().collect();
+            let tracked_kinds = self.entities.get(&entity).unwrap().clone();
+
+            for component_kind in tracked_kinds.difference(&world_kinds) {
+                self.deregister_component(&entity, component_kind);
+            }
+            for component_kind in world_kinds.difference(&tracked_kinds) {
+                self.register_component(world, &entity, component_kind);
+            }
+        }
+    }
+
+    fn register_component<W: WorldMutType<P, E>>(
+        &mut self,
+        world: &mut W,
+        entity: &E,
+        component_kind: &P::Kind,
+    ) {
+        let diff_mask_length = match world.component_of_kind(entity, component_kind) {
+            Some(component) => component.diff_mask_size(),
+            None => return,
+        };
+
+        let mut_sender = self
+            .global_diff_handler
+            .as_ref()
+            .write()
+            .expect("DiffHandler should be initialized")
+            .register_component(entity, component_kind, diff_mask_length);
+        world.component_set_mutator(entity, component_kind, &PropertyMutator::new(mut_sender));
+        self.diff_handler
+            .register_component(&self.address, entity, component_kind);
+
+        self.entities
+            .get_mut(entity)
+            .unwrap()
+            .insert(*component_kind);
+    }
+
+    fn deregister_component(&mut self, entity: &E, component_kind: &P::Kind) {
+        self.global_diff_handler
+            .as_ref()
+            .write()
+            .expect("DiffHandler should be initialized")
+            .deregister_component(entity, component_kind);
+        self.diff_handler
+            .deregister_component(entity, component_kind);
+
+        if let Some(component_kinds) = self.entities.get_mut(entity) {
+            component_kinds.remove(component_kind);
+        }
+    }
+
+    fn deregister_entity(&mut self, entity: &E) {
+        if let Some(component_kinds) = self.entities.remove(entity) {
+            for component_kind in component_kinds {
+                self.deregister_component(entity, &component_kind);
+            }
+        }
+    }
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash> PacketNotifiable for AuthorityManager<P, E> {
+    fn notify_packet_delivered(&mut self, packet_index: PacketIndex) {
+        self.sent_updates.remove(&packet_index);
+        self.channel.notify_packet_delivered(packet_index);
+    }
+}
@@ -1,51 +1,127 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     hash::Hash,
 };
 
 use naia_shared::{
     message_list_header,
-    serde::{BitReader, Serde, SerdeErr, UnsignedVariableInteger},
-    BigMap, ChannelIndex, EntityAction, EntityActionReceiver, EntityActionType, EntityHandle,
-    EntityHandleConverter, MessageId, NetEntity, NetEntityHandleConverter, Protocolize, Tick,
-    WorldMutType,
+    serde::{BitReader, BitWriter, OwnedBitReader, Serde, SerdeErr, UnsignedVariableInteger},
+    BigMap, ChannelIndex, ComponentUpdate, DiffMask, EntityAction, EntityActionReceiver,
+    EntityActionType, EntityHandle, EntityHandleConverter, MessageId, NetEntity,
+    NetEntityConverter, NetEntityHandleConverter, OwnedNetEntity, PacketIndex, Protocolize,
+    ReceivedBaselines, Tick, WorldMutType,
 };
 
-use crate::{error::NaiaClientError, event::Event};
+use crate::{
+    error::NaiaClientError,
+    event::{ComponentChanges, Event},
+};
 
 use super::entity_record::EntityRecord;
 
 pub struct EntityManager<P: Protocolize, E: Copy + Eq + Hash> {
-    entity_records: HashMap<E, EntityRecord<P::Kind>>,
+    entity_records: HashMap<E, EntityRecord<E, P::Kind>>,
     local_to_world_entity: HashMap<NetEntity, E>,
     pub handle_entity_map: BigMap<EntityHandle, E>,
+    host_entities: HashMap<E, EntityHandle>,
     receiver: EntityActionReceiver<NetEntity, P::Kind>,
     received_components: HashMap<(NetEntity, P::Kind), P>,
+    received_parents: HashMap<NetEntity, NetEntity>,
+    // Entities despawned along with their parent, whose own despawn action
+    // has not arrived yet
+    despawned_children: HashSet<NetEntity>,
+    // States of Components received as delta-compressed updates, which the
+    // Server writes later updates against
+    baselines: Option<ReceivedBaselines<NetEntity, P::Kind, P>>,
 }
 
-impl<P: Protocolize, E: Copy + Eq + Hash> Default for EntityManager<P, E> {
-    fn default() -> Self {
+impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
+    /// Create a new EntityManager, given whether the Server delta-compresses
+    /// its updates
+    pub fn new(delta_compression: bool) -> Self {
         Self {
             entity_records: HashMap::default(),
             local_to_world_entity: HashMap::default(),
             handle_entity_map: BigMap::default(),
+            host_entities: HashMap::default(),
             receiver: EntityActionReceiver::default(),
             received_components: HashMap::default(),
+            received_parents: HashMap::default(),
+            despawned_children: HashSet::default(),
+            baselines: if delta_compression {
+                Some(ReceivedBaselines::default())
+            } else {
+                None
+            },
+        }
+    }
+
+    // Host Entities
+
+    /// Track an Entity spawned by the Client, which is replicated to the
+    /// Server
+    pub fn host_spawn_entity(&mut self, entity: &E) {
+        if self.host_entities.contains_key(entity) || self.entity_records.contains_key(entity) {
+            panic!("entity already initialized!");
+        }
+        let entity_handle = self.handle_entity_map.insert(*entity);
+        self.host_entities.insert(*entity, entity_handle);
+    }
+
+    pub fn host_despawn_entity(&mut self, entity: &E) {
+        if let Some(entity_handle) = self.host_entities.remove(entity) {
+            self.handle_entity_map.remove(&entity_handle);
+        }
+    }
+
+    pub fn is_host_entity(&self, entity: &E) -> bool {
+        self.host_entities.contains_key(entity)
+    }
+
+    // Server Entities
+
+    /// Returns the NetEntity the Server uses for one of its Entities
+    pub fn net_entity(&self, entity: &E) -> Option<NetEntity> {
+        self.entity_records
+            .get(entity)
+            .map(|entity_record| entity_record.net_entity)
+    }
+
+    /// Returns the Entity spawned for the given NetEntity of the Server
+    pub fn entity(&self, net_entity: &NetEntity) -> Option<E> {
+        self.local_to_world_entity.get(net_entity).copied()
+    }
+
+    pub fn entity_parent(&self, entity: &E) -> Option<E> {
+        self.entity_records
+            .get(entity)
+            .and_then(|entity_record| entity_record.parent)
+    }
+
+    pub fn entity_children(&self, entity: &E) -> Vec<E> {
+        match self.entity_records.get(entity) {
+            Some(entity_record) => entity_record.children.iter().copied().collect(),
+            None => Vec::new(),
         }
     }
-}
 
-impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
     // Action Reader
 
     pub fn read_all<W: WorldMutType<P, E>, C: ChannelIndex>(
         &mut self,
         world: &mut W,
         server_tick: Tick,
+        previous_state_kinds: &HashSet<P::Kind>,
         reader: &mut BitReader,
         event_stream: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
     ) -> Result<(), SerdeErr> {
-        self.read_updates(world, server_tick, reader, event_stream)?;
+        self.read_updates(
+            world,
+            server_tick,
+            previous_state_kinds,
+            reader,
+            event_stream,
+        )?;
         self.read_actions(world, reader, event_stream)?;
         Ok(())
     }
@@ -96,6 +172,11 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
                 // read entity
                 let net_entity = NetEntity::de(reader)?;
 
+                // read parent
+                if let Some(parent_net_entity) = Option::<NetEntity>::de(reader)? {
+                    self.received_parents.insert(net_entity, parent_net_entity);
+                }
+
                 // read components
                 let components_num = UnsignedVariableInteger::<3>::de(reader)?.get();
                 let mut component_kinds = Vec::new();
@@ -176,50 +257,41 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
                     let entity_handle = self.handle_entity_map.insert(world_entity);
                     let mut entity_record = EntityRecord::new(net_entity, entity_handle);
 
-                    event_stream.push_back(Ok(Event::SpawnEntity(world_entity)));
+                    // the Server only spawns a child once the Client has its parent
+                    if let Some(parent_net_entity) = self.received_parents.remove(&net_entity) {
+                        if let Some(parent) = self.local_to_world_entity.get(&parent_net_entity) {
+                            entity_record.parent = Some(*parent);
+                            if let Some(parent_record) = self.entity_records.get_mut(parent) {
+                                parent_record.children.insert(world_entity);
+                            }
+                        }
+                    }
 
                     // read component list
-                    for component_kind in components {
+                    for component_kind in &components {
                         let component = self
                             .received_components
-                            .remove(&(net_entity, component_kind))
+                            .remove(&(net_entity, *component_kind))
                             .unwrap();
 
-                        entity_record.component_kinds.insert(component_kind);
+                        entity_record.component_kinds.insert(*component_kind);
 
                         component.extract_and_insert(&world_entity, world);
-
-                        event_stream
-                            .push_back(Ok(Event::InsertComponent(world_entity, component_kind)));
                     }
-                    //
 
                     self.entity_records.insert(world_entity, entity_record);
+
+                    // only emit once the Entity has all of its Components
+                    event_stream.push_back(Ok(Event::SpawnEntity(world_entity, components)));
                 }
                 EntityAction::DespawnEntity(net_entity) => {
                     //let e_u16: u16 = net_entity.into();
                     //info!("despawn entity: {}", e_u16);
 
-                    if let Some(world_entity) = self.local_to_world_entity.remove(&net_entity) {
-                        if self.entity_records.remove(&world_entity).is_none() {
-                            panic!("despawning an uninitialized entity");
-                        }
-
-                        // Generate event for each component, handing references off just in
-                        // case
-                        for component_kind in world.component_kinds(&world_entity) {
-                            if let Some(component) =
-                                world.remove_component_of_kind(&world_entity, &component_kind)
-                            {
-                                event_stream
-                                    .push_back(Ok(Event::RemoveComponent(world_entity, component)));
-                            }
-                        }
-
-                        world.despawn_entity(&world_entity);
-
-                        event_stream.push_back(Ok(Event::DespawnEntity(world_entity)));
-                    } else {
+                    if let Some(world_entity) = self.local_to_world_entity.get(&net_entity) {
+                        let world_entity = *world_entity;
+                        self.despawn_entity_tree(world, &world_entity, event_stream);
+                    } else if !self.despawned_children.remove(&net_entity) {
                         panic!("received message attempting to delete nonexistent entity");
                     }
                 }
@@ -232,10 +304,14 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
                         .remove(&(net_entity, component_kind))
                         .unwrap();
 
+                    if self.despawned_children.contains(&net_entity) {
+                        continue;
+                    }
+
                     if !self.local_to_world_entity.contains_key(&net_entity) {
                         panic!(
                             "attempting to add a component to nonexistent entity: {}",
-                            Into::<u16>::into(net_entity)
+                            Into::<u32>::into(net_entity)
                         );
                     } else {
                         let world_entity = self.local_to_world_entity.get(&net_entity).unwrap();
@@ -254,6 +330,10 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
                     //let e_u16: u16 = net_entity.into();
                     //info!("remove component for: {}", e_u16);
 
+                    if self.despawned_children.contains(&net_entity) {
+                        continue;
+                    }
+
                     let world_entity = self
                         .local_to_world_entity
                         .get_mut(&net_entity)
@@ -263,6 +343,10 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
                         .get_mut(world_entity)
                         .expect("attempting to delete component of nonexistent entity");
                     if entity_record.component_kinds.remove(&component_kind) {
+                        if let Some(baselines) = &mut self.baselines {
+                            baselines.remove_component(&net_entity, &component_kind);
+                        }
+
                         // Get component for last change
                         let component = world
                             .remove_component_of_kind(world_entity, &component_kind)
@@ -282,16 +366,76 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
         }
     }
 
+    // Despawns an Entity along with all of its descendants
+    fn despawn_entity_tree<W: WorldMutType<P, E>, C: ChannelIndex>(
+        &mut self,
+        world: &mut W,
+        world_entity: &E,
+        event_stream: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
+    ) {
+        let entity_record = self
+            .entity_records
+            .remove(world_entity)
+            .expect("despawning an uninitialized entity");
+        self.local_to_world_entity.remove(&entity_record.net_entity);
+        self.handle_entity_map.remove(&entity_record.entity_handle);
+        if let Some(baselines) = &mut self.baselines {
+            baselines.remove_entity(&entity_record.net_entity);
+        }
+
+        if let Some(parent) = entity_record.parent {
+            if let Some(parent_record) = self.entity_records.get_mut(&parent) {
+                parent_record.children.remove(world_entity);
+            }
+        }
+
+        for child in &entity_record.children {
+            if let Some(child_record) = self.entity_records.get(child) {
+                self.despawned_children.insert(child_record.net_entity);
+                self.despawn_entity_tree(world, child, event_stream);
+            }
+        }
+
+        // Generate event for each component, handing references off just in
+        // case
+        for component_kind in world.component_kinds(world_entity) {
+            if let Some(component) = world.remove_component_of_kind(world_entity, &component_kind) {
+                event_stream.push_back(Ok(Event::RemoveComponent(*world_entity, component)));
+            }
+        }
+
+        world.despawn_entity(world_entity);
+
+        event_stream.push_back(Ok(Event::DespawnEntity(*world_entity)));
+    }
+
     fn read_updates<W: WorldMutType<P, E>, C: ChannelIndex>(
         &mut self,
         world: &mut W,
         server_tick: Tick,
+        previous_state_kinds: &HashSet<P::Kind>,
         reader: &mut BitReader,
         event_stream: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
     ) -> Result<(), SerdeErr> {
         let update_count = message_list_header::read(reader)?;
+
+        // delta-compressed updates are remembered by the packet they were
+        // sent in
+        let packet_index = if self.baselines.is_some() && update_count > 0 {
+            PacketIndex::de(reader)?
+        } else {
+            0
+        };
+
         for _ in 0..update_count {
-            self.read_update(world, server_tick, reader, event_stream)?;
+            self.read_update(
+                world,
+                server_tick,
+                &packet_index,
+                previous_state_kinds,
+                reader,
+                event_stream,
+            )?;
         }
         Ok(())
     }
@@ -300,6 +444,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
         &mut self,
         world: &mut W,
         server_tick: Tick,
+        packet_index: &PacketIndex,
+        previous_state_kinds: &HashSet<P::Kind>,
         reader: &mut BitReader,
         event_stream: &mut VecDeque<Result<Event<P, E, C>, NaiaClientError>>,
     ) -> Result<(), SerdeErr> {
@@ -309,27 +455,110 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityManager<P, E> {
 
         for _ in 0..components_number {
             // read incoming update
-            let component_update = P::read_create_update(reader)?;
+            let delta_compressed = match self.baselines {
+                Some(_) => bool::de(reader)?,
+                None => false,
+            };
+            let component_update = if delta_compressed {
+                match self.read_delta_update(&net_entity, packet_index, reader)? {
+                    Some(component_update) => component_update,
+                    None => continue,
+                }
+            } else {
+                P::read_create_update(reader)?
+            };
             let component_kind = component_update.kind;
 
-            if let Some(world_entity) = self.local_to_world_entity.get(&net_entity) {
+            if let Some(world_entity) = self.local_to_world_entity.get(&net_entity).copied() {
+                let (changed_properties, previous) =
+                    match world.component_of_kind(&world_entity, &component_kind) {
+                        Some(component) => {
+                            let changed_properties =
+                                component.read_update_diff_mask(self, &component_update)?;
+                            let previous = if previous_state_kinds.contains(&component_kind) {
+                                Some(component.protocol_copy())
+                            } else {
+                                None
+                            };
+                            (changed_properties, previous)
+                        }
+                        None => continue,
+                    };
+
                 world.component_apply_update(
                     self,
-                    world_entity,
+                    &world_entity,
                     &component_kind,
                     component_update,
                 )?;
 
                 event_stream.push_back(Ok(Event::UpdateComponent(
                     server_tick,
-                    *world_entity,
+                    world_entity,
                     component_kind,
+                    ComponentChanges {
+                        changed_properties,
+                        previous,
+                    },
                 )));
             }
         }
 
         Ok(())
     }
+
+    // Reads an update written as changes from a state of the Component which
+    // was received earlier, or the whole Component if there was none, and
+    // turns it into a regular update of the changed Properties. Returns None
+    // if that earlier state is no longer kept, skipping only this update
+    fn read_delta_update(
+        &mut self,
+        net_entity: &NetEntity,
+        packet_index: &PacketIndex,
+        reader: &mut BitReader,
+    ) -> Result<Option<ComponentUpdate<P::Kind>>, SerdeErr> {
+        let component_kind = P::Kind::de(reader)?;
+        let baselines = self.baselines.as_ref().unwrap();
+
+        let (state, diff_mask) = match Option::<PacketIndex>::de(reader)? {
+            Some(baseline_index) => {
+                let update_length = UnsignedVariableInteger::<5>::de(reader)?.get();
+                match baselines.baseline(net_entity, &component_kind, &baseline_index) {
+                    Some(baseline) => baseline.dyn_ref().read_update_delta(reader, self)?,
+                    None => {
+                        for _ in 0..update_length {
+                            bool::de(reader)?;
+                        }
+                        return Ok(None);
+                    }
+                }
+            }
+            None => {
+                let state = P::read(reader, self)?;
+                let mut diff_mask = DiffMask::new(state.dyn_ref().diff_mask_size());
+                for index in 0..diff_mask.byte_number() * 8 {
+                    diff_mask.set_bit(index, true);
+                }
+                (state, diff_mask)
+            }
+        };
+
+        let mut update_writer = BitWriter::new();
+        state
+            .dyn_ref()
+            .write_update(&diff_mask, &mut update_writer, self);
+        let (length, buffer) = update_writer.flush();
+
+        self.baselines
+            .as_mut()
+            .unwrap()
+            .insert(net_entity, &component_kind, packet_index, state);
+
+        Ok(Some(ComponentUpdate::new(
+            component_kind,
+            OwnedBitReader::new(&buffer[..length]),
+        )))
+    }
 }
 
 impl<P: Protocolize, E: Copy + Eq + Hash> EntityHandleConverter<E> for EntityManager<P, E> {
@@ -341,6 +570,9 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityHandleConverter<E> for EntityMan
     }
 
     fn entity_to_handle(&self, entity: &E) -> EntityHandle {
+        if let Some(entity_handle) = self.host_entities.get(entity) {
+            return *entity_handle;
+        }
         self.entity_records
             .get(entity)
             .expect("entity does not exist!")
@@ -348,21 +580,30 @@ impl<P: Protocolize, E: Copy + Eq + Hash> EntityHandleConverter<E> for EntityMan
     }
 }
 
+// Converts the Entities spawned by the Server, which are the remote host's
 impl<P: Protocolize, E: Copy + Eq + Hash> NetEntityHandleConverter for EntityManager<P, E> {
-    fn handle_to_net_entity(&self, entity_handle: &EntityHandle) -> NetEntity {
-        let entity = self
-            .handle_entity_map
-            .get(entity_handle)
-            .expect("no entity exists for the given handle!");
-        let entity_record = self.entity_records.get(entity).unwrap();
-        entity_record.net_entity
+    fn handle_to_net_entity(&self, entity_handle: &EntityHandle) -> Option<OwnedNetEntity> {
+        let entity = self.handle_entity_map.get(entity_handle)?;
+        self.net_entity(entity).map(OwnedNetEntity::Remote)
+    }
+
+    fn net_entity_to_handle(&self, net_entity: &OwnedNetEntity) -> Option<EntityHandle> {
+        match net_entity {
+            OwnedNetEntity::Host(_) => None,
+            OwnedNetEntity::Remote(net_entity) => {
+                let entity = self.entity(net_entity)?;
+                Some(self.entity_to_handle(&entity))
+            }
+        }
+    }
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash> NetEntityConverter<E> for EntityManager<P, E> {
+    fn entity_to_net_entity(&self, entity: &E) -> Option<NetEntity> {
+        self.net_entity(entity)
     }
 
-    fn net_entity_to_handle(&self, net_entity: &NetEntity) -> EntityHandle {
-        let entity = self
-            .local_to_world_entity
-            .get(net_entity)
-            .expect("no entity exists associated with given net entity");
-        self.entity_to_handle(entity)
+    fn net_entity_to_entity(&self, net_entity: &NetEntity) -> Option<E> {
+        self.entity(net_entity)
     }
 }
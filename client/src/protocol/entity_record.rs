@@ -2,18 +2,22 @@ use std::collections::HashSet;
 
 use naia_shared::{EntityHandle, NetEntity, ProtocolKindType};
 
-pub struct EntityRecord<K: ProtocolKindType> {
+pub struct EntityRecord<E, K: ProtocolKindType> {
     pub net_entity: NetEntity,
     pub component_kinds: HashSet<K>,
     pub entity_handle: EntityHandle,
+    pub parent: Option<E>,
+    pub children: HashSet<E>,
 }
 
-impl<K: ProtocolKindType> EntityRecord<K> {
+impl<E, K: ProtocolKindType> EntityRecord<E, K> {
     pub fn new(net_entity: NetEntity, entity_handle: EntityHandle) -> Self {
         EntityRecord {
             net_entity,
             component_kinds: HashSet::new(),
             entity_handle,
+            parent: None,
+            children: HashSet::new(),
         }
     }
 }
@@ -1,3 +1,4 @@
+pub mod authority_manager;
 pub mod entity_manager;
 pub mod entity_record;
 pub mod entity_ref;
@@ -0,0 +1,80 @@
+use std::{marker::PhantomData, time::Duration};
+
+use naia_shared::{
+    serde::Serde, Instant, PacketType, Protocolize, ServerInfo, StandardHeader, Timer, Timestamp,
+};
+
+use crate::{connection::io::Io, event::ConnectionFailure};
+
+/// A query for information about a Server, which does not establish a
+/// connection. Created with `Client::query_server()`
+pub struct ServerQuery<P: Protocolize> {
+    io: Io,
+    query_id: u64,
+    resend_timer: Timer,
+    timeout: Option<Duration>,
+    start: Instant,
+    phantom_p: PhantomData<P>,
+}
+
+impl<P: Protocolize> ServerQuery<P> {
+    pub(crate) fn new(io: Io, resend_interval: Duration, timeout: Option<Duration>) -> Self {
+        let mut resend_timer = Timer::new(resend_interval);
+        resend_timer.ring_manual();
+
+        Self {
+            io,
+            query_id: Timestamp::now(),
+            resend_timer,
+            timeout,
+            start: Instant::now(),
+            phantom_p: PhantomData,
+        }
+    }
+
+    /// Must call this regularly until it returns a result, which is either
+    /// the Server's info, or the reason the query failed
+    pub fn receive(&mut self) -> Option<Result<ServerInfo<P>, ConnectionFailure>> {
+        loop {
+            match self.io.recv_reader() {
+                Ok(Some(mut reader)) => {
+                    let header_result = StandardHeader::de(&mut reader);
+                    if header_result.is_err() {
+                        continue;
+                    }
+                    if header_result.unwrap().packet_type != PacketType::ServerInfoResponse {
+                        continue;
+                    }
+
+                    if let Ok((query_id, server_info)) = ServerInfo::read_response(&mut reader) {
+                        if query_id == self.query_id {
+                            return Some(Ok(server_info));
+                        }
+                    }
+                }
+                Ok(None) => {
+                    break;
+                }
+                Err(error) => {
+                    return Some(Err(ConnectionFailure::TransportError(error.to_string())));
+                }
+            }
+        }
+
+        if let Some(timeout) = self.timeout {
+            if self.start.elapsed() > timeout {
+                return Some(Err(ConnectionFailure::Timeout));
+            }
+        }
+
+        // resend query, in case it or the response was lost
+        if self.resend_timer.ringing() {
+            self.resend_timer.reset();
+
+            let mut writer = ServerInfo::<P>::write_request(self.query_id);
+            self.io.send_writer(&mut writer);
+        }
+
+        None
+    }
+}
@@ -6,7 +6,7 @@ use naia_shared::{
     message_list_header, sequence_greater_than, sequence_less_than,
     serde::{BitCounter, BitWrite, BitWriter, Serde, UnsignedVariableInteger},
     wrapping_diff, ChannelWriter, Instant, Protocolize, ShortMessageId, Tick, TickBufferSettings,
-    MESSAGE_HISTORY_SIZE, MTU_SIZE_BITS,
+    MESSAGE_HISTORY_SIZE,
 };
 
 pub struct ChannelTickBufferSender<P: Protocolize> {
@@ -85,7 +85,7 @@ impl<P: Protocolize> ChannelTickBufferSender<P> {
         {
             // Measure
             let current_packet_size = bit_writer.bit_count();
-            if current_packet_size > MTU_SIZE_BITS {
+            if current_packet_size > bit_writer.max_bits() {
                 message_list_header::write(bit_writer, 0);
                 return None;
             }
@@ -97,7 +97,7 @@ impl<P: Protocolize> ChannelTickBufferSender<P> {
             message_list_header::write(&mut counter, 123);
 
             // Check for overflow
-            if current_packet_size + counter.bit_count() > MTU_SIZE_BITS {
+            if current_packet_size + counter.bit_count() > bit_writer.max_bits() {
                 message_list_header::write(bit_writer, 0);
                 return None;
             }
@@ -119,7 +119,7 @@ impl<P: Protocolize> ChannelTickBufferSender<P> {
                     messages,
                 );
                 last_written_tick = *message_tick;
-                if current_packet_size + counter.bit_count() <= MTU_SIZE_BITS {
+                if current_packet_size + counter.bit_count() <= bit_writer.max_bits() {
                     message_count += 1;
                 } else {
                     break;
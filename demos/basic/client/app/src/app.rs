@@ -76,7 +76,7 @@ impl App {
                     // &string_message);
                     self.message_count += 1;
                 }
-                Ok(Event::SpawnEntity(entity)) => {
+                Ok(Event::SpawnEntity(entity, _)) => {
                     if let Some(character) = self
                         .client
                         .entity(self.world.proxy(), &entity)
@@ -91,7 +91,7 @@ impl App {
                         );
                     }
                 }
-                Ok(Event::UpdateComponent(_, entity, _)) => {
+                Ok(Event::UpdateComponent(_, entity, _, _)) => {
                     if let Some(character) = self
                         .client
                         .entity(self.world.proxy(), &entity)
@@ -1,5 +1,6 @@
 use bevy::{
     ecs::{
+        entity::Entity,
         event::EventReader,
         system::{Commands, Query, ResMut},
     },
@@ -41,11 +42,18 @@ pub fn disconnect_event(client: Client<Protocol, Channels>) {
     info!("Client disconnected from: {}", client.server_address());
 }
 
-pub fn spawn_entity_event(mut event_reader: EventReader<SpawnEntityEvent>) {
+pub fn spawn_entity_event(
+    mut event_reader: EventReader<SpawnEntityEvent<ProtocolKind>>,
+    mut local: Commands,
+    color_query: Query<&Color>,
+) {
     for event in event_reader.iter() {
-        match event {
-            SpawnEntityEvent(_entity) => {
-                info!("spawned entity");
+        let SpawnEntityEvent(entity, component_kinds) = event;
+        info!("spawned entity");
+
+        if component_kinds.contains(&ProtocolKind::Color) {
+            if let Ok(color) = color_query.get(*entity) {
+                add_sprite(&mut local, entity, color);
             }
         }
     }
@@ -59,32 +67,36 @@ pub fn insert_component_event(
     for event in event_reader.iter() {
         if let InsertComponentEvent(entity, ProtocolKind::Color) = event {
             if let Ok(color) = color_query.get(*entity) {
-                info!("add color to entity");
-
-                let color = {
-                    match *color.value {
-                        ColorValue::Red => BevyColor::RED,
-                        ColorValue::Blue => BevyColor::BLUE,
-                        ColorValue::Yellow => BevyColor::YELLOW,
-                    }
-                };
-
-                local.entity(*entity).insert(SpriteBundle {
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
-                        color,
-                        ..Default::default()
-                    },
-                    transform: Transform::from_xyz(0.0, 0.0, 0.0),
-                    ..Default::default()
-                });
+                add_sprite(&mut local, entity, color);
             }
         }
     }
 }
 
+fn add_sprite(local: &mut Commands, entity: &Entity, color: &Color) {
+    info!("add color to entity");
+
+    let color = {
+        match *color.value {
+            ColorValue::Red => BevyColor::RED,
+            ColorValue::Blue => BevyColor::BLUE,
+            ColorValue::Yellow => BevyColor::YELLOW,
+        }
+    };
+
+    local.entity(*entity).insert(SpriteBundle {
+        sprite: Sprite {
+            custom_size: Some(Vec2::new(SQUARE_SIZE, SQUARE_SIZE)),
+            color,
+            ..Default::default()
+        },
+        transform: Transform::from_xyz(0.0, 0.0, 0.0),
+        ..Default::default()
+    });
+}
+
 pub fn update_component_event(
-    mut event_reader: EventReader<UpdateComponentEvent<ProtocolKind>>,
+    mut event_reader: EventReader<UpdateComponentEvent<Protocol>>,
     mut global: ResMut<Global>,
     mut position_query: Query<&mut Position>,
 ) {
@@ -94,7 +106,7 @@ pub fn update_component_event(
         let client_entity = owned_entity.predicted;
 
         for event in event_reader.iter() {
-            let UpdateComponentEvent(server_tick, updated_entity, _) = event;
+            let UpdateComponentEvent(server_tick, updated_entity, _, _) = event;
 
             // If entity is owned
             if *updated_entity == server_entity {
@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
 use naia_shared::{
-    serde::SerdeErr, BigMap, ComponentUpdate, NetEntityHandleConverter, ProtocolInserter,
-    Protocolize, ReplicaDynMutWrapper, ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper,
-    Replicate, ReplicateSafe, WorldMutType, WorldRefType,
+    serde::SerdeErr, BigMap, ComponentUpdate, NetEntityHandleConverter, PropertyMutator,
+    ProtocolInserter, Protocolize, ReplicaDynMutWrapper, ReplicaDynRefWrapper, ReplicaMutWrapper,
+    ReplicaRefWrapper, Replicate, ReplicateSafe, WorldMutType, WorldRefType,
 };
 
 use super::{
@@ -199,6 +199,17 @@ impl<'w, P: Protocolize> WorldMutType<P, Entity> for WorldMut<'w, P> {
         Ok(())
     }
 
+    fn component_set_mutator(
+        &mut self,
+        entity: &Entity,
+        component_kind: &P::Kind,
+        mutator: &PropertyMutator,
+    ) {
+        if let Some(mut component) = component_mut_of_kind(self.world, entity, component_kind) {
+            component.set_mutator(mutator);
+        }
+    }
+
     fn mirror_entities(&mut self, new_entity: &Entity, old_entity: &Entity) {
         for component_kind in self.component_kinds(old_entity) {
             self.mirror_components(new_entity, old_entity, &component_kind);
@@ -4,9 +4,9 @@ mod inner {
     use std::marker::PhantomData;
 
     use naia_shared::{
-        serde::SerdeErr, ComponentUpdate, NetEntityHandleConverter, ProtocolInserter, Protocolize,
-        ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper, Replicate, ReplicateSafe,
-        WorldMutType, WorldRefType,
+        serde::SerdeErr, ComponentUpdate, NetEntityHandleConverter, PropertyMutator,
+        ProtocolInserter, Protocolize, ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper,
+        Replicate, ReplicateSafe, WorldMutType, WorldRefType,
     };
 
     pub type EmptyEntity = u8;
@@ -149,6 +149,15 @@ mod inner {
             unimplemented!()
         }
 
+        fn component_set_mutator(
+            &mut self,
+            _entity: &EmptyEntity,
+            _component_kind: &P::Kind,
+            _mutator: &PropertyMutator,
+        ) {
+            unimplemented!()
+        }
+
         fn mirror_entities(
             &mut self,
             _mutable_entity: &EmptyEntity,
@@ -13,7 +13,7 @@ pub fn process_events(app: &mut App) {
             Ok(Event::Disconnection(server_address)) => {
                 info!("Client disconnected from: {}", server_address);
             }
-            Ok(Event::SpawnEntity(entity)) => {
+            Ok(Event::SpawnEntity(entity, _)) => {
                 let new_id = app.next_id;
                 app.next_id = app.next_id.wrapping_add(1);
                 app.entity_to_id_map.insert(entity, new_id);
@@ -7,7 +7,7 @@ use naia_hecs_demo_shared::protocol::Protocol;
 use crate::app::App;
 
 pub fn process_events(app: &mut App) {
-    let events = app.server.receive();
+    let events = app.server.receive(&mut app.world);
     if events.is_empty() {
         // If we don't sleep here, app will loop at 100% CPU until a new message comes in
         sleep(Duration::from_millis(5));
@@ -19,6 +19,9 @@ pub fn march_and_mark(app: &mut App) {
     for (entity, position) in app.world.query_mut::<&mut Position>() {
         *position.x += 1;
 
+        // the position decides whether the entity is in scope
+        app.server.mark_entity_scope_dirty(&entity);
+
         if *position.x == 100 {
             entities_to_add.push(entity);
         }
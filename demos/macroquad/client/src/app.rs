@@ -141,7 +141,7 @@ impl App {
                         }
                     }
                 }
-                Ok(Event::SpawnEntity(entity)) => {
+                Ok(Event::SpawnEntity(entity, _)) => {
                     self.squares.insert(entity);
                     info!("spawned entity");
                 }
@@ -185,7 +185,7 @@ impl App {
                         }
                     }
                 }
-                Ok(Event::UpdateComponent(server_tick, updated_entity, _)) => {
+                Ok(Event::UpdateComponent(server_tick, updated_entity, _, _)) => {
                     if let Some(owned_entity) = &self.owned_entity {
                         let server_entity = owned_entity.confirmed;
 
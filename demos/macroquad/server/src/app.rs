@@ -56,7 +56,7 @@ impl App {
     }
 
     pub fn update(&mut self) {
-        let events = self.server.receive();
+        let events = self.server.receive(self.world.proxy_mut());
         if events.is_empty() {
             // If we don't sleep here, app will loop at 100% CPU until a new message comes in
             sleep(Duration::from_millis(5));
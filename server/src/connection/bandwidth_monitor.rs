@@ -28,11 +28,17 @@ impl BandwidthMonitor {
         self.client_monitors.remove(address);
     }
 
-    pub fn record_packet(&mut self, address: &SocketAddr, bytes: usize) {
+    pub fn record_compressed_packet(
+        &mut self,
+        address: &SocketAddr,
+        bytes: usize,
+        uncompressed_bytes: usize,
+    ) {
         if let Some(client_monitor) = self.client_monitors.get_mut(address) {
-            client_monitor.record_packet(bytes);
+            client_monitor.record_compressed_packet(bytes, uncompressed_bytes);
 
-            self.total_monitor.record_packet(bytes);
+            self.total_monitor
+                .record_compressed_packet(bytes, uncompressed_bytes);
         }
     }
 
@@ -46,4 +52,15 @@ impl BandwidthMonitor {
             .expect("client associated with address does not exist")
             .bandwidth()
     }
+
+    pub fn total_compression_ratio(&mut self) -> f32 {
+        self.total_monitor.compression_ratio()
+    }
+
+    pub fn client_compression_ratio(&mut self, address: &SocketAddr) -> f32 {
+        self.client_monitors
+            .get_mut(address)
+            .expect("client associated with address does not exist")
+            .compression_ratio()
+    }
 }
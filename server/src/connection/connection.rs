@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     hash::Hash,
     net::SocketAddr,
     sync::{Arc, RwLock},
@@ -6,16 +7,14 @@ use std::{
 
 use naia_shared::{
     sequence_greater_than,
-    serde::{BitReader, BitWriter, SerdeErr},
-    BaseConnection, ChannelConfig, ChannelIndex, ConnectionConfig, EntityConverter, HostType,
-    Instant, PacketType, PingManager, ProtocolIo, Protocolize, StandardHeader, Tick, WorldRefType,
+    serde::{BitReader, SerdeErr},
+    AuthorityChannel, BaseConnection, ChannelConfig, ChannelIndex, ConnectionConfig,
+    EntityConverter, GlobalDiffHandler, HostType, HostWorldManager, Instant, MtuConfig, MtuManager,
+    PacketType, PingManager, ProtocolIo, Protocolize, StandardHeader, Tick, WorldRefType,
 };
 
 use crate::{
-    protocol::{
-        entity_manager::EntityManager, global_diff_handler::GlobalDiffHandler,
-        world_record::WorldRecord,
-    },
+    protocol::{remote_entity_manager::RemoteEntityManager, world_record::WorldRecord},
     tick::{tick_buffer_receiver::TickBufferReceiver, tick_manager::TickManager},
     user::UserKey,
 };
@@ -25,16 +24,24 @@ use super::io::Io;
 pub struct Connection<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> {
     pub user_key: UserKey,
     pub base: BaseConnection<P, C>,
-    pub entity_manager: EntityManager<P, E, C>,
+    pub entity_manager: HostWorldManager<P, E, C>,
+    pub remote_entity_manager: RemoteEntityManager<P, E>,
+    pub authority_channel: AuthorityChannel,
+    /// Entities this User has been given authority over, which have not yet
+    /// been sent a Grant because the Client does not have them yet
+    pub pending_grants: HashSet<E>,
     pub tick_buffer: TickBufferReceiver<P, C>,
     pub last_received_tick: Tick,
     pub ping_manager: PingManager,
+    pub mtu_manager: MtuManager,
 }
 
 impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connection<P, E, C> {
     pub fn new(
         connection_config: &ConnectionConfig,
         channel_config: &ChannelConfig<C>,
+        mtu_config: &MtuConfig,
+        delta_compression: bool,
         user_address: SocketAddr,
         user_key: &UserKey,
         diff_handler: &Arc<RwLock<GlobalDiffHandler<E, P::Kind>>>,
@@ -47,9 +54,13 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
                 connection_config,
                 channel_config,
             ),
-            entity_manager: EntityManager::new(user_address, diff_handler),
+            entity_manager: HostWorldManager::new(user_address, diff_handler, delta_compression),
+            remote_entity_manager: RemoteEntityManager::default(),
+            authority_channel: AuthorityChannel::default(),
+            pending_grants: HashSet::new(),
             tick_buffer: TickBufferReceiver::new(channel_config),
             ping_manager: PingManager::new(&connection_config.ping),
+            mtu_manager: MtuManager::new(mtu_config),
             last_received_tick: 0,
         }
     }
@@ -57,8 +68,10 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
     // Incoming Data
 
     pub fn process_incoming_header(&mut self, header: &StandardHeader) {
-        self.base
-            .process_incoming_header(header, &mut Some(&mut self.entity_manager));
+        self.base.process_incoming_header(
+            header,
+            &mut [&mut self.entity_manager, &mut self.authority_channel],
+        );
     }
 
     pub fn recv_client_tick(&mut self, client_tick: Tick) {
@@ -75,7 +88,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
     ) -> Result<(), SerdeErr> {
         // Read Tick Buffered Messages
         if let Some((server_tick, client_tick)) = server_and_client_tick_opt {
-            let converter = EntityConverter::new(world_record, &self.entity_manager);
+            let converter = EntityConverter::new(world_record, &self.entity_manager)
+                .with_remote(&self.remote_entity_manager);
             let channel_reader = ProtocolIo::new(&converter);
             self.tick_buffer
                 .read_messages(&server_tick, &client_tick, &channel_reader, reader)?;
@@ -83,13 +97,23 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
 
         // Read Messages
         {
-            let converter = EntityConverter::new(world_record, &self.entity_manager);
+            let converter = EntityConverter::new(world_record, &self.entity_manager)
+                .with_remote(&self.remote_entity_manager);
             let channel_reader = ProtocolIo::new(&converter);
             self.base
                 .message_manager
                 .read_messages(&channel_reader, reader)?;
         }
 
+        // Read Entity Actions from the Client
+        self.remote_entity_manager
+            .read_all(world_record, &self.entity_manager, reader)?;
+
+        // Read Authority Actions, and updates to Entities the Client has
+        // authority over
+        self.authority_channel.read_actions(reader)?;
+        self.remote_entity_manager.read_delegated_updates(reader)?;
+
         Ok(())
     }
 
@@ -127,6 +151,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
         self.base
             .message_manager
             .collect_outgoing_messages(now, rtt_millis);
+        self.authority_channel
+            .collect_outgoing_actions(now, rtt_millis);
     }
 
     fn send_outgoing_packet<W: WorldRefType<P, E>>(
@@ -139,10 +165,11 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
     ) -> bool {
         if self.base.message_manager.has_outgoing_messages()
             || self.entity_manager.has_outgoing_messages()
+            || self.authority_channel.has_outgoing_actions()
         {
             let next_packet_index = self.base.next_packet_index();
 
-            let mut bit_writer = BitWriter::new();
+            let mut bit_writer = self.mtu_manager.packet_writer();
 
             // write header
             self.base
@@ -160,7 +187,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
 
             // write messages
             {
-                let converter = EntityConverter::new(world_record, &self.entity_manager);
+                let converter = EntityConverter::new(world_record, &self.entity_manager)
+                    .with_remote(&self.remote_entity_manager);
                 let channel_writer = ProtocolIo::new(&converter);
                 self.base.message_manager.write_messages(
                     &channel_writer,
@@ -176,8 +204,13 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Connect
                 &next_packet_index,
                 world,
                 world_record,
+                &self.remote_entity_manager,
             );
 
+            // write authority actions
+            self.authority_channel
+                .write_actions(&mut bit_writer, &next_packet_index);
+
             //info!("--------------\n");
 
             // send packet
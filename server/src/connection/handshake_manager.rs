@@ -4,9 +4,10 @@ use ring::{hmac, rand};
 
 pub use naia_shared::{
     serde::{BitReader, BitWriter, Serde, SerdeErr},
-    wrapping_diff, BaseConnection, ChannelIndex, ConnectionConfig, FakeEntityConverter, Instant,
-    KeyGenerator, PacketType, PropertyMutate, PropertyMutator, ProtocolKindType, Protocolize,
-    Replicate, ReplicateSafe, SharedConfig, StandardHeader, Timer, WorldMutType, WorldRefType,
+    wrapping_diff, BaseConnection, ChannelIndex, ConnectionConfig, DictionaryVersion,
+    FakeEntityConverter, Instant, KeyGenerator, PacketType, PropertyMutate, PropertyMutator,
+    ProtocolKindType, Protocolize, RejectReason, Replicate, ReplicateSafe, SharedConfig,
+    StandardHeader, Timer, WorldMutType, WorldRefType, PROTOCOL_VERSION,
 };
 
 use crate::cache_map::CacheMap;
@@ -46,14 +47,24 @@ impl<P: Protocolize> HandshakeManager<P> {
     pub fn recv_challenge_request(
         &mut self,
         reader: &mut BitReader,
+        dictionary_version: u8,
     ) -> Result<BitWriter, SerdeErr> {
         let timestamp = Timestamp::de(reader)?;
+        let protocol_version = u16::de(reader)?;
 
-        Ok(self.write_challenge_response(&timestamp))
+        if protocol_version != PROTOCOL_VERSION {
+            return Ok(self.write_reject_response(RejectReason::VersionMismatch));
+        }
+
+        Ok(self.write_challenge_response(&timestamp, dictionary_version))
     }
 
     // Step 2 of Handshake
-    pub fn write_challenge_response(&mut self, timestamp: &Timestamp) -> BitWriter {
+    pub fn write_challenge_response(
+        &mut self,
+        timestamp: &Timestamp,
+        dictionary_version: u8,
+    ) -> BitWriter {
         let mut writer = BitWriter::new();
         StandardHeader::new(PacketType::ServerChallengeResponse, 0, 0, 0).ser(&mut writer);
         timestamp.ser(&mut writer);
@@ -69,9 +80,24 @@ impl<P: Protocolize> HandshakeManager<P> {
             .get_unchecked(timestamp)
             .ser(&mut writer);
 
+        // write which compression dictionary the Client must fetch before
+        // connecting, if any
+        DictionaryVersion::new(dictionary_version).ser(&mut writer);
+
         writer
     }
 
+    // Optional step between 2 & 3 of Handshake, also used while connected
+    pub fn recv_dictionary_request(&self, reader: &mut BitReader) -> Option<(u8, u16)> {
+        // Only Clients which have completed the challenge may request dictionaries
+        self.timestamp_validate(reader)?;
+
+        let version = DictionaryVersion::de(reader).ok()?.get() as u8;
+        let next_chunk = u16::de(reader).ok()?;
+
+        Some((version, next_chunk))
+    }
+
     // Step 3 of Handshake
     pub fn recv_connect_request(
         &mut self,
@@ -132,9 +158,10 @@ impl<P: Protocolize> HandshakeManager<P> {
         false
     }
 
-    pub fn write_reject_response(&self) -> BitWriter {
+    pub fn write_reject_response(&self, reason: RejectReason) -> BitWriter {
         let mut writer = BitWriter::new();
         StandardHeader::new(PacketType::ServerRejectResponse, 0, 0, 0).ser(&mut writer);
+        reason.ser(&mut writer);
         writer
     }
 
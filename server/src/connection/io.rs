@@ -1,13 +1,13 @@
-use std::{net::SocketAddr, panic, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, panic, time::Duration};
 
 use naia_server_socket::{NaiaServerSocketError, PacketReceiver, PacketSender};
 
 pub use naia_shared::{
-    serde::{BitWriter, OwnedBitReader},
-    wrapping_diff, BaseConnection, CompressionConfig, ConnectionConfig, Decoder, Encoder, Instant,
-    KeyGenerator, PacketType, PropertyMutate, PropertyMutator, ProtocolKindType, Protocolize,
-    Replicate, ReplicateSafe, SharedConfig, StandardHeader, Timer, Timestamp, WorldMutType,
-    WorldRefType,
+    serde::{BitWrite, BitWriter, OwnedBitReader},
+    wrapping_diff, BaseConnection, CompressionConfig, CompressionDictionary, CompressionMode,
+    ConnectionConfig, Decoder, Encoder, Instant, KeyGenerator, PacketType, PropertyMutate,
+    PropertyMutator, ProtocolKindType, Protocolize, Replicate, ReplicateSafe, SharedConfig,
+    StandardHeader, Timer, Timestamp, WorldMutType, WorldRefType,
 };
 
 use super::bandwidth_monitor::BandwidthMonitor;
@@ -17,8 +17,20 @@ pub struct Io {
     packet_receiver: Option<PacketReceiver>,
     outgoing_bandwidth_monitor: Option<BandwidthMonitor>,
     incoming_bandwidth_monitor: Option<BandwidthMonitor>,
+    outgoing_compression_mode: Option<CompressionMode>,
+    incoming_compression_mode: Option<CompressionMode>,
+    // used for addresses which have not been registered, and for dictionary training
     outgoing_encoder: Option<Encoder>,
     incoming_decoder: Option<Decoder>,
+    // per-connection contexts, for registered clients
+    client_encoders: HashMap<SocketAddr, Encoder>,
+    client_decoders: HashMap<SocketAddr, Decoder>,
+    // current dictionary, if using CompressionMode::ServerDictionary
+    dictionary: Option<CompressionDictionary>,
+    // latest dictionary version each registered client has confirmed receiving
+    client_dictionary_versions: HashMap<SocketAddr, u8>,
+    // size of the last packet received, before decompression
+    last_received_length: usize,
 }
 
 impl Io {
@@ -29,26 +41,34 @@ impl Io {
         let outgoing_bandwidth_monitor = bandwidth_measure_duration.map(BandwidthMonitor::new);
         let incoming_bandwidth_monitor = bandwidth_measure_duration.map(BandwidthMonitor::new);
 
-        let outgoing_encoder = compression_config.as_ref().and_then(|config| {
-            config
-                .server_to_client
-                .as_ref()
-                .map(|mode| Encoder::new(mode.clone()))
-        });
-        let incoming_decoder = compression_config.as_ref().and_then(|config| {
-            config
-                .client_to_server
-                .as_ref()
-                .map(|mode| Decoder::new(mode.clone()))
-        });
+        let outgoing_compression_mode = compression_config
+            .as_ref()
+            .and_then(|config| config.server_to_client.clone());
+        let incoming_compression_mode = compression_config
+            .as_ref()
+            .and_then(|config| config.client_to_server.clone());
+
+        let outgoing_encoder = outgoing_compression_mode
+            .as_ref()
+            .map(|mode| Encoder::new(mode.clone()));
+        let incoming_decoder = incoming_compression_mode
+            .as_ref()
+            .map(|mode| Decoder::new(mode.clone()));
 
         Io {
             packet_sender: None,
             packet_receiver: None,
             outgoing_bandwidth_monitor,
             incoming_bandwidth_monitor,
+            outgoing_compression_mode,
+            incoming_compression_mode,
             outgoing_encoder,
             incoming_decoder,
+            client_encoders: HashMap::new(),
+            client_decoders: HashMap::new(),
+            dictionary: None,
+            client_dictionary_versions: HashMap::new(),
+            last_received_length: 0,
         }
     }
 
@@ -71,13 +91,15 @@ impl Io {
         let mut payload = &buffer[0..length];
 
         // Compression
-        if let Some(encoder) = &mut self.outgoing_encoder {
+        if let Some(encoder) = self.client_encoders.get_mut(address) {
+            payload = encoder.encode(payload);
+        } else if let Some(encoder) = &mut self.outgoing_encoder {
             payload = encoder.encode(payload);
         }
 
         // Bandwidth monitoring
         if let Some(monitor) = &mut self.outgoing_bandwidth_monitor {
-            monitor.record_packet(address, payload.len());
+            monitor.record_compressed_packet(address, payload.len(), length);
         }
 
         self.packet_sender
@@ -97,14 +119,19 @@ impl Io {
 
         match receive_result {
             Ok(Some((address, mut payload))) => {
-                // Bandwidth monitoring
-                if let Some(monitor) = &mut self.incoming_bandwidth_monitor {
-                    monitor.record_packet(&address, payload.len());
-                }
+                let received_length = payload.len();
+                self.last_received_length = received_length;
 
                 // Decompression
-                if let Some(decoder) = &mut self.incoming_decoder {
+                if let Some(decoder) = self.client_decoders.get_mut(&address) {
                     payload = decoder.decode(payload);
+                } else if let Some(decoder) = &mut self.incoming_decoder {
+                    payload = decoder.decode(payload);
+                }
+
+                // Bandwidth monitoring
+                if let Some(monitor) = &mut self.incoming_bandwidth_monitor {
+                    monitor.record_compressed_packet(&address, received_length, payload.len());
                 }
 
                 Ok(Some((address, OwnedBitReader::new(payload))))
@@ -114,32 +141,167 @@ impl Io {
         }
     }
 
-    pub fn bandwidth_monitor_enabled(&self) -> bool {
-        self.outgoing_bandwidth_monitor.is_some() && self.incoming_bandwidth_monitor.is_some()
+    /// Size of the last packet returned by `recv_reader()`, as it was
+    /// received, before decompression
+    pub fn last_received_length(&self) -> usize {
+        self.last_received_length
+    }
+
+    /// Size of a packet as it will be sent, after compression, at most
+    pub fn max_sent_length(&self, writer: &BitWriter) -> usize {
+        let length = (writer.bit_count() as usize).div_ceil(8);
+        // the compression flag byte
+        if self.outgoing_encoder.is_some() {
+            length + 1
+        } else {
+            length
+        }
     }
 
     pub fn register_client(&mut self, address: &SocketAddr) {
-        self.outgoing_bandwidth_monitor
-            .as_mut()
-            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
-            .create_client(address);
-        self.incoming_bandwidth_monitor
-            .as_mut()
-            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
-            .create_client(address);
+        if let Some(monitor) = &mut self.outgoing_bandwidth_monitor {
+            monitor.create_client(address);
+        }
+        if let Some(monitor) = &mut self.incoming_bandwidth_monitor {
+            monitor.create_client(address);
+        }
+
+        // Each connection gets its own compression contexts, except while
+        // training, where all samples need to end up in the same dictionary
+        if let Some(mode) = &self.outgoing_compression_mode {
+            if !matches!(mode, CompressionMode::Training(_)) {
+                self.client_encoders
+                    .insert(*address, Encoder::new(mode.clone()));
+            }
+        }
+        if let Some(mode) = &self.incoming_compression_mode {
+            if !matches!(mode, CompressionMode::Training(_)) {
+                let mut decoder = Decoder::new(mode.clone());
+                if let Some(dictionary) = &self.dictionary {
+                    decoder.add_dictionary(dictionary);
+                }
+                self.client_decoders.insert(*address, decoder);
+            }
+        }
+        self.client_dictionary_versions.insert(*address, 0);
     }
 
     pub fn deregister_client(&mut self, address: &SocketAddr) {
-        self.outgoing_bandwidth_monitor
-            .as_mut()
-            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
-            .delete_client(address);
-        self.incoming_bandwidth_monitor
+        if let Some(monitor) = &mut self.outgoing_bandwidth_monitor {
+            monitor.delete_client(address);
+        }
+        if let Some(monitor) = &mut self.incoming_bandwidth_monitor {
+            monitor.delete_client(address);
+        }
+
+        self.client_encoders.remove(address);
+        self.client_decoders.remove(address);
+        self.client_dictionary_versions.remove(address);
+    }
+
+    // Compression Dictionaries
+
+    /// Replaces the current compression dictionary, returning its version.
+    /// Incoming packets can be decompressed with it right away, while each
+    /// Client's outgoing packets switch over once that Client confirms that it
+    /// has received the whole dictionary.
+    pub fn set_dictionary(&mut self, bytes: Vec<u8>) -> u8 {
+        let uses_server_dictionary = [
+            &self.outgoing_compression_mode,
+            &self.incoming_compression_mode,
+        ]
+        .iter()
+        .any(|mode| matches!(mode, Some(CompressionMode::ServerDictionary(_))));
+        if !uses_server_dictionary {
+            panic!("Setting a compression dictionary requires `CompressionMode::ServerDictionary` to be configured");
+        }
+
+        let version = self
+            .dictionary
+            .as_ref()
+            .map(|dictionary| dictionary.next_version())
+            .unwrap_or(1);
+        let dictionary = CompressionDictionary::new(version, bytes);
+
+        if let Some(decoder) = &mut self.incoming_decoder {
+            decoder.add_dictionary(&dictionary);
+        }
+        for decoder in self.client_decoders.values_mut() {
+            decoder.add_dictionary(&dictionary);
+        }
+
+        self.dictionary = Some(dictionary);
+
+        version
+    }
+
+    /// Version of the current compression dictionary, or 0 if there is none
+    pub fn dictionary_version(&self) -> u8 {
+        self.dictionary
+            .as_ref()
+            .map(|dictionary| dictionary.version)
+            .unwrap_or(0)
+    }
+
+    /// Handles a Client's request for the next chunk of the current dictionary.
+    /// Returns the packet to send back, if any.
+    pub fn recv_dictionary_request(
+        &mut self,
+        address: &SocketAddr,
+        version: u8,
+        next_chunk: u16,
+    ) -> Option<BitWriter> {
+        let dictionary = self.dictionary.as_ref()?;
+
+        if version != dictionary.version {
+            // Client is behind, start it on the current dictionary
+            return Some(dictionary.write_chunk(0));
+        }
+        if next_chunk < dictionary.chunk_count() {
+            return Some(dictionary.write_chunk(next_chunk));
+        }
+
+        // Client has the whole dictionary, this packet index is where the
+        // Server starts compressing with it
+        if let Some(acked_version) = self.client_dictionary_versions.get_mut(address) {
+            *acked_version = dictionary.version;
+            if let Some(encoder) = self.client_encoders.get_mut(address) {
+                encoder.set_dictionary(dictionary);
+            }
+        }
+        None
+    }
+
+    /// Sends the first chunk of the current dictionary to every Client which
+    /// hasn't confirmed having it yet
+    pub fn send_dictionary_announcements(&mut self) {
+        let dictionary = match &self.dictionary {
+            Some(dictionary) => dictionary,
+            None => return,
+        };
+
+        let outgoing: Vec<(SocketAddr, BitWriter)> = self
+            .client_dictionary_versions
+            .iter()
+            .filter(|(_, version)| **version != dictionary.version)
+            .map(|(address, _)| (*address, dictionary.write_chunk(0)))
+            .collect();
+
+        for (address, mut writer) in outgoing {
+            self.send_writer(&address, &mut writer);
+        }
+    }
+
+    /// Returns the dictionary trained while in `CompressionMode::Training`,
+    /// once enough outgoing packets have been sampled
+    pub fn take_trained_dictionary(&mut self) -> Option<Vec<u8>> {
+        self.outgoing_encoder
             .as_mut()
-            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
-            .delete_client(address);
+            .and_then(|encoder| encoder.take_trained_dictionary())
     }
 
+    // Bandwidth
+
     pub fn outgoing_bandwidth_total(&mut self) -> f32 {
         return self
             .outgoing_bandwidth_monitor
@@ -165,10 +327,37 @@ impl Io {
     }
 
     pub fn incoming_bandwidth_from_client(&mut self, address: &SocketAddr) -> f32 {
-        return self
-            .incoming_bandwidth_monitor
+        self.incoming_bandwidth_monitor
             .as_mut()
             .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
-            .client_bandwidth(address);
+            .client_bandwidth(address)
+    }
+
+    pub fn outgoing_compression_ratio_total(&mut self) -> f32 {
+        self.outgoing_bandwidth_monitor
+            .as_mut()
+            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
+            .total_compression_ratio()
+    }
+
+    pub fn incoming_compression_ratio_total(&mut self) -> f32 {
+        self.incoming_bandwidth_monitor
+            .as_mut()
+            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
+            .total_compression_ratio()
+    }
+
+    pub fn outgoing_compression_ratio_to_client(&mut self, address: &SocketAddr) -> f32 {
+        self.outgoing_bandwidth_monitor
+            .as_mut()
+            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
+            .client_compression_ratio(address)
+    }
+
+    pub fn incoming_compression_ratio_from_client(&mut self, address: &SocketAddr) -> f32 {
+        self.incoming_bandwidth_monitor
+            .as_mut()
+            .expect("Need to call `enable_bandwidth_monitor()` on Io before calling this")
+            .client_compression_ratio(address)
     }
 }
@@ -3,3 +3,5 @@ pub mod bandwidth_monitor;
 pub mod connection;
 pub mod handshake_manager;
 pub mod io;
+pub mod rate_limit_config;
+pub mod rate_limiter;
@@ -0,0 +1,30 @@
+use std::default::Default;
+
+/// Contains Config properties which control how many packets of a given kind
+/// are accepted from a single IP address, using a token bucket
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    /// The number of packets which can be accepted in a quick burst
+    pub burst_size: u32,
+    /// The number of packets per second which can be accepted over time
+    pub refill_per_second: f32,
+}
+
+impl RateLimitConfig {
+    /// Creates a new RateLimitConfig
+    pub fn new(burst_size: u32, refill_per_second: f32) -> Self {
+        RateLimitConfig {
+            burst_size,
+            refill_per_second,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            burst_size: 10,
+            refill_per_second: 5.0,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::{collections::HashMap, net::IpAddr, time::Duration};
+
+use naia_shared::{Instant, Timer};
+
+use super::rate_limit_config::RateLimitConfig;
+
+struct TokenBucket {
+    tokens: f32,
+    last_refill: Instant,
+}
+
+/// Keeps a token bucket for each IP address packets are received from
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: HashMap<IpAddr, TokenBucket>,
+    cleanup_timer: Timer,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        // after this long, an idle bucket is full again and can be forgotten
+        let refill_duration =
+            Duration::from_secs_f32(config.burst_size as f32 / config.refill_per_second.max(0.001));
+
+        RateLimiter {
+            config: config.clone(),
+            buckets: HashMap::new(),
+            cleanup_timer: Timer::new(refill_duration),
+        }
+    }
+
+    /// Returns whether a packet from the given address should be accepted,
+    /// consuming a token if so
+    pub fn allow(&mut self, address: &IpAddr) -> bool {
+        if self.cleanup_timer.ringing() {
+            self.cleanup_timer.reset();
+            self.cleanup();
+        }
+
+        let burst_size = self.config.burst_size as f32;
+        let bucket = self.buckets.entry(*address).or_insert_with(|| TokenBucket {
+            tokens: burst_size,
+            last_refill: Instant::now(),
+        });
+
+        let refill = bucket.last_refill.elapsed().as_secs_f32() * self.config.refill_per_second;
+        bucket.tokens = (bucket.tokens + refill).min(burst_size);
+        bucket.last_refill = Instant::now();
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    // Forget about addresses which would have a full bucket by now
+    fn cleanup(&mut self) {
+        let burst_size = self.config.burst_size as f32;
+        let refill_per_second = self.config.refill_per_second;
+        self.buckets.retain(|_, bucket| {
+            bucket.tokens + bucket.last_refill.elapsed().as_secs_f32() * refill_per_second
+                < burst_size
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use super::RateLimiter;
+    use crate::RateLimitConfig;
+
+    #[test]
+    fn limits_each_address_separately() {
+        let mut limiter = RateLimiter::new(&RateLimitConfig::new(3, 0.0));
+        let first = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let second = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        for _ in 0..3 {
+            assert!(limiter.allow(&first));
+        }
+        assert!(!limiter.allow(&first));
+        assert!(limiter.allow(&second));
+    }
+}
@@ -1,10 +1,12 @@
+use std::hash::Hash;
+
 use naia_shared::{ChannelIndex, Protocolize};
 
 use super::user::{User, UserKey};
 
 /// An Event that is emitted as a result of some communication with a Client, or
 /// a Tick event
-pub enum Event<P: Protocolize, C: ChannelIndex> {
+pub enum Event<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> {
     /// Occurs when a Client attempts to establish a connection with the Server.
     /// Used accept or reject incoming Clients
     Authorization(UserKey, P),
@@ -20,4 +22,36 @@ pub enum Event<P: Protocolize, C: ChannelIndex> {
     Tick,
     /// A Message emitted to the Server from a Client
     Message(UserKey, C, P),
+    /// Occurs when a Message sent with a time-to-live or deadline Tick could
+    /// not be delivered to the Client in time, and has been dropped
+    MessageExpired(UserKey, C, P),
+    /// Occurs when a Client spawns an Entity of its own, which now exists in
+    /// the Server's World along with every Component it was spawned with,
+    /// the kinds of which are given. The Entity is not replicated to other
+    /// Clients until it is added to a Room, and it can be rejected by
+    /// despawning it
+    SpawnEntity(UserKey, E, Vec<P::Kind>),
+    /// Occurs when a Client despawns an Entity it owns, which has already
+    /// been removed from the Server's World
+    DespawnEntity(UserKey, E),
+    /// Occurs when a Client inserts a Component into an Entity it owns. The
+    /// Component can be rejected by removing it
+    InsertComponent(UserKey, E, P::Kind),
+    /// Occurs when a Client removes a Component from an Entity it owns, which
+    /// has already been removed from the Server's World
+    RemoveComponent(UserKey, E, P),
+    /// Occurs when a Client updates a Component of an Entity it owns
+    UpdateComponent(UserKey, E, P::Kind),
+    /// Occurs when a Client asks for authority over an Entity spawned by the
+    /// Server. It can be given with `EntityMut::give_authority()`
+    AuthorityRequested(UserKey, E),
+    /// Occurs when a Client has received authority over an Entity, and its
+    /// changes to the Entity's Components will now be applied on the Server
+    AuthorityGranted(UserKey, E),
+    /// Occurs when a Client no longer has authority over an Entity, because
+    /// it was taken back by the Server or released by the Client
+    AuthorityRevoked(UserKey, E),
+    /// Occurs when a Client disconnects while having authority over an Entity,
+    /// which the Server now has authority over again
+    AuthorityLost(UserKey, E),
 }
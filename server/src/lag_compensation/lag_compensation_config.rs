@@ -0,0 +1,25 @@
+use std::default::Default;
+
+/// Contains Config properties which control how far back the Server keeps
+/// the states of lag compensated Components, and how it works out which of
+/// them a Client was rendering
+#[derive(Clone, Debug)]
+pub struct LagCompensationConfig {
+    /// The number of ticks of history kept. Queries for older ticks are
+    /// answered with the oldest states kept, which limits how far back a
+    /// Client with a very high latency can rewind the World
+    pub history_ticks: u16,
+    /// How many ticks behind their receiving tick that Clients render
+    /// interpolated Components at. Should match the `delay_ticks` of the
+    /// Clients' interpolation config
+    pub interpolation_delay_ticks: u16,
+}
+
+impl Default for LagCompensationConfig {
+    fn default() -> Self {
+        Self {
+            history_ticks: 32,
+            interpolation_delay_ticks: 2,
+        }
+    }
+}
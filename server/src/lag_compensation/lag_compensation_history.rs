@@ -0,0 +1,167 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use naia_shared::{sequence_greater_than, ProtocolKindType, Tick};
+
+/// Keeps the states of chosen Components on each Entity for the most recent
+/// ticks, so that the World can be rewound to what a Client saw
+pub struct LagCompensationHistory<E: Copy + Eq + Hash, K: ProtocolKindType, S> {
+    history_ticks: usize,
+    kinds: HashSet<K>,
+    #[allow(clippy::type_complexity)]
+    ticks: VecDeque<(Tick, HashMap<(E, K), S>)>,
+}
+
+impl<E: Copy + Eq + Hash, K: ProtocolKindType, S> LagCompensationHistory<E, K, S> {
+    pub fn new(history_ticks: u16) -> Self {
+        Self {
+            history_ticks: history_ticks.max(1) as usize,
+            kinds: HashSet::new(),
+            ticks: VecDeque::new(),
+        }
+    }
+
+    pub fn enable(&mut self, component_kind: &K) {
+        self.kinds.insert(*component_kind);
+    }
+
+    pub fn is_enabled(&self, component_kind: &K) -> bool {
+        self.kinds.contains(component_kind)
+    }
+
+    pub fn has_kinds(&self) -> bool {
+        !self.kinds.is_empty()
+    }
+
+    /// Records the states of the Components at the given tick, replacing any
+    /// recorded for the same tick
+    pub fn record(&mut self, tick: Tick, states: HashMap<(E, K), S>) {
+        if let Some((last_tick, _)) = self.ticks.back() {
+            if *last_tick == tick {
+                self.ticks.pop_back();
+            }
+        }
+        self.ticks.push_back((tick, states));
+        while self.ticks.len() > self.history_ticks {
+            self.ticks.pop_front();
+        }
+    }
+
+    /// Returns the state of an Entity's Component at the given tick, or at
+    /// the closest tick recorded to it
+    pub fn state(&self, entity: &E, component_kind: &K, tick: Tick) -> Option<&S> {
+        self.states_at(tick)?.get(&(*entity, *component_kind))
+    }
+
+    /// Returns the states of every Component of the given kind at the given
+    /// tick, or at the closest tick recorded to it
+    pub fn states(&self, component_kind: &K, tick: Tick) -> Vec<(E, &S)> {
+        match self.states_at(tick) {
+            Some(states) => states
+                .iter()
+                .filter(|((_, state_kind), _)| state_kind == component_kind)
+                .map(|((entity, _), state)| (*entity, state))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // the states of the most recent tick at or before the given one, or of
+    // the oldest tick if it is older than every tick recorded
+    fn states_at(&self, tick: Tick) -> Option<&HashMap<(E, K), S>> {
+        self.ticks
+            .iter()
+            .rev()
+            .find(|(recorded_tick, _)| !sequence_greater_than(*recorded_tick, tick))
+            .or_else(|| self.ticks.front())
+            .map(|(_, states)| states)
+    }
+}
+
+/// Returns the Server tick whose states a Client was rendering when it sent
+/// something at the given Client tick. The Client sends ahead of the Server
+/// by its round trip time plus a jitter buffer, and receives behind it by
+/// another jitter buffer, on top of which interpolated Components are
+/// rendered a further delay behind
+///
+/// Assumes the Client sends ahead by exactly its round trip time plus jitter
+/// buffer. A Client whose `ClientConfig::minimum_latency` is larger than that
+/// sends further ahead, and so is found to be rendering a later tick than it
+/// really was
+pub fn rendered_tick(
+    client_tick: Tick,
+    rtt_millis: f32,
+    jitter_millis: f32,
+    tick_interval_millis: f32,
+    interpolation_delay_ticks: u16,
+) -> Tick {
+    // mirrors the jitter buffer applied by the Client's tick manager
+    let jitter_limit = jitter_millis * 4.0;
+    let behind_ticks = ((rtt_millis + (jitter_limit * 2.0)) / tick_interval_millis).round() as u16;
+    client_tick
+        .wrapping_sub(behind_ticks)
+        .wrapping_sub(interpolation_delay_ticks)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{any::TypeId, collections::HashMap};
+
+    use naia_shared::{derive_serde, serde, ProtocolKindType};
+
+    use super::{rendered_tick, LagCompensationHistory};
+
+    #[derive(Copy, Eq, Hash)]
+    #[derive_serde]
+    enum TestKind {
+        Position,
+        Rotation,
+    }
+
+    impl ProtocolKindType for TestKind {
+        fn to_type_id(&self) -> TypeId {
+            TypeId::of::<()>()
+        }
+    }
+
+    fn record(history: &mut LagCompensationHistory<u32, TestKind, u16>, tick: u16, x: u16) {
+        let mut states = HashMap::new();
+        states.insert((7, TestKind::Position), x);
+        states.insert((7, TestKind::Rotation), x + 100);
+        history.record(tick, states);
+    }
+
+    #[test]
+    fn states_are_found_at_the_closest_tick_recorded() {
+        let mut history = LagCompensationHistory::<u32, TestKind, u16>::new(3);
+        assert!(history.state(&7, &TestKind::Position, 0).is_none());
+
+        record(&mut history, 10, 1);
+        record(&mut history, 11, 2);
+        record(&mut history, 13, 3);
+
+        assert_eq!(history.state(&7, &TestKind::Position, 11), Some(&2));
+        assert_eq!(history.state(&7, &TestKind::Position, 12), Some(&2));
+        assert_eq!(history.state(&7, &TestKind::Rotation, 12), Some(&102));
+        // too recent or too old ticks are clamped to the history kept
+        assert_eq!(history.state(&7, &TestKind::Position, 20), Some(&3));
+        assert_eq!(history.state(&7, &TestKind::Position, 5), Some(&1));
+        assert!(history.state(&8, &TestKind::Position, 11).is_none());
+
+        // the oldest tick is forgotten once the history is full
+        record(&mut history, 14, 4);
+        assert_eq!(history.state(&7, &TestKind::Position, 10), Some(&2));
+        assert_eq!(history.states(&TestKind::Position, 14), vec![(7, &4)]);
+    }
+
+    #[test]
+    fn rendered_tick_accounts_for_latency_and_interpolation() {
+        // 100ms round trip at 50ms per tick, without jitter
+        assert_eq!(rendered_tick(100, 100.0, 0.0, 50.0, 2), 96);
+        // jitter buffers on both ends
+        assert_eq!(rendered_tick(100, 100.0, 12.5, 50.0, 0), 96);
+        assert_eq!(rendered_tick(1, 100.0, 0.0, 50.0, 2), 65533);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod lag_compensation_config;
+pub mod lag_compensation_history;
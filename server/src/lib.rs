@@ -20,21 +20,25 @@ mod cache_map;
 mod connection;
 mod error;
 mod event;
+mod lag_compensation;
 mod protocol;
 mod room;
-mod sequence_list;
 mod server;
 mod server_config;
+mod spatial_scope;
 mod tick;
 mod user;
 mod user_scope;
 
+pub use connection::rate_limit_config::RateLimitConfig;
 pub use error::NaiaServerError;
 pub use event::Event;
+pub use lag_compensation::lag_compensation_config::LagCompensationConfig;
 pub use protocol::entity_ref::EntityRef;
 pub use room::{RoomKey, RoomMut, RoomRef};
 pub use server::Server;
 pub use server_config::ServerConfig;
+pub use spatial_scope::spatial_scope_config::SpatialScopeConfig;
 pub use user::{User, UserKey, UserMut, UserRef};
 pub use user_scope::UserScopeMut;
 
@@ -0,0 +1,47 @@
+use std::hash::Hash;
+
+use naia_shared::{ChannelIndex, ProtocolInserter, Protocolize, ReplicateSafe, WorldMutType};
+
+use crate::Server;
+
+/// Inserts Components received from a Client into the Server's World, making
+/// sure they are registered for replication like any other Component
+pub struct ComponentInserter<
+    's,
+    'w,
+    P: Protocolize,
+    E: Copy + Eq + Hash + Send + Sync,
+    C: ChannelIndex,
+    W: WorldMutType<P, E>,
+> {
+    server: &'s mut Server<P, E, C>,
+    world: &'w mut W,
+}
+
+impl<
+        's,
+        'w,
+        P: Protocolize,
+        E: Copy + Eq + Hash + Send + Sync,
+        C: ChannelIndex,
+        W: WorldMutType<P, E>,
+    > ComponentInserter<'s, 'w, P, E, C, W>
+{
+    pub fn new(server: &'s mut Server<P, E, C>, world: &'w mut W) -> Self {
+        Self { server, world }
+    }
+}
+
+impl<
+        's,
+        'w,
+        P: Protocolize,
+        E: Copy + Eq + Hash + Send + Sync,
+        C: ChannelIndex,
+        W: WorldMutType<P, E>,
+    > ProtocolInserter<P, E> for ComponentInserter<'s, 'w, P, E, C, W>
+{
+    fn insert<R: ReplicateSafe<P>>(&mut self, entity: &E, component: R) {
+        self.server.insert_component(self.world, entity, component);
+    }
+}
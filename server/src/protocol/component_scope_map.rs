@@ -0,0 +1,250 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use naia_shared::{DiffMask, ProtocolKindType};
+
+use crate::user::UserKey;
+
+pub type ComponentScopePredicate<E> = Box<dyn Fn(&UserKey, &E) -> bool + Send + Sync>;
+
+/// Keeps track of which Components of an in-scope Entity, and which of their
+/// Properties, each User is allowed to see
+pub struct ComponentScopeMap<E: Copy + Eq + Hash, K: ProtocolKindType> {
+    excluded_components: HashMap<(UserKey, E), HashSet<K>>,
+    predicates: HashMap<K, ComponentScopePredicate<E>>,
+    property_predicates: HashMap<K, ComponentScopePredicate<E>>,
+}
+
+impl<E: Copy + Eq + Hash, K: ProtocolKindType> ComponentScopeMap<E, K> {
+    pub fn new() -> Self {
+        Self {
+            excluded_components: HashMap::new(),
+            predicates: HashMap::new(),
+            property_predicates: HashMap::new(),
+        }
+    }
+
+    /// A Component is visible to a User unless it has been excluded for them,
+    /// or the predicate registered for its kind rejects them
+    pub fn is_visible(&self, user_key: &UserKey, entity: &E, component_kind: &K) -> bool {
+        if let Some(excluded) = self.excluded_components.get(&(*user_key, *entity)) {
+            if excluded.contains(component_kind) {
+                return false;
+            }
+        }
+        match self.predicates.get(component_kind) {
+            Some(predicate) => predicate(user_key, entity),
+            None => true,
+        }
+    }
+
+    pub fn set_excluded(
+        &mut self,
+        user_key: &UserKey,
+        entity: &E,
+        component_kind: &K,
+        is_excluded: bool,
+    ) {
+        let key = (*user_key, *entity);
+        if is_excluded {
+            self.excluded_components
+                .entry(key)
+                .or_default()
+                .insert(*component_kind);
+        } else if let Some(excluded) = self.excluded_components.get_mut(&key) {
+            excluded.remove(component_kind);
+            if excluded.is_empty() {
+                self.excluded_components.remove(&key);
+            }
+        }
+    }
+
+    pub fn set_predicate(&mut self, component_kind: &K, predicate: ComponentScopePredicate<E>) {
+        self.predicates.insert(*component_kind, predicate);
+    }
+
+    pub fn remove_predicate(&mut self, component_kind: &K) {
+        self.predicates.remove(component_kind);
+    }
+
+    /// Returns which Properties of a Component are hidden from a User, given
+    /// the Component's owner-only and conditional Properties. Conditional
+    /// Properties are only visible to Users which the predicate registered for
+    /// the Component's kind accepts
+    pub fn hidden_properties(
+        &self,
+        user_key: &UserKey,
+        entity: &E,
+        component_kind: &K,
+        is_owner: bool,
+        owner_only: &DiffMask,
+        conditional: &DiffMask,
+    ) -> Option<DiffMask> {
+        let mut hidden = DiffMask::new(owner_only.byte_number());
+
+        if !is_owner {
+            hidden.or(owner_only);
+        }
+
+        if !conditional.is_clear() {
+            let is_visible = match self.property_predicates.get(component_kind) {
+                Some(predicate) => predicate(user_key, entity),
+                None => false,
+            };
+            if !is_visible {
+                hidden.or(conditional);
+            }
+        }
+
+        if hidden.is_clear() {
+            None
+        } else {
+            Some(hidden)
+        }
+    }
+
+    pub fn set_property_predicate(
+        &mut self,
+        component_kind: &K,
+        predicate: ComponentScopePredicate<E>,
+    ) {
+        self.property_predicates.insert(*component_kind, predicate);
+    }
+
+    pub fn remove_property_predicate(&mut self, component_kind: &K) {
+        self.property_predicates.remove(component_kind);
+    }
+
+    pub fn remove_user(&mut self, user_key: &UserKey) {
+        self.excluded_components
+            .retain(|(excluded_user, _), _| excluded_user != user_key);
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        self.excluded_components
+            .retain(|(_, excluded_entity), _| excluded_entity != entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use naia_shared::{derive_serde, serde, BigMapKey, DiffMask, ProtocolKindType};
+
+    use crate::user::UserKey;
+
+    use super::ComponentScopeMap;
+
+    #[derive(Copy, Eq, Hash)]
+    #[derive_serde]
+    enum TestKind {
+        Position,
+        Inventory,
+    }
+
+    impl ProtocolKindType for TestKind {
+        fn to_type_id(&self) -> TypeId {
+            TypeId::of::<()>()
+        }
+    }
+
+    #[test]
+    fn excluded_components_are_hidden_from_that_user_only() {
+        let mut scope_map = ComponentScopeMap::<u32, TestKind>::new();
+        let user_a = UserKey::from_u64(0);
+        let user_b = UserKey::from_u64(1);
+
+        scope_map.set_excluded(&user_a, &7, &TestKind::Inventory, true);
+        assert!(!scope_map.is_visible(&user_a, &7, &TestKind::Inventory));
+        assert!(scope_map.is_visible(&user_a, &7, &TestKind::Position));
+        assert!(scope_map.is_visible(&user_b, &7, &TestKind::Inventory));
+
+        scope_map.set_excluded(&user_a, &7, &TestKind::Inventory, false);
+        assert!(scope_map.is_visible(&user_a, &7, &TestKind::Inventory));
+    }
+
+    #[test]
+    fn predicate_decides_visibility_for_its_kind() {
+        let mut scope_map = ComponentScopeMap::<u32, TestKind>::new();
+        let owner = UserKey::from_u64(0);
+        let other = UserKey::from_u64(1);
+
+        scope_map.set_predicate(
+            &TestKind::Inventory,
+            Box::new(move |user_key, entity| *user_key == owner && *entity == 7),
+        );
+        assert!(scope_map.is_visible(&owner, &7, &TestKind::Inventory));
+        assert!(!scope_map.is_visible(&other, &7, &TestKind::Inventory));
+        assert!(scope_map.is_visible(&other, &7, &TestKind::Position));
+
+        scope_map.remove_predicate(&TestKind::Inventory);
+        assert!(scope_map.is_visible(&other, &7, &TestKind::Inventory));
+    }
+
+    #[test]
+    fn owner_only_and_conditional_properties_are_hidden() {
+        let mut scope_map = ComponentScopeMap::<u32, TestKind>::new();
+        let owner = UserKey::from_u64(0);
+        let other = UserKey::from_u64(1);
+
+        let mut owner_only = DiffMask::new(1);
+        owner_only.set_bit(1, true);
+        let mut conditional = DiffMask::new(1);
+        conditional.set_bit(2, true);
+
+        // without a predicate, conditional Properties are hidden from everyone
+        let hidden = scope_map
+            .hidden_properties(
+                &owner,
+                &7,
+                &TestKind::Position,
+                true,
+                &owner_only,
+                &conditional,
+            )
+            .unwrap();
+        assert_eq!(hidden.bit(1), Some(false));
+        assert_eq!(hidden.bit(2), Some(true));
+
+        scope_map.set_property_predicate(
+            &TestKind::Position,
+            Box::new(move |user_key, _| *user_key == other),
+        );
+        assert!(scope_map
+            .hidden_properties(
+                &owner,
+                &7,
+                &TestKind::Position,
+                true,
+                &owner_only,
+                &conditional
+            )
+            .is_some());
+        let hidden = scope_map
+            .hidden_properties(
+                &other,
+                &7,
+                &TestKind::Position,
+                false,
+                &owner_only,
+                &conditional,
+            )
+            .unwrap();
+        assert_eq!(hidden.bit(1), Some(true));
+        assert_eq!(hidden.bit(2), Some(false));
+
+        assert!(scope_map
+            .hidden_properties(
+                &other,
+                &7,
+                &TestKind::Position,
+                true,
+                &owner_only,
+                &conditional
+            )
+            .is_none());
+    }
+}
@@ -5,7 +5,7 @@ use naia_shared::{
     WorldMutType, WorldRefType,
 };
 
-use crate::{room::RoomKey, server::Server};
+use crate::{room::RoomKey, server::Server, user::UserKey};
 
 // EntityRef
 
@@ -114,6 +114,54 @@ impl<
             .remove_component::<R, W>(&mut self.world, &self.entity)
     }
 
+    // Hierarchy
+
+    /// Makes the Entity a child of the given parent Entity. A child is in
+    /// scope for exactly those Users which its parent is in scope for, is
+    /// spawned on Clients after its parent, and is despawned along with it.
+    /// Panics if this would make the Entity its own ancestor.
+    pub fn set_parent(&mut self, parent: &E) -> &mut Self {
+        self.server.entity_set_parent(&self.entity, Some(*parent));
+
+        self
+    }
+
+    /// Detaches the Entity from its parent, if it has one
+    pub fn remove_parent(&mut self) -> &mut Self {
+        self.server.entity_set_parent(&self.entity, None);
+
+        self
+    }
+
+    /// Returns the parent of the Entity, if it has one
+    pub fn parent(&self) -> Option<E> {
+        self.server.entity_parent(&self.entity)
+    }
+
+    // Authority
+
+    /// Lets the Client of the given User change the Entity's Properties,
+    /// which are then replicated to the Server and all other Clients. Takes
+    /// authority away from any other Client which has it.
+    /// Panics if the Entity was spawned by a Client.
+    pub fn give_authority(&mut self, user_key: &UserKey) -> &mut Self {
+        self.server.entity_give_authority(&self.entity, user_key);
+
+        self
+    }
+
+    /// Takes authority over the Entity back from whichever Client has it
+    pub fn take_authority(&mut self) -> &mut Self {
+        self.server.entity_take_authority(&self.entity);
+
+        self
+    }
+
+    /// Returns the User whose Client has authority over the Entity, if any
+    pub fn authority(&self) -> Option<UserKey> {
+        self.server.entity_authority(&self.entity)
+    }
+
     // Rooms
 
     pub fn enter_room(&mut self, room_key: &RoomKey) -> &mut Self {
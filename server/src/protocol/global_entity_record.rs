@@ -2,20 +2,28 @@ use std::collections::HashSet;
 
 use naia_shared::{EntityHandle, ProtocolKindType};
 
-use crate::room::RoomKey;
+use crate::{room::RoomKey, user::UserKey};
 
-pub struct GlobalEntityRecord<K: ProtocolKindType> {
+pub struct GlobalEntityRecord<E, K: ProtocolKindType> {
     pub room_key: Option<RoomKey>,
     pub entity_handle: EntityHandle,
     pub component_kinds: HashSet<K>,
+    pub owner: Option<UserKey>,
+    pub authority: Option<UserKey>,
+    pub parent: Option<E>,
+    pub children: HashSet<E>,
 }
 
-impl<K: ProtocolKindType> GlobalEntityRecord<K> {
+impl<E, K: ProtocolKindType> GlobalEntityRecord<E, K> {
     pub fn new(entity_handle: EntityHandle) -> Self {
         Self {
             room_key: None,
             entity_handle,
             component_kinds: HashSet::new(),
+            owner: None,
+            authority: None,
+            parent: None,
+            children: HashSet::new(),
         }
     }
 }
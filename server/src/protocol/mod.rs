@@ -1,11 +1,8 @@
-pub mod entity_action_event;
-pub mod entity_manager;
-pub mod entity_message_waitlist;
+pub mod component_inserter;
+pub mod component_scope_map;
 pub mod entity_ref;
 pub mod entity_scope_map;
-pub mod global_diff_handler;
 pub mod global_entity_record;
-pub mod mut_channel;
-pub mod user_diff_handler;
-pub mod world_channel;
+pub mod remote_entity_manager;
+pub mod scope_candidate_map;
 pub mod world_record;
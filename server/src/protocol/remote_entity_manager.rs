@@ -0,0 +1,288 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use naia_shared::{
+    message_list_header,
+    serde::{BitReader, Serde, SerdeErr, UnsignedVariableInteger},
+    ComponentUpdate, EntityAction, EntityActionReceiver, EntityActionType, EntityConverter,
+    EntityHandleConverter, MessageId, NetEntity, NetEntityConverter, Protocolize,
+};
+
+/// A change to an Entity spawned by a Client, waiting to be applied to the
+/// Server's World
+pub enum RemoteEntityAction<P: Protocolize> {
+    SpawnEntity(NetEntity, Vec<P>),
+    DespawnEntity(NetEntity),
+    InsertComponent(NetEntity, P),
+    RemoveComponent(NetEntity, P::Kind),
+    UpdateComponent(NetEntity, ComponentUpdate<P::Kind>),
+    /// An update to a Component of an Entity spawned by the Server, which the
+    /// Client has been given authority over. The NetEntity is the one the
+    /// Server uses for the Entity on this connection
+    UpdateDelegatedComponent(NetEntity, ComponentUpdate<P::Kind>),
+}
+
+/// Reads the Entities which a given Client has spawned from its incoming
+/// packets, and keeps track of which Entity each one was spawned as on the
+/// Server
+pub struct RemoteEntityManager<P: Protocolize, E: Copy + Eq + Hash> {
+    net_entity_to_entity: HashMap<NetEntity, E>,
+    entity_to_net_entity: HashMap<E, NetEntity>,
+    receiver: EntityActionReceiver<NetEntity, P::Kind>,
+    received_components: HashMap<(NetEntity, P::Kind), P>,
+    incoming_actions: VecDeque<RemoteEntityAction<P>>,
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash> Default for RemoteEntityManager<P, E> {
+    fn default() -> Self {
+        Self {
+            net_entity_to_entity: HashMap::default(),
+            entity_to_net_entity: HashMap::default(),
+            receiver: EntityActionReceiver::default(),
+            received_components: HashMap::default(),
+            incoming_actions: VecDeque::default(),
+        }
+    }
+}
+
+impl<P: Protocolize, E: Copy + Eq + Hash> RemoteEntityManager<P, E> {
+    // Entities
+
+    pub fn insert_entity(&mut self, net_entity: NetEntity, entity: E) {
+        self.net_entity_to_entity.insert(net_entity, entity);
+        self.entity_to_net_entity.insert(entity, net_entity);
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        if let Some(net_entity) = self.entity_to_net_entity.remove(entity) {
+            self.net_entity_to_entity.remove(&net_entity);
+        }
+    }
+
+    pub fn entity(&self, net_entity: &NetEntity) -> Option<E> {
+        self.net_entity_to_entity.get(net_entity).copied()
+    }
+
+    /// The number of Entities the Client has spawned on the Server
+    pub fn entity_count(&self) -> usize {
+        self.net_entity_to_entity.len()
+    }
+
+    pub fn take_incoming_actions(&mut self) -> VecDeque<RemoteEntityAction<P>> {
+        std::mem::take(&mut self.incoming_actions)
+    }
+
+    // Action Reader
+
+    /// Reads the updates & actions to the Entities spawned by the Client.
+    /// Entity Properties can refer to these Entities, or to the ones spawned
+    /// by the Server, found through `host_converter`
+    pub fn read_all(
+        &mut self,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        host_converter: &dyn NetEntityConverter<E>,
+        reader: &mut BitReader,
+    ) -> Result<(), SerdeErr> {
+        self.read_updates(reader)?;
+        self.read_actions(handle_converter, host_converter, reader)?;
+        Ok(())
+    }
+
+    fn read_message_id(
+        reader: &mut BitReader,
+        last_id_opt: &mut Option<MessageId>,
+    ) -> Result<MessageId, SerdeErr> {
+        let current_id = if let Some(last_id) = last_id_opt {
+            // read diff
+            let id_diff = UnsignedVariableInteger::<3>::de(reader)?.get() as MessageId;
+            last_id.wrapping_add(id_diff)
+        } else {
+            // read message id
+            MessageId::de(reader)?
+        };
+        *last_id_opt = Some(current_id);
+        Ok(current_id)
+    }
+
+    fn read_actions(
+        &mut self,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        host_converter: &dyn NetEntityConverter<E>,
+        reader: &mut BitReader,
+    ) -> Result<(), SerdeErr> {
+        let mut last_read_id: Option<MessageId> = None;
+        let action_count = message_list_header::read(reader)?;
+        for _ in 0..action_count {
+            self.read_action(handle_converter, host_converter, reader, &mut last_read_id)?;
+        }
+        self.process_incoming_actions();
+        Ok(())
+    }
+
+    fn read_action(
+        &mut self,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        host_converter: &dyn NetEntityConverter<E>,
+        reader: &mut BitReader,
+        last_read_id: &mut Option<MessageId>,
+    ) -> Result<(), SerdeErr> {
+        let action_id = Self::read_message_id(reader, last_read_id)?;
+
+        let action_type = EntityActionType::de(reader)?;
+
+        match action_type {
+            // Entity Creation
+            EntityActionType::SpawnEntity => {
+                // read entity
+                let net_entity = NetEntity::de(reader)?;
+
+                // read parent, Clients do not spawn Entities as children
+                let _parent_net_entity = Option::<NetEntity>::de(reader)?;
+
+                // read components
+                let components_num = UnsignedVariableInteger::<3>::de(reader)?.get();
+                let mut component_kinds = Vec::new();
+                for _ in 0..components_num {
+                    let new_component = {
+                        let converter = EntityConverter::new(handle_converter, host_converter)
+                            .with_remote(self);
+                        P::read(reader, &converter)?
+                    };
+                    let new_component_kind = new_component.dyn_ref().kind();
+                    self.received_components
+                        .insert((net_entity, new_component_kind), new_component);
+                    component_kinds.push(new_component_kind);
+                }
+
+                self.receiver.buffer_action(
+                    action_id,
+                    EntityAction::SpawnEntity(net_entity, component_kinds),
+                );
+            }
+            // Entity Deletion
+            EntityActionType::DespawnEntity => {
+                // read all data
+                let net_entity = NetEntity::de(reader)?;
+
+                self.receiver
+                    .buffer_action(action_id, EntityAction::DespawnEntity(net_entity));
+            }
+            // Add Component to Entity
+            EntityActionType::InsertComponent => {
+                // read all data
+                let net_entity = NetEntity::de(reader)?;
+                let new_component = {
+                    let converter =
+                        EntityConverter::new(handle_converter, host_converter).with_remote(self);
+                    P::read(reader, &converter)?
+                };
+                let new_component_kind = new_component.dyn_ref().kind();
+
+                self.receiver.buffer_action(
+                    action_id,
+                    EntityAction::InsertComponent(net_entity, new_component_kind),
+                );
+                self.received_components
+                    .insert((net_entity, new_component_kind), new_component);
+            }
+            // Component Removal
+            EntityActionType::RemoveComponent => {
+                // read all data
+                let net_entity = NetEntity::de(reader)?;
+                let component_kind = P::Kind::de(reader)?;
+
+                self.receiver.buffer_action(
+                    action_id,
+                    EntityAction::RemoveComponent(net_entity, component_kind),
+                );
+            }
+            EntityActionType::Noop => {
+                self.receiver.buffer_action(action_id, EntityAction::Noop);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_incoming_actions(&mut self) {
+        for action in self.receiver.receive_actions() {
+            match action {
+                EntityAction::SpawnEntity(net_entity, component_kinds) => {
+                    let components = component_kinds
+                        .iter()
+                        .filter_map(|component_kind| {
+                            self.received_components
+                                .remove(&(net_entity, *component_kind))
+                        })
+                        .collect();
+                    self.incoming_actions
+                        .push_back(RemoteEntityAction::SpawnEntity(net_entity, components));
+                }
+                EntityAction::DespawnEntity(net_entity) => {
+                    self.incoming_actions
+                        .push_back(RemoteEntityAction::DespawnEntity(net_entity));
+                }
+                EntityAction::InsertComponent(net_entity, component_kind) => {
+                    if let Some(component) = self
+                        .received_components
+                        .remove(&(net_entity, component_kind))
+                    {
+                        self.incoming_actions
+                            .push_back(RemoteEntityAction::InsertComponent(net_entity, component));
+                    }
+                }
+                EntityAction::RemoveComponent(net_entity, component_kind) => {
+                    self.incoming_actions
+                        .push_back(RemoteEntityAction::RemoveComponent(
+                            net_entity,
+                            component_kind,
+                        ));
+                }
+                EntityAction::Noop => {
+                    // do nothing
+                }
+            }
+        }
+    }
+
+    fn read_updates(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.read_updates_as(reader, RemoteEntityAction::UpdateComponent)
+    }
+
+    /// Reads updates to Server Entities which the Client has authority over
+    pub fn read_delegated_updates(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.read_updates_as(reader, RemoteEntityAction::UpdateDelegatedComponent)
+    }
+
+    fn read_updates_as(
+        &mut self,
+        reader: &mut BitReader,
+        into_action: fn(NetEntity, ComponentUpdate<P::Kind>) -> RemoteEntityAction<P>,
+    ) -> Result<(), SerdeErr> {
+        let update_count = message_list_header::read(reader)?;
+        for _ in 0..update_count {
+            let net_entity = NetEntity::de(reader)?;
+
+            let components_number = UnsignedVariableInteger::<3>::de(reader)?.get();
+            for _ in 0..components_number {
+                let component_update = P::read_create_update(reader)?;
+                self.incoming_actions
+                    .push_back(into_action(net_entity, component_update));
+            }
+        }
+        Ok(())
+    }
+}
+
+// NetEntityConverter
+impl<P: Protocolize, E: Copy + Eq + Hash> NetEntityConverter<E> for RemoteEntityManager<P, E> {
+    fn entity_to_net_entity(&self, entity: &E) -> Option<NetEntity> {
+        self.entity_to_net_entity.get(entity).copied()
+    }
+
+    fn net_entity_to_entity(&self, net_entity: &NetEntity) -> Option<E> {
+        self.entity(net_entity)
+    }
+}
@@ -0,0 +1,351 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::{room::RoomKey, user::UserKey};
+
+/// Caches every combination of a User & Entity which share a Room, and so
+/// which could be in scope for one another. Kept up to date as Users &
+/// Entities join or leave Rooms, and tracks which combinations have yet to be
+/// checked by the application, and which the Server has yet to reevaluate
+pub struct ScopeCandidateMap<E: Copy + Eq + Hash> {
+    rooms_of_candidate: HashMap<(UserKey, E), HashSet<RoomKey>>,
+    entities_of_user: HashMap<UserKey, HashSet<E>>,
+    users_of_entity: HashMap<E, HashSet<UserKey>>,
+    unchecked_candidates: HashSet<(RoomKey, UserKey, E)>,
+    dirty_users: HashSet<UserKey>,
+    dirty_entities: HashSet<E>,
+    changed_candidates: HashSet<(UserKey, E)>,
+}
+
+impl<E: Copy + Eq + Hash> ScopeCandidateMap<E> {
+    pub fn new() -> Self {
+        Self {
+            rooms_of_candidate: HashMap::new(),
+            entities_of_user: HashMap::new(),
+            users_of_entity: HashMap::new(),
+            unchecked_candidates: HashSet::new(),
+            dirty_users: HashSet::new(),
+            dirty_entities: HashSet::new(),
+            changed_candidates: HashSet::new(),
+        }
+    }
+
+    pub fn insert(&mut self, room_key: &RoomKey, user_key: &UserKey, entity: &E) {
+        let is_new = self
+            .rooms_of_candidate
+            .entry((*user_key, *entity))
+            .or_default()
+            .insert(*room_key);
+        if !is_new {
+            return;
+        }
+
+        self.entities_of_user
+            .entry(*user_key)
+            .or_default()
+            .insert(*entity);
+        self.users_of_entity
+            .entry(*entity)
+            .or_default()
+            .insert(*user_key);
+        self.unchecked_candidates
+            .insert((*room_key, *user_key, *entity));
+        self.changed_candidates.insert((*user_key, *entity));
+    }
+
+    pub fn remove(&mut self, room_key: &RoomKey, user_key: &UserKey, entity: &E) {
+        let key = (*user_key, *entity);
+        self.unchecked_candidates
+            .remove(&(*room_key, *user_key, *entity));
+
+        if let Some(rooms) = self.rooms_of_candidate.get_mut(&key) {
+            rooms.remove(room_key);
+            if !rooms.is_empty() {
+                return;
+            }
+        } else {
+            return;
+        }
+
+        self.rooms_of_candidate.remove(&key);
+        self.changed_candidates.remove(&key);
+        if let Some(entities) = self.entities_of_user.get_mut(user_key) {
+            entities.remove(entity);
+            if entities.is_empty() {
+                self.entities_of_user.remove(user_key);
+            }
+        }
+        if let Some(users) = self.users_of_entity.get_mut(entity) {
+            users.remove(user_key);
+            if users.is_empty() {
+                self.users_of_entity.remove(entity);
+            }
+        }
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        if let Some(users) = self.users_of_entity.remove(entity) {
+            for user_key in users {
+                if let Some(rooms) = self.rooms_of_candidate.remove(&(user_key, *entity)) {
+                    for room_key in rooms {
+                        self.unchecked_candidates
+                            .remove(&(room_key, user_key, *entity));
+                    }
+                }
+                if let Some(entities) = self.entities_of_user.get_mut(&user_key) {
+                    entities.remove(entity);
+                    if entities.is_empty() {
+                        self.entities_of_user.remove(&user_key);
+                    }
+                }
+            }
+        }
+        self.dirty_entities.remove(entity);
+        self.changed_candidates
+            .retain(|(_, changed_entity)| changed_entity != entity);
+    }
+
+    pub fn remove_user(&mut self, user_key: &UserKey) {
+        if let Some(entities) = self.entities_of_user.remove(user_key) {
+            for entity in entities {
+                if let Some(rooms) = self.rooms_of_candidate.remove(&(*user_key, entity)) {
+                    for room_key in rooms {
+                        self.unchecked_candidates
+                            .remove(&(room_key, *user_key, entity));
+                    }
+                }
+                if let Some(users) = self.users_of_entity.get_mut(&entity) {
+                    users.remove(user_key);
+                    if users.is_empty() {
+                        self.users_of_entity.remove(&entity);
+                    }
+                }
+            }
+        }
+        self.dirty_users.remove(user_key);
+        self.changed_candidates
+            .retain(|(changed_user, _)| changed_user != user_key);
+    }
+
+    /// Every combination of a Room, User & Entity, whether or not it has been
+    /// checked already
+    pub fn all_scope_checks(&self) -> Vec<(RoomKey, UserKey, E)> {
+        let mut output = Vec::new();
+        for ((user_key, entity), rooms) in self.rooms_of_candidate.iter() {
+            for room_key in rooms {
+                output.push((*room_key, *user_key, *entity));
+            }
+        }
+        output
+    }
+
+    pub fn mark_user_dirty(&mut self, user_key: &UserKey) {
+        self.dirty_users.insert(*user_key);
+    }
+
+    pub fn mark_entity_dirty(&mut self, entity: &E) {
+        self.dirty_entities.insert(*entity);
+    }
+
+    /// Returns the combinations which are new since the last call, along with
+    /// every combination of the Users & Entities marked dirty since then
+    pub fn take_scope_checks(&mut self) -> Vec<(RoomKey, UserKey, E)> {
+        let mut output: HashSet<(RoomKey, UserKey, E)> =
+            std::mem::take(&mut self.unchecked_candidates);
+
+        for user_key in self.dirty_users.drain() {
+            if let Some(entities) = self.entities_of_user.get(&user_key) {
+                for entity in entities {
+                    for room_key in &self.rooms_of_candidate[&(user_key, *entity)] {
+                        output.insert((*room_key, user_key, *entity));
+                    }
+                }
+            }
+        }
+        for entity in self.dirty_entities.drain() {
+            if let Some(users) = self.users_of_entity.get(&entity) {
+                for user_key in users {
+                    for room_key in &self.rooms_of_candidate[&(*user_key, entity)] {
+                        output.insert((*room_key, *user_key, entity));
+                    }
+                }
+            }
+        }
+
+        output.into_iter().collect()
+    }
+
+    // Changed Candidates
+
+    pub fn mark_candidate_changed(&mut self, user_key: &UserKey, entity: &E) {
+        let key = (*user_key, *entity);
+        if self.rooms_of_candidate.contains_key(&key) {
+            self.changed_candidates.insert(key);
+        }
+    }
+
+    pub fn mark_user_changed(&mut self, user_key: &UserKey) {
+        if let Some(entities) = self.entities_of_user.get(user_key) {
+            for entity in entities {
+                self.changed_candidates.insert((*user_key, *entity));
+            }
+        }
+    }
+
+    pub fn mark_entity_changed(&mut self, entity: &E) {
+        if let Some(users) = self.users_of_entity.get(entity) {
+            for user_key in users {
+                self.changed_candidates.insert((*user_key, *entity));
+            }
+        }
+    }
+
+    pub fn mark_all_changed(&mut self) {
+        self.changed_candidates
+            .extend(self.rooms_of_candidate.keys().copied());
+    }
+
+    /// Returns the combinations of a User & Entity which are new, or whose
+    /// scope may have changed, since the last call
+    pub fn take_changed_candidates(&mut self) -> Vec<(UserKey, E)> {
+        self.changed_candidates.drain().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use naia_shared::BigMapKey;
+
+    use crate::{room::RoomKey, user::UserKey};
+
+    use super::ScopeCandidateMap;
+
+    fn sorted(checks: Vec<(RoomKey, UserKey, u32)>) -> Vec<(u64, u64, u32)> {
+        let mut output: Vec<(u64, u64, u32)> = checks
+            .into_iter()
+            .map(|(room_key, user_key, entity)| (room_key.to_u64(), user_key.to_u64(), entity))
+            .collect();
+        output.sort_unstable();
+        output
+    }
+
+    fn sorted_changes(changes: Vec<(UserKey, u32)>) -> Vec<(u64, u32)> {
+        let mut output: Vec<(u64, u32)> = changes
+            .into_iter()
+            .map(|(user_key, entity)| (user_key.to_u64(), entity))
+            .collect();
+        output.sort_unstable();
+        output
+    }
+
+    #[test]
+    fn only_new_candidates_are_checked() {
+        let mut candidates = ScopeCandidateMap::<u32>::new();
+        let room = RoomKey::from_u64(0);
+        let user = UserKey::from_u64(0);
+
+        candidates.insert(&room, &user, &1);
+        candidates.insert(&room, &user, &2);
+        assert_eq!(
+            sorted(candidates.take_scope_checks()),
+            vec![(0, 0, 1), (0, 0, 2)]
+        );
+        assert!(candidates.take_scope_checks().is_empty());
+
+        candidates.insert(&room, &user, &3);
+        assert_eq!(sorted(candidates.take_scope_checks()), vec![(0, 0, 3)]);
+
+        // removed before it was checked
+        candidates.insert(&room, &user, &4);
+        candidates.remove(&room, &user, &4);
+        assert!(candidates.take_scope_checks().is_empty());
+        assert_eq!(candidates.all_scope_checks().len(), 3);
+    }
+
+    #[test]
+    fn dirty_users_and_entities_are_checked_again() {
+        let mut candidates = ScopeCandidateMap::<u32>::new();
+        let room = RoomKey::from_u64(0);
+        let user_a = UserKey::from_u64(0);
+        let user_b = UserKey::from_u64(1);
+
+        for user in [&user_a, &user_b] {
+            for entity in [1, 2] {
+                candidates.insert(&room, user, &entity);
+            }
+        }
+        candidates.take_scope_checks();
+
+        candidates.mark_entity_dirty(&1);
+        assert_eq!(
+            sorted(candidates.take_scope_checks()),
+            vec![(0, 0, 1), (0, 1, 1)]
+        );
+
+        candidates.mark_user_dirty(&user_b);
+        candidates.mark_entity_dirty(&2);
+        assert_eq!(
+            sorted(candidates.take_scope_checks()),
+            vec![(0, 0, 2), (0, 1, 1), (0, 1, 2)]
+        );
+
+        candidates.remove_entity(&2);
+        candidates.mark_entity_dirty(&2);
+        candidates.mark_user_dirty(&user_a);
+        assert_eq!(sorted(candidates.take_scope_checks()), vec![(0, 0, 1)]);
+
+        candidates.remove_user(&user_a);
+        candidates.mark_entity_dirty(&1);
+        assert_eq!(sorted(candidates.take_scope_checks()), vec![(0, 1, 1)]);
+    }
+
+    #[test]
+    fn only_changed_candidates_are_reevaluated() {
+        let mut candidates = ScopeCandidateMap::<u32>::new();
+        let room = RoomKey::from_u64(0);
+        let user_a = UserKey::from_u64(0);
+        let user_b = UserKey::from_u64(1);
+
+        for user in [&user_a, &user_b] {
+            for entity in [1, 2] {
+                candidates.insert(&room, user, &entity);
+            }
+        }
+        assert_eq!(candidates.take_changed_candidates().len(), 4);
+        assert!(candidates.take_changed_candidates().is_empty());
+
+        // checking scope doesn't reevaluate anything by itself
+        candidates.mark_entity_dirty(&1);
+        candidates.take_scope_checks();
+        assert!(candidates.take_changed_candidates().is_empty());
+
+        candidates.mark_candidate_changed(&user_a, &1);
+        candidates.mark_candidate_changed(&user_a, &3);
+        assert_eq!(
+            sorted_changes(candidates.take_changed_candidates()),
+            vec![(0, 1)]
+        );
+
+        candidates.mark_entity_changed(&2);
+        assert_eq!(
+            sorted_changes(candidates.take_changed_candidates()),
+            vec![(0, 2), (1, 2)]
+        );
+
+        candidates.mark_user_changed(&user_b);
+        candidates.remove(&room, &user_b, &1);
+        assert_eq!(
+            sorted_changes(candidates.take_changed_candidates()),
+            vec![(1, 2)]
+        );
+
+        candidates.mark_all_changed();
+        candidates.remove_user(&user_a);
+        assert_eq!(
+            sorted_changes(candidates.take_changed_candidates()),
+            vec![(1, 2)]
+        );
+    }
+}
@@ -2,10 +2,10 @@ use std::{collections::HashMap, hash::Hash};
 
 use naia_shared::{BigMap, EntityHandle, EntityHandleConverter, ProtocolKindType};
 
-use crate::{protocol::global_entity_record::GlobalEntityRecord, room::RoomKey};
+use crate::{protocol::global_entity_record::GlobalEntityRecord, room::RoomKey, user::UserKey};
 
 pub struct WorldRecord<E: Copy + Eq + Hash, K: ProtocolKindType> {
-    entity_records: HashMap<E, GlobalEntityRecord<K>>,
+    entity_records: HashMap<E, GlobalEntityRecord<E, K>>,
     handle_entity_map: BigMap<EntityHandle, E>,
 }
 
@@ -30,11 +30,13 @@ impl<E: Copy + Eq + Hash, K: ProtocolKindType> WorldRecord<E, K> {
             .insert(*entity, GlobalEntityRecord::new(entity_handle));
     }
 
-    pub fn despawn_entity(&mut self, entity: &E) -> Option<GlobalEntityRecord<K>> {
+    pub fn despawn_entity(&mut self, entity: &E) -> Option<GlobalEntityRecord<E, K>> {
         if !self.entity_records.contains_key(entity) {
             panic!("entity does not exist!");
         }
 
+        self.entity_set_parent(entity, None);
+
         self.entity_records.remove(entity)
     }
 
@@ -62,6 +64,10 @@ impl<E: Copy + Eq + Hash, K: ProtocolKindType> WorldRecord<E, K> {
         self.entity_records.contains_key(entity)
     }
 
+    pub fn entities(&self) -> Vec<E> {
+        self.entity_records.keys().copied().collect()
+    }
+
     pub fn component_kinds(&self, entity: &E) -> Option<Vec<K>> {
         if !self.entity_records.contains_key(entity) {
             return None;
@@ -71,6 +77,124 @@ impl<E: Copy + Eq + Hash, K: ProtocolKindType> WorldRecord<E, K> {
         return Some(component_kind_set.iter().copied().collect());
     }
 
+    // Hierarchy
+
+    pub(crate) fn entity_parent(&self, entity: &E) -> Option<E> {
+        self.entity_records
+            .get(entity)
+            .and_then(|entity_record| entity_record.parent)
+    }
+
+    pub(crate) fn entity_children(&self, entity: &E) -> Vec<E> {
+        match self.entity_records.get(entity) {
+            Some(entity_record) => entity_record.children.iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the topmost ancestor of the Entity, or the Entity itself if it
+    /// has no parent
+    pub(crate) fn entity_root(&self, entity: &E) -> E {
+        let mut root = *entity;
+        while let Some(parent) = self.entity_parent(&root) {
+            root = parent;
+        }
+        root
+    }
+
+    /// Returns the Entity followed by all of its descendants, with every
+    /// parent coming before its children
+    pub(crate) fn entity_tree(&self, entity: &E) -> Vec<E> {
+        let mut tree = vec![*entity];
+        let mut index = 0;
+        while index < tree.len() {
+            let children = self.entity_children(&tree[index]);
+            tree.extend(children);
+            index += 1;
+        }
+        tree
+    }
+
+    pub(crate) fn entity_set_parent(&mut self, entity: &E, parent_opt: Option<E>) {
+        if !self.entity_records.contains_key(entity) {
+            panic!("entity does not exist!");
+        }
+        if let Some(parent) = &parent_opt {
+            if !self.entity_records.contains_key(parent) {
+                panic!("parent entity does not exist!");
+            }
+            if self.entity_tree(entity).contains(parent) {
+                panic!("an Entity cannot be the parent of itself or of its ancestors!");
+            }
+        }
+
+        let entity_record = self.entity_records.get_mut(entity).unwrap();
+        let old_parent_opt = std::mem::replace(&mut entity_record.parent, parent_opt);
+
+        if let Some(old_parent) = old_parent_opt {
+            if let Some(old_parent_record) = self.entity_records.get_mut(&old_parent) {
+                old_parent_record.children.remove(entity);
+            }
+        }
+        if let Some(parent) = parent_opt {
+            self.entity_records
+                .get_mut(&parent)
+                .unwrap()
+                .children
+                .insert(*entity);
+        }
+    }
+
+    // Ownership
+
+    pub(crate) fn entity_owner(&self, entity: &E) -> Option<UserKey> {
+        self.entity_records
+            .get(entity)
+            .and_then(|entity_record| entity_record.owner)
+    }
+
+    pub(crate) fn entity_set_owner(&mut self, entity: &E, user_key: &UserKey) {
+        if let Some(entity_record) = self.entity_records.get_mut(entity) {
+            entity_record.owner = Some(*user_key);
+        }
+    }
+
+    /// Hands all Entities owned by the given User over to the Server
+    pub(crate) fn user_release_entities(&mut self, user_key: &UserKey) {
+        for entity_record in self.entity_records.values_mut() {
+            if entity_record.owner == Some(*user_key) {
+                entity_record.owner = None;
+            }
+        }
+    }
+
+    // Authority
+
+    pub(crate) fn entity_authority(&self, entity: &E) -> Option<UserKey> {
+        self.entity_records
+            .get(entity)
+            .and_then(|entity_record| entity_record.authority)
+    }
+
+    pub(crate) fn entity_set_authority(&mut self, entity: &E, user_key_opt: Option<UserKey>) {
+        if let Some(entity_record) = self.entity_records.get_mut(entity) {
+            entity_record.authority = user_key_opt;
+        }
+    }
+
+    /// Takes authority over all Entities back from the given User, returning
+    /// those Entities
+    pub(crate) fn user_release_authority(&mut self, user_key: &UserKey) -> Vec<E> {
+        let mut entities = Vec::new();
+        for (entity, entity_record) in self.entity_records.iter_mut() {
+            if entity_record.authority == Some(*user_key) {
+                entity_record.authority = None;
+                entities.push(*entity);
+            }
+        }
+        entities
+    }
+
     // Rooms
 
     pub(crate) fn entity_is_in_room(&self, entity: &E, room_key: &RoomKey) -> bool {
@@ -114,3 +238,64 @@ impl<E: Copy + Eq + Hash, K: ProtocolKindType> EntityHandleConverter<E> for Worl
             .entity_handle;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use naia_shared::{derive_serde, serde, ProtocolKindType};
+
+    use super::WorldRecord;
+
+    #[derive(Copy, Eq, Hash)]
+    #[derive_serde]
+    enum TestKind {
+        Position,
+    }
+
+    impl ProtocolKindType for TestKind {
+        fn to_type_id(&self) -> TypeId {
+            TypeId::of::<()>()
+        }
+    }
+
+    fn world_record(entities: &[u32]) -> WorldRecord<u32, TestKind> {
+        let mut world_record = WorldRecord::default();
+        for entity in entities {
+            world_record.spawn_entity(entity);
+        }
+        world_record
+    }
+
+    #[test]
+    fn entity_tree_lists_parents_before_children() {
+        let mut world_record = world_record(&[1, 2, 3, 4]);
+        world_record.entity_set_parent(&3, Some(2));
+        world_record.entity_set_parent(&2, Some(1));
+        world_record.entity_set_parent(&4, Some(1));
+
+        let tree = world_record.entity_tree(&1);
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree[0], 1);
+        let position = |entity| tree.iter().position(|e| *e == entity).unwrap();
+        assert!(position(2) < position(3));
+    }
+
+    #[test]
+    fn despawning_a_child_detaches_it_from_its_parent() {
+        let mut world_record = world_record(&[1, 2]);
+        world_record.entity_set_parent(&2, Some(1));
+        assert_eq!(world_record.entity_parent(&2), Some(1));
+
+        world_record.despawn_entity(&2);
+        assert!(world_record.entity_children(&1).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn entity_cannot_become_its_own_ancestor() {
+        let mut world_record = world_record(&[1, 2]);
+        world_record.entity_set_parent(&2, Some(1));
+        world_record.entity_set_parent(&1, Some(2));
+    }
+}
@@ -4,6 +4,7 @@ use std::{
     net::SocketAddr,
     panic,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 #[cfg(feature = "bevy_support")]
@@ -12,7 +13,8 @@ use bevy_ecs::prelude::Resource;
 use naia_server_socket::{ServerAddrs, Socket};
 use naia_shared::{
     serde::{BitWriter, Serde},
-    ChannelIndex, EntityHandle, EntityHandleConverter, Tick,
+    AuthorityAction, ChannelIndex, EntityConverter, EntityHandle, EntityHandleConverter,
+    GlobalDiffHandler, MtuManager, RejectReason, ServerInfo, Tick,
 };
 pub use naia_shared::{
     wrapping_diff, BaseConnection, BigMap, ConnectionConfig, Instant, KeyGenerator, NetEntity,
@@ -26,13 +28,19 @@ use crate::{
         connection::Connection,
         handshake_manager::{HandshakeManager, HandshakeResult},
         io::Io,
+        rate_limiter::RateLimiter,
     },
+    lag_compensation::lag_compensation_history::{rendered_tick, LagCompensationHistory},
     protocol::{
+        component_inserter::ComponentInserter,
+        component_scope_map::ComponentScopeMap,
         entity_ref::{EntityMut, EntityRef},
         entity_scope_map::EntityScopeMap,
-        global_diff_handler::GlobalDiffHandler,
+        remote_entity_manager::RemoteEntityAction,
+        scope_candidate_map::ScopeCandidateMap,
         world_record::WorldRecord,
     },
+    spatial_scope::spatial_grid::SpatialGrid,
     tick::tick_manager::TickManager,
 };
 
@@ -59,20 +67,30 @@ pub struct Server<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelI
     timeout_timer: Timer,
     ping_timer: Timer,
     handshake_manager: HandshakeManager<P>,
+    handshake_rate_limiter: Option<RateLimiter>,
+    info_query_rate_limiter: Option<RateLimiter>,
+    server_info: Option<P>,
     // Users
     users: BigMap<UserKey, User>,
     user_connections: HashMap<SocketAddr, Connection<P, E, C>>,
+    // Users awaiting a decision on their auth message, and since when
+    pending_users: HashMap<SocketAddr, (UserKey, Instant)>,
     // Rooms
     rooms: BigMap<RoomKey, Room<E>>,
     // Entities
     world_record: WorldRecord<E, P::Kind>,
     entity_scope_map: EntityScopeMap<E>,
+    component_scope_map: ComponentScopeMap<E, P::Kind>,
+    scope_candidate_map: ScopeCandidateMap<E>,
+    spatial_grid: Option<SpatialGrid<UserKey, E>>,
     // Components
     diff_handler: Arc<RwLock<GlobalDiffHandler<E, P::Kind>>>,
     // Events
-    incoming_events: VecDeque<Result<Event<P, C>, NaiaServerError>>,
+    incoming_events: VecDeque<Result<Event<P, E, C>, NaiaServerError>>,
     // Ticks
     tick_manager: Option<TickManager>,
+    // Lag Compensation
+    lag_compensation: LagCompensationHistory<E, P::Kind, P>,
 }
 
 impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<P, E, C> {
@@ -96,20 +114,37 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
             timeout_timer: Timer::new(server_config.connection.disconnection_timeout_duration),
             ping_timer: Timer::new(server_config.connection.ping.ping_interval),
             handshake_manager: HandshakeManager::new(server_config.require_auth),
+            handshake_rate_limiter: server_config
+                .handshake_rate_limit
+                .as_ref()
+                .map(RateLimiter::new),
+            info_query_rate_limiter: server_config
+                .info_query_rate_limit
+                .as_ref()
+                .map(RateLimiter::new),
+            server_info: None,
             // Users
             users: BigMap::default(),
             user_connections: HashMap::new(),
+            pending_users: HashMap::new(),
             // Rooms
             rooms: BigMap::default(),
             // Entities
             world_record: WorldRecord::default(),
             entity_scope_map: EntityScopeMap::new(),
+            component_scope_map: ComponentScopeMap::new(),
+            scope_candidate_map: ScopeCandidateMap::new(),
+            spatial_grid: server_config.spatial_scope.as_ref().map(SpatialGrid::new),
             // Components
             diff_handler: Arc::new(RwLock::new(GlobalDiffHandler::default())),
             // Events
             incoming_events: VecDeque::new(),
             // Ticks
             tick_manager,
+            // Lag Compensation
+            lag_compensation: LagCompensationHistory::new(
+                server_config.lag_compensation.history_ticks,
+            ),
         }
     }
 
@@ -127,12 +162,43 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     }
 
     /// Must be called regularly, maintains connection to and receives messages
-    /// from all Clients
-    pub fn receive(&mut self) -> VecDeque<Result<Event<P, C>, NaiaServerError>> {
+    /// from all Clients, and applies changes to Client-owned Entities to the
+    /// World
+    pub fn receive<W: WorldMutType<P, E>>(
+        &mut self,
+        mut world: W,
+    ) -> VecDeque<Result<Event<P, E, C>, NaiaServerError>> {
         // Need to run this to maintain connection with all clients, and receive packets
         // until none left
         self.maintain_socket();
 
+        // apply changes Clients have made to their own Entities
+        let remote_actions: Vec<(UserKey, VecDeque<RemoteEntityAction<P>>)> = self
+            .user_connections
+            .values_mut()
+            .map(|connection| {
+                (
+                    connection.user_key,
+                    connection.remote_entity_manager.take_incoming_actions(),
+                )
+            })
+            .collect();
+        for (user_key, actions) in remote_actions {
+            for action in actions {
+                self.process_remote_entity_action(&mut world, &user_key, action);
+            }
+        }
+
+        // handle changes of authority over Server Entities
+        let user_keys: Vec<UserKey> = self
+            .user_connections
+            .values()
+            .map(|connection| connection.user_key)
+            .collect();
+        for user_key in user_keys {
+            self.process_authority_actions(&user_key);
+        }
+
         // tick event
         let mut did_tick = false;
         if let Some(tick_manager) = &mut self.tick_manager {
@@ -157,6 +223,16 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                     message,
                 )));
             }
+
+            // report messages which expired before being delivered
+            let expired_messages = connection.base.message_manager.take_expired_messages();
+            for (channel, message) in expired_messages {
+                self.incoming_events.push_back(Ok(Event::MessageExpired(
+                    connection.user_key,
+                    channel,
+                    message,
+                )));
+            }
         }
 
         // receive tick buffered messages on tick
@@ -186,12 +262,22 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     // Connections
 
     /// Accepts an incoming Client User, allowing them to establish a connection
-    /// with the Server
+    /// with the Server. If the Server has reached its maximum number of Users,
+    /// the User is rejected instead.
     pub fn accept_connection(&mut self, user_key: &UserKey) {
+        if self.is_full() {
+            self.reject_user(user_key, RejectReason::ServerFull);
+            return;
+        }
+
         if let Some(user) = self.users.get(user_key) {
+            self.pending_users.remove(&user.address);
+
             let new_connection = Connection::new(
                 &self.server_config.connection,
                 &self.shared_config.channel,
+                &self.shared_config.mtu,
+                self.shared_config.delta_compression,
                 user.address,
                 user_key,
                 &self.diff_handler,
@@ -201,9 +287,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
             self.io.send_writer(&user.address, &mut writer);
             //
             self.user_connections.insert(user.address, new_connection);
-            if self.io.bandwidth_monitor_enabled() {
-                self.io.register_client(&user.address);
-            }
+            self.io.register_client(&user.address);
             self.incoming_events
                 .push_back(Ok(Event::Connection(*user_key)));
         }
@@ -212,13 +296,20 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     /// Rejects an incoming Client User, terminating their attempt to establish
     /// a connection with the Server
     pub fn reject_connection(&mut self, user_key: &UserKey) {
-        if let Some(user) = self.users.get(user_key) {
-            // send connect reject response
-            let mut writer = self.handshake_manager.write_reject_response();
-            self.io.send_writer(&user.address, &mut writer);
-            //
+        self.reject_user(user_key, RejectReason::Auth);
+    }
+
+    /// Sets the application-defined info which is sent in answer to server
+    /// info queries, alongside the number of Users, tick rate and Protocol
+    /// fingerprint.
+    /// Panics if the info makes the response larger than a server info query,
+    /// which is a little smaller than a single packet
+    pub fn set_server_info<R: ReplicateSafe<P>>(&mut self, info: R) {
+        let previous_info = self.server_info.replace(info.into_protocol());
+        if !self.server_info().fits_in_response() {
+            self.server_info = previous_info;
+            panic!("Server info is too large to fit within a server info response");
         }
-        self.delete_user(user_key);
     }
 
     // Messages
@@ -230,6 +321,48 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         user_key: &UserKey,
         channel: C,
         message: &R,
+    ) {
+        self.send_message_inner(user_key, channel, message, None);
+    }
+
+    /// Queues up an Message to be sent to the Client associated with a given
+    /// UserKey, which will be dropped if it has not been delivered within the
+    /// given time-to-live. Only reliable Channels will drop a Message, which
+    /// is then reported back with an `Event::MessageExpired`
+    pub fn send_message_with_ttl<R: ReplicateSafe<P>>(
+        &mut self,
+        user_key: &UserKey,
+        channel: C,
+        message: &R,
+        ttl: Duration,
+    ) {
+        let mut expires_at = Instant::now();
+        expires_at.add_millis(ttl.as_millis() as u32);
+        self.send_message_inner(user_key, channel, message, Some(expires_at));
+    }
+
+    /// Queues up an Message to be sent to the Client associated with a given
+    /// UserKey, which will be dropped if it has not been delivered by the
+    /// given Server Tick. Only reliable Channels will drop a Message, which
+    /// is then reported back with an `Event::MessageExpired`.
+    /// Panics if `SharedConfig::tick_interval` is None
+    pub fn send_message_until_tick<R: ReplicateSafe<P>>(
+        &mut self,
+        user_key: &UserKey,
+        channel: C,
+        message: &R,
+        deadline_tick: Tick,
+    ) {
+        let expires_at = self.tick_to_instant(deadline_tick);
+        self.send_message_inner(user_key, channel, message, Some(expires_at));
+    }
+
+    fn send_message_inner<R: ReplicateSafe<P>>(
+        &mut self,
+        user_key: &UserKey,
+        channel: C,
+        message: &R,
+        expires_at: Option<Instant>,
     ) {
         if !self
             .shared_config
@@ -261,19 +394,23 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                         connection
                             .base
                             .message_manager
-                            .send_message(channel, message.protocol_copy());
+                            .send_message_with_expiry_opt(
+                                channel,
+                                message.protocol_copy(),
+                                expires_at,
+                            );
                     } else {
                         // Entity hasn't been added to the User Scope yet, or replicated to Client
                         // yet
                         connection
                             .entity_manager
-                            .queue_entity_message(entities, channel, message);
+                            .queue_entity_message(entities, channel, message, expires_at);
                     }
                 } else {
                     connection
                         .base
                         .message_manager
-                        .send_message(channel, message.protocol_copy());
+                        .send_message_with_expiry_opt(channel, message.protocol_copy(), expires_at);
                 }
             }
         }
@@ -289,21 +426,39 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     ///
     /// Return a collection of Entity Scope Sets, being a unique combination of
     /// a related Room, User, and Entity, used to determine which Entities to
-    /// replicate to which Users
-    pub fn scope_checks(&self) -> Vec<(RoomKey, UserKey, E)> {
-        let mut list: Vec<(RoomKey, UserKey, E)> = Vec::new();
+    /// replicate to which Users.
+    ///
+    /// Only returns the Scope Sets which are new since the last call, because
+    /// a User or Entity joined a Room, along with those of the Users &
+    /// Entities marked with `mark_user_scope_dirty()` or
+    /// `mark_entity_scope_dirty()` since then. A decision made for a Scope
+    /// Set stands until it is returned again
+    pub fn scope_checks(&mut self) -> Vec<(RoomKey, UserKey, E)> {
+        self.scope_candidate_map.take_scope_checks()
+    }
 
-        // TODO: precache this, instead of generating a new list every call
-        // likely this is called A LOT
-        for (room_key, room) in self.rooms.iter() {
-            for user_key in room.user_keys() {
-                for entity in room.entities() {
-                    list.push((room_key, *user_key, *entity));
-                }
-            }
-        }
+    /// Return every Entity Scope Set, whether or not it has been returned by
+    /// `scope_checks()` already
+    pub fn all_scope_checks(&self) -> Vec<(RoomKey, UserKey, E)> {
+        self.scope_candidate_map.all_scope_checks()
+    }
+
+    /// Marks that something which decides whether the Entity is in scope has
+    /// changed, so that all of its Scope Sets are returned by the next call
+    /// to `scope_checks()`, and its Component scope predicates are evaluated
+    /// again
+    pub fn mark_entity_scope_dirty(&mut self, entity: &E) {
+        self.scope_candidate_map.mark_entity_dirty(entity);
+        self.scope_candidate_map.mark_entity_changed(entity);
+    }
 
-        list
+    /// Marks that something which decides which Entities are in scope for the
+    /// User has changed, so that all of their Scope Sets are returned by the
+    /// next call to `scope_checks()`, and Component scope predicates are
+    /// evaluated again for them
+    pub fn mark_user_scope_dirty(&mut self, user_key: &UserKey) {
+        self.scope_candidate_map.mark_user_dirty(user_key);
+        self.scope_candidate_map.mark_user_changed(user_key);
     }
 
     /// Sends all update messages to all Clients. If you don't call this
@@ -312,9 +467,18 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     pub fn send_all_updates<W: WorldRefType<P, E>>(&mut self, world: W) {
         let now = Instant::now();
 
+        // apply changes in which Entities Users can see
+        self.update_spatial_scopes();
+
         // update entity scopes
         self.update_entity_scopes(&world);
 
+        // keep the states sent this tick, to rewind to later
+        self.record_lag_compensation(&world);
+
+        // grant authority to Clients which now have the Entity
+        self.send_pending_grants();
+
         // loop through all connections, send packet
         let mut user_addresses: Vec<SocketAddr> = self.user_connections.keys().copied().collect();
         fastrand::shuffle(&mut user_addresses);
@@ -380,6 +544,28 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         world.entities()
     }
 
+    /// Returns the User which spawned the given Entity, if it was spawned by
+    /// a Client rather than the Server
+    pub fn entity_owner(&self, entity: &E) -> Option<UserKey> {
+        self.world_record.entity_owner(entity)
+    }
+
+    /// Returns the parent of the given Entity, if it has one
+    pub fn entity_parent(&self, entity: &E) -> Option<E> {
+        self.world_record.entity_parent(entity)
+    }
+
+    /// Returns the children of the given Entity
+    pub fn entity_children(&self, entity: &E) -> Vec<E> {
+        self.world_record.entity_children(entity)
+    }
+
+    /// Returns the User whose Client has authority over the given Entity, if
+    /// it has been given to one with `EntityMut::give_authority()`
+    pub fn entity_authority(&self, entity: &E) -> Option<UserKey> {
+        self.world_record.entity_authority(entity)
+    }
+
     // Users
 
     /// Returns whether or not a User exists for the given RoomKey
@@ -432,6 +618,89 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         panic!("No User exists for given Key!");
     }
 
+    /// Only replicates Components of the given type to the Users for whom
+    /// the predicate returns true, for each Entity in their scope. This is
+    /// evaluated when an Entity enters a User's scope, and again after a call
+    /// to `mark_entity_scope_dirty()` or `mark_user_scope_dirty()`, in
+    /// addition to Components excluded with
+    /// `UserScopeMut::exclude_component()`
+    pub fn set_component_scope<R: Replicate<P>, F>(&mut self, predicate: F)
+    where
+        F: Fn(&UserKey, &E) -> bool + Send + Sync + 'static,
+    {
+        self.component_scope_map
+            .set_predicate(&P::kind_of::<R>(), Box::new(predicate));
+        self.scope_candidate_map.mark_all_changed();
+    }
+
+    /// Replicates Components of the given type to every User again, after a
+    /// call to `set_component_scope()`
+    pub fn clear_component_scope<R: Replicate<P>>(&mut self) {
+        self.component_scope_map
+            .remove_predicate(&P::kind_of::<R>());
+        self.scope_candidate_map.mark_all_changed();
+    }
+
+    /// Only replicates the Properties of Components of the given type which
+    /// are marked with `#[replicate(conditional)]` to the Users for whom the
+    /// predicate returns true. Other Users receive default values for them.
+    /// Without a predicate, conditional Properties are hidden from every User.
+    /// Like `set_component_scope()`, the predicate is evaluated when an Entity
+    /// enters a User's scope, or is marked dirty
+    pub fn set_property_scope<R: Replicate<P>, F>(&mut self, predicate: F)
+    where
+        F: Fn(&UserKey, &E) -> bool + Send + Sync + 'static,
+    {
+        self.component_scope_map
+            .set_property_predicate(&P::kind_of::<R>(), Box::new(predicate));
+        self.scope_candidate_map.mark_all_changed();
+    }
+
+    /// Hides the conditional Properties of Components of the given type from
+    /// every User again, after a call to `set_property_scope()`
+    pub fn clear_property_scope<R: Replicate<P>>(&mut self) {
+        self.component_scope_map
+            .remove_property_predicate(&P::kind_of::<R>());
+        self.scope_candidate_map.mark_all_changed();
+    }
+
+    // Spatial Scope
+
+    /// Sets the position of an Entity in the spatial index, which includes it
+    /// in the scope of every User whose view covers that position, and
+    /// excludes it from the rest. The Entity & User must still share a Room.
+    /// Only the Users near the Entity are reevaluated, so this is cheap to
+    /// call every tick for each Entity which moved.
+    /// Panics if `ServerConfig::spatial_scope` is None
+    pub fn set_entity_position(&mut self, entity: &E, x: f32, y: f32) {
+        self.spatial_grid_mut().set_entity_position(entity, x, y);
+    }
+
+    /// Removes an Entity from the spatial index, excluding it from the scope
+    /// of every User who could see it. Despawned Entities are removed
+    /// automatically.
+    /// Panics if `ServerConfig::spatial_scope` is None
+    pub fn remove_entity_position(&mut self, entity: &E) {
+        self.spatial_grid_mut().remove_entity(entity);
+    }
+
+    /// Sets the view of a User in the spatial index, as a circle of the given
+    /// radius. Entities positioned within it are included in the User's scope,
+    /// and the rest are excluded.
+    /// Panics if `ServerConfig::spatial_scope` is None
+    pub fn set_user_view(&mut self, user_key: &UserKey, x: f32, y: f32, radius: f32) {
+        self.spatial_grid_mut()
+            .set_user_view(user_key, x, y, radius);
+    }
+
+    /// Removes the view of a User from the spatial index, excluding every
+    /// Entity they could see from their scope. Disconnected Users are removed
+    /// automatically.
+    /// Panics if `ServerConfig::spatial_scope` is None
+    pub fn remove_user_view(&mut self, user_key: &UserKey) {
+        self.spatial_grid_mut().remove_user(user_key);
+    }
+
     // Rooms
 
     /// Creates a new Room on the Server and returns a corresponding RoomMut,
@@ -504,6 +773,92 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
             .map(|tick_manager| tick_manager.server_tick());
     }
 
+    // Lag Compensation
+
+    /// Keeps a history of the states of Components of the given type, as
+    /// sent each tick, so that they can be rewound to what a Client saw with
+    /// `lag_compensated_component()`. Requires a tick interval to be set in
+    /// the SharedConfig
+    pub fn enable_lag_compensation<R: Replicate<P>>(&mut self) {
+        self.lag_compensation.enable(&P::kind_of::<R>());
+    }
+
+    /// Gets the Server tick whose states the given User's Client was
+    /// rendering when it sent something at the given Client tick, accounting
+    /// for its latency and interpolation delay
+    ///
+    /// The Client's sending offset is not sent to the Server, so it is assumed
+    /// to be the Client's round trip time plus jitter buffer. This is not the
+    /// case for Clients with a larger `ClientConfig::minimum_latency`
+    pub fn lag_compensated_tick(&self, user_key: &UserKey, client_tick: Tick) -> Option<Tick> {
+        let tick_interval = self.shared_config.tick_interval?;
+        let user = self.users.get(user_key)?;
+        let connection = self.user_connections.get(&user.address)?;
+        Some(rendered_tick(
+            client_tick,
+            connection.ping_manager.rtt,
+            connection.ping_manager.jitter,
+            tick_interval.as_secs_f32() * 1000.0,
+            self.server_config
+                .lag_compensation
+                .interpolation_delay_ticks,
+        ))
+    }
+
+    /// Gets the state of an Entity's Component as the given User's Client
+    /// rendered it when it sent something at the given Client tick. Rewinding
+    /// is limited to the history kept. Returns None if lag compensation is
+    /// not enabled for the Component or the Entity did not have it then
+    pub fn lag_compensated_component<R: Replicate<P>>(
+        &self,
+        user_key: &UserKey,
+        client_tick: Tick,
+        entity: &E,
+    ) -> Option<R> {
+        let tick = self.lag_compensated_tick(user_key, client_tick)?;
+        self.lag_compensation
+            .state(entity, &P::kind_of::<R>(), tick)?
+            .clone()
+            .cast::<R>()
+    }
+
+    /// Gets the state of every Component of the given type as the given
+    /// User's Client rendered them when it sent something at the given Client
+    /// tick, along with the Entity each is attached to
+    pub fn lag_compensated_components<R: Replicate<P>>(
+        &self,
+        user_key: &UserKey,
+        client_tick: Tick,
+    ) -> Vec<(E, R)> {
+        let tick = match self.lag_compensated_tick(user_key, client_tick) {
+            Some(tick) => tick,
+            None => return Vec::new(),
+        };
+        self.lag_compensation
+            .states(&P::kind_of::<R>(), tick)
+            .into_iter()
+            .filter_map(|(entity, state)| state.clone().cast::<R>().map(|state| (entity, state)))
+            .collect()
+    }
+
+    // Compression
+
+    /// Replaces the compression dictionary used with
+    /// `CompressionMode::ServerDictionary`, returning its version. Clients
+    /// fetch the new dictionary in the background, and each connection
+    /// switches over to it once its Client has received the whole thing.
+    /// Clients which connect afterwards receive it during the handshake.
+    pub fn set_compression_dictionary(&mut self, dictionary: Vec<u8>) -> u8 {
+        self.io.set_dictionary(dictionary)
+    }
+
+    /// Returns the dictionary trained with `CompressionMode::Training` on
+    /// outgoing packets, once enough samples have been collected. Pass it to
+    /// `set_compression_dictionary()` to start using it without restarting.
+    pub fn take_trained_dictionary(&mut self) -> Option<Vec<u8>> {
+        self.io.take_trained_dictionary()
+    }
+
     // Bandwidth monitoring
     pub fn outgoing_bandwidth_total(&mut self) -> f32 {
         self.io.outgoing_bandwidth_total()
@@ -521,6 +876,26 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         self.io.incoming_bandwidth_from_client(address)
     }
 
+    /// Ratio of compressed to uncompressed bytes sent to all Clients, over the
+    /// configured bandwidth measure duration
+    pub fn outgoing_compression_ratio_total(&mut self) -> f32 {
+        self.io.outgoing_compression_ratio_total()
+    }
+
+    /// Ratio of compressed to uncompressed bytes received from all Clients,
+    /// over the configured bandwidth measure duration
+    pub fn incoming_compression_ratio_total(&mut self) -> f32 {
+        self.io.incoming_compression_ratio_total()
+    }
+
+    pub fn outgoing_compression_ratio_to_client(&mut self, address: &SocketAddr) -> f32 {
+        self.io.outgoing_compression_ratio_to_client(address)
+    }
+
+    pub fn incoming_compression_ratio_from_client(&mut self, address: &SocketAddr) -> f32 {
+        self.io.incoming_compression_ratio_from_client(address)
+    }
+
     // Ping
     /// Gets the average Round Trip Time measured to the given User's Client
     pub fn rtt(&self, user_key: &UserKey) -> Option<f32> {
@@ -556,26 +931,87 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
             panic!("attempted to de-spawn nonexistent entity");
         }
 
+        // the Entity's children are despawned along with it, parents first so
+        // that Clients can despawn each hierarchy at once
+        let entity_tree = self.world_record.entity_tree(entity);
+
         // TODO: we can make this more efficient in the future by caching which Entities
         // are in each User's scope
         for (_, user_connection) in self.user_connections.iter_mut() {
-            //remove entity from user connection
-            user_connection.entity_manager.despawn_entity(entity);
+            for tree_entity in &entity_tree {
+                //remove entity from user connection
+                user_connection.entity_manager.despawn_entity(tree_entity);
+                user_connection.pending_grants.remove(tree_entity);
+            }
         }
 
-        // Clean up associated components
-        for component_kind in self.world_record.component_kinds(entity).unwrap() {
-            self.component_cleanup(entity, &component_kind);
+        for tree_entity in entity_tree.iter().rev() {
+            self.despawn_entity_cleanup(world, tree_entity);
         }
+    }
 
-        // Delete from world
-        world.despawn_entity(entity);
+    pub(crate) fn entity_set_parent(&mut self, entity: &E, parent_opt: Option<E>) {
+        if self.world_record.entity_parent(entity) == parent_opt {
+            return;
+        }
 
-        // Delete scope
-        self.entity_scope_map.remove_entity(entity);
+        self.world_record.entity_set_parent(entity, parent_opt);
 
-        // Remove from ECS Record
-        self.world_record.despawn_entity(entity);
+        // Clients receive the Entity again as a child of its new parent, once
+        // the parent is in scope for them
+        let entity_tree = self.world_record.entity_tree(entity);
+        for (_, user_connection) in self.user_connections.iter_mut() {
+            if user_connection.entity_manager.scope_has_entity(entity) {
+                for tree_entity in &entity_tree {
+                    user_connection.entity_manager.despawn_entity(tree_entity);
+                }
+            }
+        }
+        self.mark_entity_tree_changed(entity);
+    }
+
+    pub(crate) fn entity_give_authority(&mut self, entity: &E, user_key: &UserKey) {
+        if self.world_record.entity_owner(entity).is_some() {
+            panic!("cannot give authority over an Entity which was spawned by a Client");
+        }
+        if self.world_record.entity_authority(entity) == Some(*user_key) {
+            return;
+        }
+
+        self.entity_take_authority(entity);
+
+        if let Some(address) = self.user_address(user_key) {
+            if let Some(connection) = self.user_connections.get_mut(&address) {
+                self.world_record
+                    .entity_set_authority(entity, Some(*user_key));
+                connection.pending_grants.insert(*entity);
+            }
+        }
+        self.mark_entity_tree_changed(entity);
+    }
+
+    pub(crate) fn entity_take_authority(&mut self, entity: &E) {
+        if let Some(user_key) = self.world_record.entity_authority(entity) {
+            self.world_record.entity_set_authority(entity, None);
+            self.mark_entity_tree_changed(entity);
+
+            if let Some(address) = self.user_address(&user_key) {
+                if let Some(connection) = self.user_connections.get_mut(&address) {
+                    if !connection.pending_grants.remove(entity) {
+                        if let Some(net_entity) =
+                            connection.entity_manager.host_entity_to_net_entity(entity)
+                        {
+                            connection
+                                .authority_channel
+                                .send_action(net_entity, AuthorityAction::Revoke);
+                        }
+                    }
+                }
+            }
+
+            self.incoming_events
+                .push_back(Ok(Event::AuthorityRevoked(user_key, *entity)));
+        }
     }
 
     //// Entity Scopes
@@ -586,8 +1022,27 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         entity: &E,
         is_contained: bool,
     ) {
+        if self.entity_scope_map.get(user_key, entity) == Some(&is_contained) {
+            return;
+        }
         self.entity_scope_map
             .insert(*user_key, *entity, is_contained);
+        self.scope_candidate_map
+            .mark_candidate_changed(user_key, entity);
+    }
+
+    pub(crate) fn user_scope_set_component(
+        &mut self,
+        user_key: &UserKey,
+        entity: &E,
+        component_kind: &P::Kind,
+        is_contained: bool,
+    ) {
+        self.component_scope_map
+            .set_excluded(user_key, entity, component_kind, !is_contained);
+        let root = self.world_record.entity_root(entity);
+        self.scope_candidate_map
+            .mark_candidate_changed(user_key, &root);
     }
 
     //// Components
@@ -620,7 +1075,21 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         // add component to connections already tracking entity
         for (_, user_connection) in self.user_connections.iter_mut() {
             // insert component into user's connection
-            if user_connection.entity_manager.scope_has_entity(entity) {
+            if user_connection.entity_manager.scope_has_entity(entity)
+                && self.component_scope_map.is_visible(
+                    &user_connection.user_key,
+                    entity,
+                    &component_kind,
+                )
+            {
+                Self::update_hidden_properties(
+                    world,
+                    &self.world_record,
+                    &self.component_scope_map,
+                    user_connection,
+                    entity,
+                    &component_kind,
+                );
                 user_connection
                     .entity_manager
                     .insert_component(entity, &component_kind);
@@ -668,8 +1137,20 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     /// All necessary cleanup, when they're actually gone...
     pub(crate) fn delete_user(&mut self, user_key: &UserKey) -> Option<User> {
         if let Some(user) = self.users.remove(user_key) {
+            self.pending_users.remove(&user.address);
+
             if self.user_connections.remove(&user.address).is_some() {
                 self.entity_scope_map.remove_user(user_key);
+                self.component_scope_map.remove_user(user_key);
+                self.scope_candidate_map.remove_user(user_key);
+                if let Some(spatial_grid) = &mut self.spatial_grid {
+                    spatial_grid.remove_user(user_key);
+                }
+                self.world_record.user_release_entities(user_key);
+                for entity in self.world_record.user_release_authority(user_key) {
+                    self.incoming_events
+                        .push_back(Ok(Event::AuthorityLost(*user_key, entity)));
+                }
                 self.handshake_manager.delete_user(&user.address);
 
                 // TODO: cache this?
@@ -678,9 +1159,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                     room.unsubscribe_user(user_key);
                 }
 
-                if self.io.bandwidth_monitor_enabled() {
-                    self.io.deregister_client(&user.address);
-                }
+                self.io.deregister_client(&user.address);
 
                 return Some(user);
             }
@@ -724,7 +1203,16 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     /// Room with them
     pub(crate) fn room_add_user(&mut self, room_key: &RoomKey, user_key: &UserKey) {
         if let Some(room) = self.rooms.get_mut(room_key) {
+            if room.has_user(user_key) {
+                return;
+            }
             room.subscribe_user(user_key);
+            for entity in room.entities() {
+                // despawned Entities are never candidates
+                if self.world_record.has_entity(entity) {
+                    self.scope_candidate_map.insert(room_key, user_key, entity);
+                }
+            }
         }
     }
 
@@ -732,6 +1220,9 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     pub(crate) fn room_remove_user(&mut self, room_key: &RoomKey, user_key: &UserKey) {
         if let Some(room) = self.rooms.get_mut(room_key) {
             room.unsubscribe_user(user_key);
+            for entity in room.entities() {
+                self.scope_candidate_map.remove(room_key, user_key, entity);
+            }
         }
     }
 
@@ -758,6 +1249,9 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         let mut is_some = false;
         if let Some(room) = self.rooms.get_mut(room_key) {
             room.add_entity(entity);
+            for user_key in room.user_keys() {
+                self.scope_candidate_map.insert(room_key, user_key, entity);
+            }
             is_some = true;
         }
         if is_some {
@@ -769,6 +1263,9 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
     pub(crate) fn room_remove_entity(&mut self, room_key: &RoomKey, entity: &E) {
         if let Some(room) = self.rooms.get_mut(room_key) {
             room.remove_entity(entity);
+            for user_key in room.user_keys() {
+                self.scope_candidate_map.remove(room_key, user_key, entity);
+            }
             self.world_record.entity_leave_rooms(entity);
         }
     }
@@ -779,6 +1276,9 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
             let entities: Vec<E> = room.entities().copied().collect();
             for entity in entities {
                 room.remove_entity(&entity);
+                for user_key in room.user_keys() {
+                    self.scope_candidate_map.remove(room_key, user_key, &entity);
+                }
                 self.world_record.entity_leave_rooms(&entity);
             }
         }
@@ -812,6 +1312,18 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
             for user_key in user_disconnects {
                 self.disconnect_user(&user_key);
             }
+
+            // forget about Users whose auth was never decided on in time
+            let timeout = self.server_config.connection.disconnection_timeout_duration;
+            let expired_users: Vec<UserKey> = self
+                .pending_users
+                .values()
+                .filter(|(_, pending_since)| pending_since.elapsed() > timeout)
+                .map(|(user_key, _)| *user_key)
+                .collect();
+            for user_key in expired_users {
+                self.delete_user(&user_key);
+            }
         }
 
         // heartbeats
@@ -840,6 +1352,9 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                     connection.base.mark_sent();
                 }
             }
+
+            // let Clients know about a new compression dictionary
+            self.io.send_dictionary_announcements();
         }
 
         // pings
@@ -868,6 +1383,28 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                     self.io.send_writer(user_address, &mut writer);
                     connection.base.mark_sent();
                 }
+
+                // send path MTU probes
+                if let Some(probe_size) = connection.mtu_manager.next_probe_size() {
+                    let mut writer = BitWriter::with_max_size(probe_size as usize);
+
+                    // write header
+                    connection
+                        .base
+                        .write_outgoing_header(PacketType::MtuProbe, &mut writer);
+
+                    // write server tick
+                    if let Some(tick_manager) = self.tick_manager.as_mut() {
+                        tick_manager.write_server_tick(&mut writer);
+                    }
+
+                    // write body
+                    MtuManager::write_probe(probe_size, &mut writer);
+
+                    // send packet
+                    self.io.send_writer(user_address, &mut writer);
+                    connection.base.mark_sent();
+                }
             }
         }
 
@@ -889,14 +1426,75 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                     // Handshake stuff
                     match header.packet_type {
                         PacketType::ClientChallengeRequest => {
-                            if let Ok(mut writer) =
-                                self.handshake_manager.recv_challenge_request(&mut reader)
+                            if !self.allow_handshake_packet(&address) {
+                                continue;
+                            }
+                            if self.is_full() && !self.user_connections.contains_key(&address) {
+                                let mut writer = self
+                                    .handshake_manager
+                                    .write_reject_response(RejectReason::ServerFull);
+                                self.io.send_writer(&address, &mut writer);
+                                continue;
+                            }
+
+                            let dictionary_version = self.io.dictionary_version();
+                            if let Ok(mut writer) = self
+                                .handshake_manager
+                                .recv_challenge_request(&mut reader, dictionary_version)
                             {
                                 self.io.send_writer(&address, &mut writer);
                             }
                             continue;
                         }
+                        PacketType::ClientInfoRequest => {
+                            if !self.allow_info_query(&address) {
+                                continue;
+                            }
+
+                            if let Ok(query_id) = u64::de(&mut reader) {
+                                let mut writer = self.server_info().write_response(query_id);
+                                // never answer with more than was received, so
+                                // queries from spoofed addresses can't be used
+                                // to amplify traffic
+                                if self.io.max_sent_length(&writer) > self.io.last_received_length()
+                                {
+                                    continue;
+                                }
+                                self.io.send_writer(&address, &mut writer);
+                            }
+                            continue;
+                        }
+                        PacketType::ClientDictionaryRequest => {
+                            if !self.user_connections.contains_key(&address)
+                                && !self.allow_handshake_packet(&address)
+                            {
+                                continue;
+                            }
+
+                            if let Some((version, next_chunk)) =
+                                self.handshake_manager.recv_dictionary_request(&mut reader)
+                            {
+                                if let Some(mut writer) = self
+                                    .io
+                                    .recv_dictionary_request(&address, version, next_chunk)
+                                {
+                                    // never answer with more than was received,
+                                    // as with server info queries
+                                    if self.io.max_sent_length(&writer)
+                                        > self.io.last_received_length()
+                                    {
+                                        continue;
+                                    }
+                                    self.io.send_writer(&address, &mut writer);
+                                }
+                            }
+                            continue;
+                        }
                         PacketType::ClientConnectRequest => {
+                            if !self.allow_handshake_packet(&address) {
+                                continue;
+                            }
+
                             match self
                                 .handshake_manager
                                 .recv_connect_request(&address, &mut reader)
@@ -908,11 +1506,21 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
                                             self.handshake_manager.write_connect_response();
                                         self.io.send_writer(&address, &mut writer);
                                         //
-                                    } else {
+                                    } else if self.pending_users.contains_key(&address) {
+                                        // still awaiting a decision on this
+                                        // User's auth message
+                                    } else if self.is_full() {
+                                        let mut writer = self
+                                            .handshake_manager
+                                            .write_reject_response(RejectReason::ServerFull);
+                                        self.io.send_writer(&address, &mut writer);
+                                    } else if self.can_add_user(&address) {
                                         let user = User::new(address);
                                         let user_key = self.users.insert(user);
 
                                         if let Some(auth_message) = auth_message_opt {
+                                            self.pending_users
+                                                .insert(address, (user_key, Instant::now()));
                                             self.incoming_events.push_back(Ok(
                                                 Event::Authorization(user_key, auth_message),
                                             ));
@@ -1057,6 +1665,68 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
 
                                 user_connection.ping_manager.process_pong(&mut reader);
                             }
+                            PacketType::MtuProbe => {
+                                // read client tick
+                                if let Some(tick_manager) = self.tick_manager.as_ref() {
+                                    ////
+                                    let client_tick_result =
+                                        tick_manager.read_client_tick(&mut reader);
+                                    if client_tick_result.is_err() {
+                                        // Received a malformed packet
+                                        // TODO: increase suspicion against packet sender
+                                        continue;
+                                    }
+                                    let client_tick = client_tick_result.unwrap();
+                                    user_connection.recv_client_tick(client_tick);
+                                    ////
+                                }
+
+                                // read incoming probe size
+                                let probe_size_result = MtuManager::read_probe(&mut reader);
+                                if probe_size_result.is_err() {
+                                    // Received a malformed packet
+                                    continue;
+                                }
+                                let probe_size = probe_size_result.unwrap();
+
+                                // write probe ack payload
+                                let mut writer = BitWriter::new();
+
+                                // write header
+                                user_connection
+                                    .base
+                                    .write_outgoing_header(PacketType::MtuProbeAck, &mut writer);
+
+                                // write server tick
+                                if let Some(tick_manager) = self.tick_manager.as_ref() {
+                                    tick_manager.write_server_tick(&mut writer);
+                                }
+
+                                // write size
+                                probe_size.ser(&mut writer);
+
+                                // send packet
+                                self.io.send_writer(&address, &mut writer);
+                                user_connection.base.mark_sent();
+                            }
+                            PacketType::MtuProbeAck => {
+                                // read client tick
+                                if let Some(tick_manager) = self.tick_manager.as_ref() {
+                                    ////
+                                    let client_tick_result =
+                                        tick_manager.read_client_tick(&mut reader);
+                                    if client_tick_result.is_err() {
+                                        // Received a malformed packet
+                                        // TODO: increase suspicion against packet sender
+                                        continue;
+                                    }
+                                    let client_tick = client_tick_result.unwrap();
+                                    user_connection.recv_client_tick(client_tick);
+                                    ////
+                                }
+
+                                user_connection.mtu_manager.process_probe_ack(&mut reader);
+                            }
                             _ => {}
                         }
                     }
@@ -1073,6 +1743,77 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         }
     }
 
+    fn reject_user(&mut self, user_key: &UserKey, reason: RejectReason) {
+        if let Some(user) = self.users.get(user_key) {
+            // send connect reject response
+            let mut writer = self.handshake_manager.write_reject_response(reason);
+            self.io.send_writer(&user.address, &mut writer);
+            //
+        }
+        self.delete_user(user_key);
+    }
+
+    fn server_info(&self) -> ServerInfo<P> {
+        ServerInfo {
+            user_count: self.user_connections.len() as u32,
+            max_users: self
+                .server_config
+                .max_users
+                .map(|max_users| max_users as u32),
+            tick_interval: self.shared_config.tick_interval,
+            protocol_fingerprint: P::protocol_fingerprint(),
+            info: self.server_info.clone(),
+        }
+    }
+
+    // Whether the Server has reached its maximum number of connected Users
+    fn is_full(&self) -> bool {
+        self.server_config
+            .max_users
+            .is_some_and(|max_users| self.user_connections.len() >= max_users)
+    }
+
+    // Whether a new User can be added for the given address, without going
+    // over the limits on pending handshakes and connections per IP address
+    fn can_add_user(&self, address: &SocketAddr) -> bool {
+        if let Some(max_pending_handshakes) = self.server_config.max_pending_handshakes {
+            if self.pending_users.len() >= max_pending_handshakes {
+                return false;
+            }
+        }
+
+        if let Some(max_connections_per_ip) = self.server_config.max_connections_per_ip {
+            let ip_connections = self
+                .users
+                .iter()
+                .filter(|(_, user)| user.address.ip() == address.ip())
+                .count();
+            if ip_connections >= max_connections_per_ip {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // Whether a handshake packet from the given address is within the rate
+    // limit
+    fn allow_handshake_packet(&mut self, address: &SocketAddr) -> bool {
+        match self.handshake_rate_limiter.as_mut() {
+            Some(rate_limiter) => rate_limiter.allow(&address.ip()),
+            None => true,
+        }
+    }
+
+    // Whether a server info query from the given address is within the rate
+    // limit
+    fn allow_info_query(&mut self, address: &SocketAddr) -> bool {
+        match self.info_query_rate_limiter.as_mut() {
+            Some(rate_limiter) => rate_limiter.allow(&address.ip()),
+            None => true,
+        }
+    }
+
     pub(crate) fn disconnect_user(&mut self, user_key: &UserKey) {
         if let Some(user) = self.delete_user(user_key) {
             self.incoming_events
@@ -1080,69 +1821,564 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         }
     }
 
+    // Tick Helpers
+
+    fn tick_to_instant(&self, tick: Tick) -> Instant {
+        let tick_manager = self
+            .tick_manager
+            .as_ref()
+            .expect("Server must be configured with a tick interval to use Tick deadlines");
+        let tick_interval = self.shared_config.tick_interval.unwrap();
+
+        let ticks_remaining = wrapping_diff(tick_manager.server_tick(), tick).max(0) as u32;
+
+        let mut instant = Instant::now();
+        instant.add_millis(ticks_remaining * tick_interval.as_millis() as u32);
+        instant
+    }
+
+    // Lag Compensation
+
+    fn record_lag_compensation<W: WorldRefType<P, E>>(&mut self, world: &W) {
+        if !self.lag_compensation.has_kinds() {
+            return;
+        }
+        let server_tick = match self.server_tick() {
+            Some(server_tick) => server_tick,
+            None => return,
+        };
+
+        let mut states = HashMap::new();
+        for entity in self.world_record.entities() {
+            for component_kind in self.world_record.component_kinds(&entity).unwrap() {
+                if !self.lag_compensation.is_enabled(&component_kind) {
+                    continue;
+                }
+                if let Some(component) = world.component_of_kind(&entity, &component_kind) {
+                    states.insert((entity, component_kind), component.protocol_copy());
+                }
+            }
+        }
+        self.lag_compensation.record(server_tick, states);
+    }
+
     // Entity Helpers
 
     fn spawn_entity_init(&mut self, entity: &E) {
         self.world_record.spawn_entity(entity);
     }
 
+    fn despawn_entity_cleanup<W: WorldMutType<P, E>>(&mut self, world: &mut W, entity: &E) {
+        // stop accepting changes from the owning Client
+        if let Some(owner_key) = self.world_record.entity_owner(entity) {
+            if let Some(user) = self.users.get(&owner_key) {
+                if let Some(owner_connection) = self.user_connections.get_mut(&user.address) {
+                    owner_connection.remote_entity_manager.remove_entity(entity);
+                }
+            }
+        }
+
+        // Clean up associated components
+        for component_kind in self.world_record.component_kinds(entity).unwrap() {
+            self.component_cleanup(entity, &component_kind);
+        }
+
+        // Delete from world
+        world.despawn_entity(entity);
+
+        // Delete scope
+        self.entity_scope_map.remove_entity(entity);
+        self.component_scope_map.remove_entity(entity);
+        self.scope_candidate_map.remove_entity(entity);
+        if let Some(spatial_grid) = &mut self.spatial_grid {
+            spatial_grid.remove_entity(entity);
+        }
+
+        // Remove from ECS Record
+        self.world_record.despawn_entity(entity);
+    }
+
     // Entity Scopes
 
+    fn spatial_grid_mut(&mut self) -> &mut SpatialGrid<UserKey, E> {
+        self.spatial_grid
+            .as_mut()
+            .expect("Server must be configured with a spatial scope to use positions & views")
+    }
+
+    // Reevaluates the scope of the tree the Entity belongs to, for every User
+    fn mark_entity_tree_changed(&mut self, entity: &E) {
+        let root = self.world_record.entity_root(entity);
+        self.scope_candidate_map.mark_entity_changed(&root);
+    }
+
+    fn update_spatial_scopes(&mut self) {
+        let scope_changes = match &mut self.spatial_grid {
+            Some(spatial_grid) => spatial_grid.take_scope_changes(),
+            None => return,
+        };
+
+        for (user_key, entity, in_scope) in scope_changes {
+            // skip Users & Entities which have since been removed
+            if !self.users.contains_key(&user_key) || !self.world_record.has_entity(&entity) {
+                continue;
+            }
+            if in_scope {
+                self.user_scope(&user_key).include(&entity);
+            } else {
+                self.user_scope(&user_key).exclude(&entity);
+            }
+        }
+    }
+
     fn update_entity_scopes<W: WorldRefType<P, E>>(&mut self, world: &W) {
+        let mut removed_scopes: Vec<(UserKey, E)> = Vec::new();
+
         for (_, room) in self.rooms.iter_mut() {
             while let Some((removed_user, removed_entity)) = room.pop_entity_removal_queue() {
+                // the scope of a child follows its parent
+                if self.world_record.entity_parent(&removed_entity).is_some() {
+                    continue;
+                }
                 if let Some(user) = self.users.get(&removed_user) {
                     if let Some(user_connection) = self.user_connections.get_mut(&user.address) {
                         //remove entity from user connection
-                        user_connection
-                            .entity_manager
-                            .despawn_entity(&removed_entity);
+                        for tree_entity in self.world_record.entity_tree(&removed_entity) {
+                            if user_connection
+                                .entity_manager
+                                .scope_has_entity(&tree_entity)
+                            {
+                                user_connection.entity_manager.despawn_entity(&tree_entity);
+                                removed_scopes.push((removed_user, tree_entity));
+                            }
+                        }
                     }
                 }
+                // the User & Entity may still share another Room
+                self.scope_candidate_map
+                    .mark_candidate_changed(&removed_user, &removed_entity);
             }
+        }
 
-            // TODO: we should be able to cache these tuples of keys to avoid building a new
-            // list each time
-            for user_key in room.user_keys() {
-                for entity in room.entities() {
-                    if world.has_entity(entity) {
-                        if let Some(user) = self.users.get(user_key) {
-                            if let Some(user_connection) =
-                                self.user_connections.get_mut(&user.address)
-                            {
-                                let currently_in_scope =
-                                    user_connection.entity_manager.scope_has_entity(entity);
+        // only the candidates which are new, or whose scope may have changed,
+        // need to be reevaluated
+        let mut unfinished_candidates: Vec<(UserKey, E)> = Vec::new();
 
-                                let should_be_in_scope = if let Some(in_scope) =
-                                    self.entity_scope_map.get(user_key, entity)
-                                {
-                                    *in_scope
-                                } else {
-                                    false
-                                };
+        let changed_candidates = self.scope_candidate_map.take_changed_candidates();
+        for (user_key, entity) in &changed_candidates {
+            // the scope of a child follows its parent
+            if !world.has_entity(entity) || self.world_record.entity_parent(entity).is_some() {
+                continue;
+            }
+            if let Some(user) = self.users.get(user_key) {
+                if let Some(user_connection) = self.user_connections.get_mut(&user.address) {
+                    let root_in_scope =
+                        if let Some(in_scope) = self.entity_scope_map.get(user_key, entity) {
+                            *in_scope
+                        } else {
+                            false
+                        };
+
+                    for tree_entity in self.world_record.entity_tree(entity) {
+                        let currently_in_scope = user_connection
+                            .entity_manager
+                            .scope_has_entity(&tree_entity);
 
-                                if should_be_in_scope {
-                                    if !currently_in_scope {
-                                        // add entity to the connections local scope
-                                        user_connection.entity_manager.spawn_entity(entity);
-                                        // add components to connections local scope
-                                        for component_kind in
-                                            self.world_record.component_kinds(entity).unwrap()
+                        // the owning Client already has the Entity
+                        let is_owner =
+                            self.world_record.entity_owner(&tree_entity) == Some(*user_key);
+
+                        let should_be_in_scope = root_in_scope && !is_owner;
+
+                        if should_be_in_scope {
+                            if currently_in_scope {
+                                // keep the Components the User can see in sync
+                                for component_kind in
+                                    self.world_record.component_kinds(&tree_entity).unwrap()
+                                {
+                                    let is_visible = self.component_scope_map.is_visible(
+                                        user_key,
+                                        &tree_entity,
+                                        &component_kind,
+                                    );
+                                    let has_component = user_connection
+                                        .entity_manager
+                                        .scope_has_component(&tree_entity, &component_kind);
+                                    if is_visible {
+                                        Self::update_hidden_properties(
+                                            world,
+                                            &self.world_record,
+                                            &self.component_scope_map,
+                                            user_connection,
+                                            &tree_entity,
+                                            &component_kind,
+                                        );
+                                    }
+                                    if is_visible && !has_component {
+                                        user_connection
+                                            .entity_manager
+                                            .insert_component(&tree_entity, &component_kind);
+                                    } else if !is_visible && has_component {
+                                        user_connection
+                                            .entity_manager
+                                            .remove_component(&tree_entity, &component_kind);
+                                    }
+                                }
+                            } else {
+                                // add entity to the connections local scope, children
+                                // only once the Client has their parent
+                                match self.world_record.entity_parent(&tree_entity) {
+                                    None => {
+                                        user_connection.entity_manager.spawn_entity(&tree_entity);
+                                    }
+                                    Some(parent) => {
+                                        if !user_connection
+                                            .entity_manager
+                                            .entity_channel_is_open(&parent)
                                         {
-                                            user_connection
-                                                .entity_manager
-                                                .insert_component(entity, &component_kind);
+                                            // try again next tick
+                                            unfinished_candidates.push((*user_key, *entity));
+                                            continue;
                                         }
+                                        user_connection
+                                            .entity_manager
+                                            .spawn_child_entity(&tree_entity, &parent);
                                     }
-                                } else if currently_in_scope {
-                                    // remove entity from the connections local scope
-                                    user_connection.entity_manager.despawn_entity(entity);
+                                }
+                                // add visible components to connections local scope
+                                for component_kind in
+                                    self.world_record.component_kinds(&tree_entity).unwrap()
+                                {
+                                    if !self.component_scope_map.is_visible(
+                                        user_key,
+                                        &tree_entity,
+                                        &component_kind,
+                                    ) {
+                                        continue;
+                                    }
+                                    Self::update_hidden_properties(
+                                        world,
+                                        &self.world_record,
+                                        &self.component_scope_map,
+                                        user_connection,
+                                        &tree_entity,
+                                        &component_kind,
+                                    );
+                                    user_connection
+                                        .entity_manager
+                                        .insert_component(&tree_entity, &component_kind);
                                 }
                             }
+                        } else if currently_in_scope {
+                            // remove entity from the connections local scope
+                            user_connection.entity_manager.despawn_entity(&tree_entity);
+                            removed_scopes.push((*user_key, tree_entity));
+                        }
+                    }
+                }
+            }
+        }
+
+        for (user_key, entity) in unfinished_candidates {
+            self.scope_candidate_map
+                .mark_candidate_changed(&user_key, &entity);
+        }
+
+        // a Client can't keep authority over an Entity it no longer has
+        for (user_key, entity) in removed_scopes {
+            if self.world_record.entity_authority(&entity) == Some(user_key) {
+                self.entity_take_authority(&entity);
+            }
+        }
+    }
+
+    // Hides the owner-only and conditional Properties of a Component from a
+    // User which may not see them
+    fn update_hidden_properties<W: WorldRefType<P, E>>(
+        world: &W,
+        world_record: &WorldRecord<E, P::Kind>,
+        component_scope_map: &ComponentScopeMap<E, P::Kind>,
+        user_connection: &mut Connection<P, E, C>,
+        entity: &E,
+        component_kind: &P::Kind,
+    ) {
+        let hidden_properties = match world.component_of_kind(entity, component_kind) {
+            Some(component) => {
+                let user_key = user_connection.user_key;
+                let is_owner = world_record.entity_owner(entity) == Some(user_key)
+                    || world_record.entity_authority(entity) == Some(user_key);
+                component_scope_map.hidden_properties(
+                    &user_key,
+                    entity,
+                    component_kind,
+                    is_owner,
+                    &component.owner_only_properties(),
+                    &component.conditional_properties(),
+                )
+            }
+            None => None,
+        };
+        user_connection.entity_manager.set_hidden_properties(
+            entity,
+            component_kind,
+            hidden_properties,
+        );
+    }
+
+    // Client-owned Entities
+
+    fn process_remote_entity_action<W: WorldMutType<P, E>>(
+        &mut self,
+        world: &mut W,
+        user_key: &UserKey,
+        action: RemoteEntityAction<P>,
+    ) {
+        let address = match self.user_address(user_key) {
+            Some(address) => address,
+            None => return,
+        };
+        let remote_entity = |server: &Self, net_entity: &NetEntity| -> Option<E> {
+            server
+                .user_connections
+                .get(&address)
+                .and_then(|connection| connection.remote_entity_manager.entity(net_entity))
+        };
+
+        match action {
+            RemoteEntityAction::SpawnEntity(net_entity, components) => {
+                let connection = match self.user_connections.get(&address) {
+                    Some(connection) => connection,
+                    None => return,
+                };
+                if connection
+                    .remote_entity_manager
+                    .entity(&net_entity)
+                    .is_some()
+                {
+                    return;
+                }
+                if let Some(max_entities) = self.server_config.max_entities_per_user {
+                    if connection.remote_entity_manager.entity_count() >= max_entities {
+                        return;
+                    }
+                }
+
+                let entity = world.spawn_entity();
+                self.spawn_entity_init(&entity);
+                self.world_record.entity_set_owner(&entity, user_key);
+                self.user_connections
+                    .get_mut(&address)
+                    .unwrap()
+                    .remote_entity_manager
+                    .insert_entity(net_entity, entity);
+
+                let mut component_kinds = Vec::new();
+                for component in components {
+                    component_kinds.push(component.dyn_ref().kind());
+                    component.extract_and_insert(&entity, &mut ComponentInserter::new(self, world));
+                }
+
+                // only emit once the Entity has all of its Components
+                self.incoming_events.push_back(Ok(Event::SpawnEntity(
+                    *user_key,
+                    entity,
+                    component_kinds,
+                )));
+            }
+            RemoteEntityAction::DespawnEntity(net_entity) => {
+                if let Some(entity) = remote_entity(self, &net_entity) {
+                    if world.has_entity(&entity) {
+                        self.despawn_entity(world, &entity);
+                        self.incoming_events
+                            .push_back(Ok(Event::DespawnEntity(*user_key, entity)));
+                    }
+                }
+            }
+            RemoteEntityAction::InsertComponent(net_entity, component) => {
+                if let Some(entity) = remote_entity(self, &net_entity) {
+                    let component_kind = component.dyn_ref().kind();
+                    if world.has_entity(&entity)
+                        && !world.has_component_of_kind(&entity, &component_kind)
+                    {
+                        component
+                            .extract_and_insert(&entity, &mut ComponentInserter::new(self, world));
+                        self.incoming_events.push_back(Ok(Event::InsertComponent(
+                            *user_key,
+                            entity,
+                            component_kind,
+                        )));
+                    }
+                }
+            }
+            RemoteEntityAction::RemoveComponent(net_entity, component_kind) => {
+                if let Some(entity) = remote_entity(self, &net_entity) {
+                    if world.has_component_of_kind(&entity, &component_kind) {
+                        if let Some(component) =
+                            self.remove_component_of_kind(world, &entity, &component_kind)
+                        {
+                            self.incoming_events.push_back(Ok(Event::RemoveComponent(
+                                *user_key, entity, component,
+                            )));
                         }
                     }
                 }
             }
+            RemoteEntityAction::UpdateComponent(net_entity, component_update) => {
+                if let Some(entity) = remote_entity(self, &net_entity) {
+                    let component_kind = component_update.kind;
+                    if !world.has_component_of_kind(&entity, &component_kind) {
+                        return;
+                    }
+                    let connection = self.user_connections.get(&address).unwrap();
+                    let converter =
+                        EntityConverter::new(&self.world_record, &connection.entity_manager)
+                            .with_remote(&connection.remote_entity_manager);
+                    if world
+                        .component_apply_update(
+                            &converter,
+                            &entity,
+                            &component_kind,
+                            component_update,
+                        )
+                        .is_ok()
+                    {
+                        self.incoming_events.push_back(Ok(Event::UpdateComponent(
+                            *user_key,
+                            entity,
+                            component_kind,
+                        )));
+                    }
+                }
+            }
+            RemoteEntityAction::UpdateDelegatedComponent(net_entity, component_update) => {
+                let connection = match self.user_connections.get(&address) {
+                    Some(connection) => connection,
+                    None => return,
+                };
+                let entity = match connection
+                    .entity_manager
+                    .net_entity_to_host_entity(&net_entity)
+                {
+                    Some(entity) => entity,
+                    None => return,
+                };
+                let component_kind = component_update.kind;
+                if self.world_record.entity_authority(&entity) != Some(*user_key)
+                    || !world.has_component_of_kind(&entity, &component_kind)
+                {
+                    return;
+                }
+                let converter =
+                    EntityConverter::new(&self.world_record, &connection.entity_manager)
+                        .with_remote(&connection.remote_entity_manager);
+                let changed_properties = match world
+                    .component_of_kind(&entity, &component_kind)
+                    .map(|component| component.read_update_diff_mask(&converter, &component_update))
+                {
+                    Some(Ok(changed_properties)) => changed_properties,
+                    _ => return,
+                };
+                if world
+                    .component_apply_update(&converter, &entity, &component_kind, component_update)
+                    .is_ok()
+                {
+                    // the Client with authority already has these changes, but
+                    // not those the Server made to other Properties
+                    self.user_connections
+                        .get_mut(&address)
+                        .unwrap()
+                        .entity_manager
+                        .clear_diff_mask_bits(&entity, &component_kind, &changed_properties);
+
+                    self.incoming_events.push_back(Ok(Event::UpdateComponent(
+                        *user_key,
+                        entity,
+                        component_kind,
+                    )));
+                }
+            }
+        }
+    }
+
+    fn process_authority_actions(&mut self, user_key: &UserKey) {
+        let address = match self.user_address(user_key) {
+            Some(address) => address,
+            None => return,
+        };
+        let connection = match self.user_connections.get_mut(&address) {
+            Some(connection) => connection,
+            None => return,
+        };
+
+        // Grants the Client has received
+        for (net_entity, action) in connection.authority_channel.take_delivered_actions() {
+            if action != AuthorityAction::Grant {
+                continue;
+            }
+            if let Some(entity) = connection
+                .entity_manager
+                .net_entity_to_host_entity(&net_entity)
+            {
+                if self.world_record.entity_authority(&entity) == Some(*user_key) {
+                    self.incoming_events
+                        .push_back(Ok(Event::AuthorityGranted(*user_key, entity)));
+                }
+            }
+        }
+
+        // Requests & Releases from the Client
+        for (net_entity, action) in connection.authority_channel.receive_actions() {
+            let entity = match connection
+                .entity_manager
+                .net_entity_to_host_entity(&net_entity)
+            {
+                Some(entity) => entity,
+                None => continue,
+            };
+            match action {
+                AuthorityAction::Request => {
+                    if self.world_record.entity_owner(&entity).is_none()
+                        && self.world_record.entity_authority(&entity) != Some(*user_key)
+                    {
+                        self.incoming_events
+                            .push_back(Ok(Event::AuthorityRequested(*user_key, entity)));
+                    }
+                }
+                AuthorityAction::Release => {
+                    if self.world_record.entity_authority(&entity) == Some(*user_key) {
+                        self.world_record.entity_set_authority(&entity, None);
+                        self.scope_candidate_map
+                            .mark_entity_changed(&self.world_record.entity_root(&entity));
+                        connection.pending_grants.remove(&entity);
+                        self.incoming_events
+                            .push_back(Ok(Event::AuthorityRevoked(*user_key, entity)));
+                    }
+                }
+                AuthorityAction::Grant | AuthorityAction::Revoke => {
+                    // only ever sent by the Server
+                }
+            }
+        }
+    }
+
+    fn send_pending_grants(&mut self) {
+        for connection in self.user_connections.values_mut() {
+            let ready_entities: Vec<E> = connection
+                .pending_grants
+                .iter()
+                .filter(|entity| connection.entity_manager.entity_channel_is_open(entity))
+                .copied()
+                .collect();
+            for entity in ready_entities {
+                connection.pending_grants.remove(&entity);
+                if let Some(net_entity) =
+                    connection.entity_manager.host_entity_to_net_entity(&entity)
+                {
+                    connection
+                        .authority_channel
+                        .send_action(net_entity, AuthorityAction::Grant);
+                }
+            }
         }
     }
 
@@ -1166,6 +2402,23 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> Server<
         component_ref.set_mutator(&prop_mutator);
     }
 
+    fn remove_component_of_kind<W: WorldMutType<P, E>>(
+        &mut self,
+        world: &mut W,
+        entity: &E,
+        component_kind: &P::Kind,
+    ) -> Option<P> {
+        for (_, user_connection) in self.user_connections.iter_mut() {
+            user_connection
+                .entity_manager
+                .remove_component(entity, component_kind);
+        }
+
+        self.component_cleanup(entity, component_kind);
+
+        world.remove_component_of_kind(entity, component_kind)
+    }
+
     fn component_cleanup(&mut self, entity: &E, component_kind: &P::Kind) {
         self.world_record.remove_component(entity, component_kind);
         self.diff_handler
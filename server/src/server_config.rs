@@ -2,6 +2,12 @@ use std::default::Default;
 
 use naia_shared::ConnectionConfig;
 
+use crate::{
+    connection::rate_limit_config::RateLimitConfig,
+    lag_compensation::lag_compensation_config::LagCompensationConfig,
+    spatial_scope::spatial_scope_config::SpatialScopeConfig,
+};
+
 /// Contains Config properties which will be used by the Server
 #[derive(Clone)]
 pub struct ServerConfig {
@@ -10,6 +16,34 @@ pub struct ServerConfig {
     /// Determines whether to require that the Client send some auth message
     /// in order to connect.
     pub require_auth: bool,
+    /// The maximum number of connected Users. Once reached, new Clients are
+    /// rejected because the Server is full. If None, there is no limit
+    pub max_users: Option<usize>,
+    /// The maximum number of Clients which can be awaiting a decision on
+    /// their auth message at once. Further connect requests are ignored
+    /// until there is room. If None, there is no limit
+    pub max_pending_handshakes: Option<usize>,
+    /// The maximum number of Users which can be connected or connecting from
+    /// a single IP address. If None, there is no limit
+    pub max_connections_per_ip: Option<usize>,
+    /// The maximum number of Entities a single Client can spawn on the
+    /// Server. Spawns past it are ignored. If None, there is no limit
+    pub max_entities_per_user: Option<usize>,
+    /// Limits the rate at which handshake packets, including dictionary
+    /// requests from Clients which have yet to connect, are accepted from a
+    /// single IP address. If None, there is no limit
+    pub handshake_rate_limit: Option<RateLimitConfig>,
+    /// Limits the rate at which server info queries are answered for a
+    /// single IP address. If None, there is no limit
+    pub info_query_rate_limit: Option<RateLimitConfig>,
+    /// Used to configure the history kept of Components which have lag
+    /// compensation enabled
+    pub lag_compensation: LagCompensationConfig,
+    /// Enables a spatial index which scopes Entities to the Users within
+    /// view of them, from the positions given to
+    /// `Server::set_entity_position()` & `Server::set_user_view()`. If None,
+    /// scopes are only set by hand
+    pub spatial_scope: Option<SpatialScopeConfig>,
 }
 
 impl Default for ServerConfig {
@@ -17,6 +51,14 @@ impl Default for ServerConfig {
         Self {
             connection: ConnectionConfig::default(),
             require_auth: true,
+            max_users: None,
+            max_pending_handshakes: Some(256),
+            max_connections_per_ip: None,
+            max_entities_per_user: Some(1024),
+            handshake_rate_limit: Some(RateLimitConfig::default()),
+            info_query_rate_limit: Some(RateLimitConfig::default()),
+            lag_compensation: LagCompensationConfig::default(),
+            spatial_scope: None,
         }
     }
 }
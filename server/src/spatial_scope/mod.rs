@@ -0,0 +1,2 @@
+pub mod spatial_grid;
+pub mod spatial_scope_config;
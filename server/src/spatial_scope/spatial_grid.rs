@@ -0,0 +1,339 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use super::spatial_scope_config::SpatialScopeConfig;
+
+type Cell = (i32, i32);
+
+struct View<E: Copy + Eq + Hash> {
+    x: f32,
+    y: f32,
+    radius: f32,
+    visible: HashSet<E>,
+}
+
+/// A uniform grid of the positions of Entities and of the views of Users,
+/// which works out which Entities each User can see. Only the Entities &
+/// Users near a change in position are reevaluated, and only the changes in
+/// what each User can see are reported
+pub struct SpatialGrid<U: Copy + Eq + Hash, E: Copy + Eq + Hash> {
+    cell_size: f32,
+    entity_positions: HashMap<E, (f32, f32)>,
+    entities_in_cell: HashMap<Cell, HashSet<E>>,
+    views: HashMap<U, View<E>>,
+    views_over_cell: HashMap<Cell, HashSet<U>>,
+    viewers_of_entity: HashMap<E, HashSet<U>>,
+    scope_changes: HashMap<(U, E), bool>,
+}
+
+impl<U: Copy + Eq + Hash, E: Copy + Eq + Hash> SpatialGrid<U, E> {
+    pub fn new(config: &SpatialScopeConfig) -> Self {
+        Self {
+            cell_size: config.cell_size,
+            entity_positions: HashMap::new(),
+            entities_in_cell: HashMap::new(),
+            views: HashMap::new(),
+            views_over_cell: HashMap::new(),
+            viewers_of_entity: HashMap::new(),
+            scope_changes: HashMap::new(),
+        }
+    }
+
+    // Entities
+
+    pub fn set_entity_position(&mut self, entity: &E, x: f32, y: f32) {
+        let cell = self.cell_of(x, y);
+
+        match self.entity_positions.insert(*entity, (x, y)) {
+            Some(old_position) => {
+                if old_position == (x, y) {
+                    return;
+                }
+                let old_cell = self.cell_of(old_position.0, old_position.1);
+                if old_cell != cell {
+                    self.remove_entity_from_cell(entity, &old_cell);
+                    self.entities_in_cell
+                        .entry(cell)
+                        .or_default()
+                        .insert(*entity);
+                }
+            }
+            None => {
+                self.entities_in_cell
+                    .entry(cell)
+                    .or_default()
+                    .insert(*entity);
+            }
+        }
+
+        // only the Users which could see the Entity before, or whose view
+        // covers where it is now, need to be reevaluated
+        let mut users: HashSet<U> = HashSet::new();
+        if let Some(viewers) = self.viewers_of_entity.get(entity) {
+            users.extend(viewers.iter().copied());
+        }
+        if let Some(viewers) = self.views_over_cell.get(&cell) {
+            users.extend(viewers.iter().copied());
+        }
+
+        for user in users {
+            self.evaluate(&user, entity);
+        }
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        if let Some((x, y)) = self.entity_positions.remove(entity) {
+            let cell = self.cell_of(x, y);
+            self.remove_entity_from_cell(entity, &cell);
+        }
+
+        if let Some(viewers) = self.viewers_of_entity.remove(entity) {
+            for user in viewers {
+                if let Some(view) = self.views.get_mut(&user) {
+                    view.visible.remove(entity);
+                }
+                self.scope_changes.insert((user, *entity), false);
+            }
+        }
+    }
+
+    // Users
+
+    pub fn set_user_view(&mut self, user: &U, x: f32, y: f32, radius: f32) {
+        let mut view = match self.views.remove(user) {
+            Some(old_view) => {
+                if (old_view.x, old_view.y, old_view.radius) == (x, y, radius) {
+                    self.views.insert(*user, old_view);
+                    return;
+                }
+                let old_cells = self.cells_covered(old_view.x, old_view.y, old_view.radius);
+                let new_cells = self.cells_covered(x, y, radius);
+                if old_cells != new_cells {
+                    self.remove_view_from_cells(user, old_cells);
+                    self.add_view_to_cells(user, new_cells);
+                }
+                old_view
+            }
+            None => {
+                let new_cells = self.cells_covered(x, y, radius);
+                self.add_view_to_cells(user, new_cells);
+                View {
+                    x,
+                    y,
+                    radius,
+                    visible: HashSet::new(),
+                }
+            }
+        };
+        view.x = x;
+        view.y = y;
+        view.radius = radius;
+
+        // only the Entities which the User could see before, or which are in
+        // the cells covered by its view now, need to be reevaluated
+        let mut entities: HashSet<E> = view.visible.clone();
+        let ((min_x, min_y), (max_x, max_y)) = self.cells_covered(x, y, radius);
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                if let Some(cell_entities) = self.entities_in_cell.get(&(cell_x, cell_y)) {
+                    entities.extend(cell_entities.iter().copied());
+                }
+            }
+        }
+
+        self.views.insert(*user, view);
+
+        for entity in entities {
+            self.evaluate(user, &entity);
+        }
+    }
+
+    pub fn remove_user(&mut self, user: &U) {
+        if let Some(view) = self.views.remove(user) {
+            let cells = self.cells_covered(view.x, view.y, view.radius);
+            self.remove_view_from_cells(user, cells);
+
+            for entity in view.visible {
+                if let Some(viewers) = self.viewers_of_entity.get_mut(&entity) {
+                    viewers.remove(user);
+                    if viewers.is_empty() {
+                        self.viewers_of_entity.remove(&entity);
+                    }
+                }
+                self.scope_changes.insert((*user, entity), false);
+            }
+        }
+    }
+
+    // Scope Changes
+
+    /// Returns every change in whether a User can see an Entity since the
+    /// last call, as a User, Entity, and whether the Entity is now visible
+    pub fn take_scope_changes(&mut self) -> Vec<(U, E, bool)> {
+        self.scope_changes
+            .drain()
+            .map(|((user, entity), in_scope)| (user, entity, in_scope))
+            .collect()
+    }
+
+    // Private methods
+
+    fn evaluate(&mut self, user: &U, entity: &E) {
+        let view = self.views.get_mut(user).unwrap();
+        let in_view = match self.entity_positions.get(entity) {
+            Some((x, y)) => {
+                let dx = x - view.x;
+                let dy = y - view.y;
+                dx * dx + dy * dy <= view.radius * view.radius
+            }
+            None => false,
+        };
+
+        if in_view == view.visible.contains(entity) {
+            return;
+        }
+
+        if in_view {
+            view.visible.insert(*entity);
+            self.viewers_of_entity
+                .entry(*entity)
+                .or_default()
+                .insert(*user);
+        } else {
+            view.visible.remove(entity);
+            if let Some(viewers) = self.viewers_of_entity.get_mut(entity) {
+                viewers.remove(user);
+                if viewers.is_empty() {
+                    self.viewers_of_entity.remove(entity);
+                }
+            }
+        }
+
+        self.scope_changes.insert((*user, *entity), in_view);
+    }
+
+    fn cell_of(&self, x: f32, y: f32) -> Cell {
+        (
+            (x / self.cell_size).floor() as i32,
+            (y / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn cells_covered(&self, x: f32, y: f32, radius: f32) -> (Cell, Cell) {
+        (
+            self.cell_of(x - radius, y - radius),
+            self.cell_of(x + radius, y + radius),
+        )
+    }
+
+    fn remove_entity_from_cell(&mut self, entity: &E, cell: &Cell) {
+        if let Some(cell_entities) = self.entities_in_cell.get_mut(cell) {
+            cell_entities.remove(entity);
+            if cell_entities.is_empty() {
+                self.entities_in_cell.remove(cell);
+            }
+        }
+    }
+
+    fn add_view_to_cells(&mut self, user: &U, ((min_x, min_y), (max_x, max_y)): (Cell, Cell)) {
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                self.views_over_cell
+                    .entry((cell_x, cell_y))
+                    .or_default()
+                    .insert(*user);
+            }
+        }
+    }
+
+    fn remove_view_from_cells(&mut self, user: &U, ((min_x, min_y), (max_x, max_y)): (Cell, Cell)) {
+        for cell_x in min_x..=max_x {
+            for cell_y in min_y..=max_y {
+                if let Some(cell_views) = self.views_over_cell.get_mut(&(cell_x, cell_y)) {
+                    cell_views.remove(user);
+                    if cell_views.is_empty() {
+                        self.views_over_cell.remove(&(cell_x, cell_y));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SpatialGrid, SpatialScopeConfig};
+
+    fn sorted_changes(grid: &mut SpatialGrid<u8, u32>) -> Vec<(u8, u32, bool)> {
+        let mut changes = grid.take_scope_changes();
+        changes.sort_by_key(|(user, entity, _)| (*user, *entity));
+        changes
+    }
+
+    #[test]
+    fn users_see_entities_within_radius() {
+        let mut grid = SpatialGrid::<u8, u32>::new(&SpatialScopeConfig { cell_size: 10.0 });
+
+        grid.set_entity_position(&1, 0.0, 0.0);
+        grid.set_entity_position(&2, 25.0, 0.0);
+        grid.set_entity_position(&3, -100.0, -100.0);
+        grid.set_user_view(&0, 5.0, 0.0, 15.0);
+        grid.set_user_view(&1, -95.0, -95.0, 10.0);
+
+        assert_eq!(sorted_changes(&mut grid), vec![(0, 1, true), (1, 3, true)]);
+
+        // nothing moved, so nothing changed
+        grid.set_user_view(&0, 5.0, 0.0, 15.0);
+        grid.set_entity_position(&1, 0.0, 0.0);
+        assert!(grid.take_scope_changes().is_empty());
+    }
+
+    #[test]
+    fn moving_reports_only_changes() {
+        let mut grid = SpatialGrid::<u8, u32>::new(&SpatialScopeConfig { cell_size: 10.0 });
+
+        grid.set_user_view(&0, 0.0, 0.0, 15.0);
+        grid.set_entity_position(&1, 5.0, 5.0);
+        grid.set_entity_position(&2, 50.0, 0.0);
+        assert_eq!(sorted_changes(&mut grid), vec![(0, 1, true)]);
+
+        // an Entity moving within view changes nothing
+        grid.set_entity_position(&1, -5.0, 5.0);
+        assert!(grid.take_scope_changes().is_empty());
+
+        // an Entity moving into and out of view
+        grid.set_entity_position(&2, 10.0, 0.0);
+        grid.set_entity_position(&1, 30.0, 0.0);
+        assert_eq!(sorted_changes(&mut grid), vec![(0, 1, false), (0, 2, true)]);
+
+        // the User moving
+        grid.set_user_view(&0, 30.0, 0.0, 5.0);
+        assert_eq!(sorted_changes(&mut grid), vec![(0, 1, true), (0, 2, false)]);
+    }
+
+    #[test]
+    fn removal_hides_entities() {
+        let mut grid = SpatialGrid::<u8, u32>::new(&SpatialScopeConfig { cell_size: 10.0 });
+
+        grid.set_user_view(&0, 0.0, 0.0, 15.0);
+        grid.set_user_view(&1, 0.0, 0.0, 15.0);
+        grid.set_entity_position(&1, 5.0, 5.0);
+        grid.set_entity_position(&2, -5.0, -5.0);
+        grid.take_scope_changes();
+
+        grid.remove_entity(&1);
+        assert_eq!(
+            sorted_changes(&mut grid),
+            vec![(0, 1, false), (1, 1, false)]
+        );
+
+        grid.remove_user(&1);
+        assert_eq!(sorted_changes(&mut grid), vec![(1, 2, false)]);
+
+        // removed Entities & Users are no longer tracked
+        grid.set_user_view(&0, 100.0, 0.0, 15.0);
+        assert_eq!(sorted_changes(&mut grid), vec![(0, 2, false)]);
+    }
+}
@@ -0,0 +1,17 @@
+use std::default::Default;
+
+/// Contains Config properties which control the spatial index the Server
+/// uses to scope Entities to the Users who are near them
+#[derive(Clone, Debug)]
+pub struct SpatialScopeConfig {
+    /// The width & height of each cell of the uniform grid which Entities &
+    /// Users are sorted into. Ideally close to the typical view radius of a
+    /// User, so that each view covers only a few cells
+    pub cell_size: f32,
+}
+
+impl Default for SpatialScopeConfig {
+    fn default() -> Self {
+        Self { cell_size: 100.0 }
+    }
+}
@@ -27,4 +27,23 @@ impl<'s, P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex>
 
         self
     }
+
+    /// Replicates a Component of the given kind on the Entity to the User
+    /// again, after a call to `exclude_component()`
+    pub fn include_component(&mut self, entity: &E, component_kind: &P::Kind) -> &mut Self {
+        self.server
+            .user_scope_set_component(&self.key, entity, component_kind, true);
+
+        self
+    }
+
+    /// Hides a Component of the given kind on the Entity from the User, even
+    /// while the Entity itself is in their scope. If the User already has the
+    /// Component, it is removed from their copy of the Entity
+    pub fn exclude_component(&mut self, entity: &E, component_kind: &P::Kind) -> &mut Self {
+        self.server
+            .user_scope_set_component(&self.key, entity, component_kind, false);
+
+        self
+    }
 }
@@ -7,10 +7,12 @@
 mod channel_index;
 mod protocolize;
 mod replicate;
+mod replicate_nested;
 
 use channel_index::channels_impl;
 use protocolize::protocolize_impl;
 use replicate::replicate_impl;
+use replicate_nested::replicate_nested_impl;
 
 /// Derives the Protocolize trait for a given enum
 #[proc_macro_derive(Protocolize)]
@@ -18,12 +20,25 @@ pub fn protocolize_derive(input: proc_macro::TokenStream) -> proc_macro::TokenSt
     protocolize_impl(input)
 }
 
-/// Derives the Replicate trait for a given struct
-#[proc_macro_derive(Replicate, attributes(protocol_path))]
+/// Derives the Replicate trait for a given struct, whose fields may be a
+/// `Property`, `PropertyVec`, `PropertyMap`, `EntityProperty` or
+/// `NestedProperty`. Properties can be marked
+/// with `#[replicate(owner_only)]` or `#[replicate(conditional)]` to limit
+/// which Users they are replicated to, in which case their type must
+/// implement Default
+#[proc_macro_derive(Replicate, attributes(protocol_path, replicate))]
 pub fn replicate_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     replicate_impl(input)
 }
 
+/// Derives the ReplicateNested trait for a given struct of Properties, so that
+/// it can be a `NestedProperty` of a Replicate struct, which syncs only the
+/// Properties within it that have changed
+#[proc_macro_derive(ReplicateNested)]
+pub fn replicate_nested_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    replicate_nested_impl(input)
+}
+
 #[proc_macro_attribute]
 pub fn derive_channels(
     first_input: proc_macro::TokenStream,
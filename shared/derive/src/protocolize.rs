@@ -1,4 +1,4 @@
-use proc_macro2::{Punct, Spacing, Span, TokenStream};
+use proc_macro2::{Literal, Punct, Spacing, Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::{parse_macro_input, Data, DeriveInput, Ident};
 
@@ -24,6 +24,7 @@ pub fn protocolize_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStre
     let write_update_method = write_update_method(&protocol_name, &variants);
     let read_method = read_method(&kind_enum_name, &variants);
     let read_create_update_method = read_create_update_method(&kind_enum_name, &variants);
+    let protocol_fingerprint_method = protocol_fingerprint_method(&protocol_name, &input.data);
 
     let gen = quote! {
         use std::{any::{Any, TypeId}, ops::{Deref, DerefMut}, sync::RwLock, collections::HashMap};
@@ -49,6 +50,7 @@ pub fn protocolize_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStre
             #extract_and_insert_method
             #write_method
             #write_update_method
+            #protocol_fingerprint_method
         }
 
         impl Clone for #protocol_name {
@@ -120,6 +122,34 @@ pub fn kind_enum(enum_name: &Ident, variants: &Vec<Ident>) -> TokenStream {
     }
 }
 
+fn protocol_fingerprint_method(protocol_name: &Ident, data: &Data) -> TokenStream {
+    // FNV-1a hash of the Protocol's name, variants and the types they hold
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut hash_str = |value: &str| {
+        for byte in value.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+
+    hash_str(&protocol_name.to_string());
+    if let Data::Enum(ref data) = *data {
+        for variant in data.variants.iter() {
+            hash_str(&variant.ident.to_string());
+            let fields = &variant.fields;
+            hash_str(&quote! { #fields }.to_string());
+        }
+    }
+
+    let hash_literal = Literal::u64_suffixed(hash);
+
+    quote! {
+        fn protocol_fingerprint() -> u64 {
+            #hash_literal
+        }
+    }
+}
+
 fn kind_of_method() -> TokenStream {
     quote! {
         fn kind_of<R: ReplicateSafe<Self>>() -> Self::Kind {
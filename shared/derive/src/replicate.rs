@@ -1,8 +1,8 @@
 use proc_macro2::{Punct, Spacing, Span, TokenStream};
 use quote::{format_ident, quote};
 use syn::{
-    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, Ident, Lit, Meta, Path,
-    PathArguments, Result, Type,
+    parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Fields, GenericArgument, Ident,
+    Lit, Meta, NestedMeta, Path, PathArguments, Result, Type,
 };
 
 pub fn replicate_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -29,14 +29,7 @@ pub fn replicate_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream
         read_create_update_method(&replica_name, &protocol_kind_name, &properties);
 
     // ReplicateSafe Derive Methods
-    let diff_mask_size = {
-        let len = properties.len();
-        if len == 0 {
-            0
-        } else {
-            ((len - 1) / 8) + 1
-        }
-    } as u8;
+    let diff_mask_size = diff_mask_size(&properties);
     let dyn_ref_method = dyn_ref_method(&protocol_name);
     let dyn_mut_method = dyn_mut_method(&protocol_name);
     let to_protocol_method = into_protocol_method(&protocol_name, &replica_name);
@@ -45,8 +38,39 @@ pub fn replicate_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     let mirror_method = mirror_method(&protocol_name, &replica_name, &properties);
     let set_mutator_method = set_mutator_method(&properties);
     let read_apply_update_method = read_apply_update_method(&protocol_kind_name, &properties);
+    let read_update_diff_mask_method = read_update_diff_mask_method(
+        &protocol_kind_name,
+        &enum_name,
+        &properties,
+        &diff_mask_size,
+    );
     let write_method = write_method(&properties);
+    let write_masked_method = write_masked_method(&enum_name, &properties);
     let write_update_method = write_update_method(&enum_name, &properties);
+    let write_update_delta_method = write_update_delta_method(&protocol_name, &properties);
+    let delta_sent_state_method =
+        delta_sent_state_method(&protocol_name, &replica_name, &properties);
+    let read_update_delta_method = read_update_delta_method(
+        &protocol_name,
+        &replica_name,
+        &enum_name,
+        &properties,
+        &diff_mask_size,
+    );
+    let owner_only_properties_method = properties_mask_method(
+        &format_ident!("owner_only_properties"),
+        &enum_name,
+        &properties,
+        &diff_mask_size,
+        PropertyVisibility::OwnerOnly,
+    );
+    let conditional_properties_method = properties_mask_method(
+        &format_ident!("conditional_properties"),
+        &enum_name,
+        &properties,
+        &diff_mask_size,
+        PropertyVisibility::Conditional,
+    );
     let has_entity_properties = has_entity_properties_method(&properties);
     let entities = entities_method(&properties);
 
@@ -81,8 +105,15 @@ pub fn replicate_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream
             #mirror_method
             #set_mutator_method
             #write_method
+            #write_masked_method
             #write_update_method
+            #write_update_delta_method
+            #delta_sent_state_method
+            #read_update_delta_method
             #read_apply_update_method
+            #read_update_diff_mask_method
+            #owner_only_properties_method
+            #conditional_properties_method
             #has_entity_properties
             #entities
         }
@@ -95,42 +126,80 @@ pub fn replicate_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream
     proc_macro::TokenStream::from(gen)
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PropertyVisibility {
+    Public,
+    OwnerOnly,
+    Conditional,
+}
+
 pub struct NormalProperty {
     pub variable_name: Ident,
+    // the type of the field, which may be a Property, PropertyVec or
+    // PropertyMap
+    pub property_type: Type,
+    // the type of the value contained in the field
     pub inner_type: Type,
     pub uppercase_variable_name: Ident,
+    pub visibility: PropertyVisibility,
 }
 
 pub struct EntityProperty {
     pub variable_name: Ident,
     pub uppercase_variable_name: Ident,
+    pub visibility: PropertyVisibility,
+}
+
+pub struct NestedProperty {
+    pub variable_name: Ident,
+    pub inner_type: Type,
+    // the index of the nested struct's first Property in the DiffMask, after
+    // every top-level Property and the Properties of earlier nested structs
+    pub first_index: TokenStream,
 }
 
 #[allow(clippy::large_enum_variant)]
 pub enum Property {
     Normal(NormalProperty),
     Entity(EntityProperty),
+    Nested(NestedProperty),
 }
 
 impl Property {
-    pub fn normal(variable_name: Ident, inner_type: Type) -> Self {
+    pub fn normal(
+        variable_name: Ident,
+        property_type: Type,
+        inner_type: Type,
+        visibility: PropertyVisibility,
+    ) -> Self {
         Self::Normal(NormalProperty {
             variable_name: variable_name.clone(),
+            property_type,
             inner_type,
             uppercase_variable_name: Ident::new(
                 variable_name.to_string().to_uppercase().as_str(),
                 Span::call_site(),
             ),
+            visibility,
         })
     }
 
-    pub fn entity(variable_name: Ident) -> Self {
+    pub fn entity(variable_name: Ident, visibility: PropertyVisibility) -> Self {
         Self::Entity(EntityProperty {
             variable_name: variable_name.clone(),
             uppercase_variable_name: Ident::new(
                 variable_name.to_string().to_uppercase().as_str(),
                 Span::call_site(),
             ),
+            visibility,
+        })
+    }
+
+    pub fn nested(variable_name: Ident, inner_type: Type, first_index: TokenStream) -> Self {
+        Self::Nested(NestedProperty {
+            variable_name,
+            inner_type,
+            first_index,
         })
     }
 
@@ -138,39 +207,92 @@ impl Property {
         match self {
             Self::Normal(property) => &property.variable_name,
             Self::Entity(property) => &property.variable_name,
+            Self::Nested(property) => &property.variable_name,
+        }
+    }
+
+    /// Nested Properties have no variant of the Property enum, as each of
+    /// their inner Properties takes its own bit of the DiffMask
+    pub fn uppercase_variable_name(&self) -> Option<&Ident> {
+        match self {
+            Self::Normal(property) => Some(&property.uppercase_variable_name),
+            Self::Entity(property) => Some(&property.uppercase_variable_name),
+            Self::Nested(_) => None,
         }
     }
 
-    pub fn uppercase_variable_name(&self) -> &Ident {
+    pub fn visibility(&self) -> PropertyVisibility {
         match self {
-            Self::Normal(property) => &property.uppercase_variable_name,
-            Self::Entity(property) => &property.uppercase_variable_name,
+            Self::Normal(property) => property.visibility,
+            Self::Entity(property) => property.visibility,
+            Self::Nested(_) => PropertyVisibility::Public,
         }
     }
 }
 
 fn properties(input: &DeriveInput) -> Vec<Property> {
     let mut fields = Vec::new();
+    let mut nested_fields = Vec::new();
 
     if let Data::Struct(data_struct) = &input.data {
         if let Fields::Named(fields_named) = &data_struct.fields {
             for field in fields_named.named.iter() {
                 if let Some(variable_name) = &field.ident {
+                    let visibility = property_visibility(&field.attrs);
                     if let Type::Path(type_path) = &field.ty {
                         if let Some(property_seg) = type_path.path.segments.first() {
                             let property_type = property_seg.ident.clone();
                             if property_type == "EntityProperty" {
-                                fields.push(Property::entity(variable_name.clone()));
+                                fields.push(Property::entity(variable_name.clone(), visibility));
                                 continue;
+                            } else if property_type == "NestedProperty" {
+                                if visibility != PropertyVisibility::Public {
+                                    panic!("A 'NestedProperty' cannot be marked with '#[replicate(owner_only)]' or '#[replicate(conditional)]'");
+                                }
+                                if let PathArguments::AngleBracketed(angle_args) =
+                                    &property_seg.arguments
+                                {
+                                    if let Some(GenericArgument::Type(inner_type)) =
+                                        angle_args.args.first()
+                                    {
+                                        nested_fields.push((
+                                            fields.len(),
+                                            variable_name.clone(),
+                                            inner_type.clone(),
+                                        ));
+                                        continue;
+                                    }
+                                }
                             } else if let PathArguments::AngleBracketed(angle_args) =
                                 &property_seg.arguments
                             {
-                                if let Some(GenericArgument::Type(inner_type)) =
-                                    angle_args.args.first()
-                                {
+                                let mut type_args = angle_args.args.iter().filter_map(|arg| {
+                                    if let GenericArgument::Type(arg_type) = arg {
+                                        Some(arg_type)
+                                    } else {
+                                        None
+                                    }
+                                });
+                                let inner_type: Option<Type> = if property_type == "PropertyVec" {
+                                    type_args
+                                        .next()
+                                        .map(|item_type| parse_quote! { Vec<#item_type> })
+                                } else if property_type == "PropertyMap" {
+                                    match (type_args.next(), type_args.next()) {
+                                        (Some(key_type), Some(value_type)) => Some(parse_quote! {
+                                            std::collections::HashMap<#key_type, #value_type>
+                                        }),
+                                        _ => None,
+                                    }
+                                } else {
+                                    type_args.next().cloned()
+                                };
+                                if let Some(inner_type) = inner_type {
                                     fields.push(Property::normal(
                                         variable_name.clone(),
-                                        inner_type.clone(),
+                                        field.ty.clone(),
+                                        inner_type,
+                                        visibility,
                                     ));
                                     continue;
                                 }
@@ -182,9 +304,88 @@ fn properties(input: &DeriveInput) -> Vec<Property> {
         }
     }
 
+    // Nested Properties keep their place among the fields, but their inner
+    // Properties take the DiffMask bits after every top-level Property
+    let mut first_index = {
+        let top_level_count = fields.len() as u8;
+        quote! { #top_level_count }
+    };
+    for (offset, (position, variable_name, inner_type)) in nested_fields.into_iter().enumerate() {
+        let next_index = quote! {
+            #first_index + <#inner_type as naia_shared::ReplicateNested>::PROPERTY_COUNT
+        };
+        fields.insert(
+            position + offset,
+            Property::nested(variable_name, inner_type, first_index),
+        );
+        first_index = next_index;
+    }
+
     fields
 }
 
+fn diff_mask_size(properties: &[Property]) -> TokenStream {
+    let mut nested_count = quote! {};
+    let mut top_level_count: usize = 0;
+    for property in properties.iter() {
+        if let Property::Nested(property) = property {
+            let inner_type = &property.inner_type;
+            nested_count = quote! {
+                #nested_count + <#inner_type as naia_shared::ReplicateNested>::PROPERTY_COUNT
+            };
+        } else {
+            top_level_count += 1;
+        }
+    }
+
+    if nested_count.is_empty() {
+        let size = if top_level_count == 0 {
+            0
+        } else {
+            ((top_level_count - 1) / 8) + 1
+        } as u8;
+        return quote! { #size };
+    }
+
+    let top_level_count = top_level_count as u8;
+    quote! {
+        {
+            let property_count: u8 = #top_level_count #nested_count;
+            if property_count == 0 {
+                0
+            } else {
+                ((property_count - 1) / 8) + 1
+            }
+        }
+    }
+}
+
+fn property_visibility(attrs: &[Attribute]) -> PropertyVisibility {
+    let mut visibility = PropertyVisibility::Public;
+
+    for attr in attrs {
+        if !attr.path.is_ident("replicate") {
+            continue;
+        }
+        if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
+            for nested in meta_list.nested.iter() {
+                if let NestedMeta::Meta(Meta::Path(path)) = nested {
+                    if path.is_ident("owner_only") {
+                        visibility = PropertyVisibility::OwnerOnly;
+                        continue;
+                    } else if path.is_ident("conditional") {
+                        visibility = PropertyVisibility::Conditional;
+                        continue;
+                    }
+                }
+                panic!("Unknown replicate attribute. Expected '#[replicate(owner_only)]' or '#[replicate(conditional)]'");
+            }
+        }
+    }
+
+    visibility
+}
+
 fn protocol_path(input: &DeriveInput) -> (Path, Ident) {
     let mut path_result: Option<Result<Path>> = None;
 
@@ -219,9 +420,14 @@ fn protocol_path(input: &DeriveInput) -> (Path, Ident) {
 }
 
 fn property_enum(enum_name: &Ident, properties: &[Property]) -> TokenStream {
-    if properties.is_empty() {
+    let uppercase_variant_names: Vec<&Ident> = properties
+        .iter()
+        .filter_map(|property| property.uppercase_variable_name())
+        .collect();
+
+    if uppercase_variant_names.is_empty() {
         return quote! {
-            enum #enum_name {}
+            pub enum #enum_name {}
         };
     }
 
@@ -229,9 +435,7 @@ fn property_enum(enum_name: &Ident, properties: &[Property]) -> TokenStream {
 
     let mut variant_list = quote! {};
 
-    for (index, property) in properties.iter().enumerate() {
-        let uppercase_variant_name = property.uppercase_variable_name();
-
+    for (index, uppercase_variant_name) in uppercase_variant_names.into_iter().enumerate() {
         let new_output_right = quote! {
             #uppercase_variant_name = #index as u8,
         };
@@ -244,7 +448,7 @@ fn property_enum(enum_name: &Ident, properties: &[Property]) -> TokenStream {
 
     quote! {
         #hashtag[repr(u8)]
-        enum #enum_name {
+        pub enum #enum_name {
             #variant_list
         }
     }
@@ -310,6 +514,17 @@ fn clone_method(replica_name: &Ident, properties: &[Property]) -> TokenStream {
                 };
                 entity_property_output = new_output_result;
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                let new_output_right = quote! {
+                    (*self.#field_name).clone(),
+                };
+                let new_output_result = quote! {
+                    #output
+                    #new_output_right
+                };
+                output = new_output_result;
+            }
         };
     }
 
@@ -355,8 +570,18 @@ fn set_mutator_method(properties: &[Property]) -> TokenStream {
 
     for property in properties.iter() {
         let field_name = property.variable_name();
-        let new_output_right = quote! {
-            self.#field_name.set_mutator(mutator);
+        let new_output_right = match property {
+            Property::Nested(property) => {
+                let first_index = &property.first_index;
+                quote! {
+                    self.#field_name.set_mutator(mutator, #first_index);
+                }
+            }
+            _ => {
+                quote! {
+                    self.#field_name.set_mutator(mutator);
+                }
+            }
         };
         let new_output_result = quote! {
             #output
@@ -396,6 +621,19 @@ pub fn new_complete_method(
             Property::Entity(_) => {
                 continue;
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                let field_type = &property.inner_type;
+
+                let new_output_right = quote! {
+                    #field_name: #field_type,
+                };
+
+                let new_output_result = quote! {
+                    #args #new_output_right
+                };
+                args = new_output_result;
+            }
         };
     }
 
@@ -403,11 +641,11 @@ pub fn new_complete_method(
     for property in properties.iter() {
         let new_output_right = match property {
             Property::Normal(property) => {
+                let property_type = &property.property_type;
                 let field_name = &property.variable_name;
-                let field_type = &property.inner_type;
                 let uppercase_variant_name = &property.uppercase_variable_name;
                 quote! {
-                    #field_name: Property::<#field_type>::new(#field_name, #enum_name::#uppercase_variant_name as u8)
+                    #field_name: <#property_type>::new(#field_name, #enum_name::#uppercase_variant_name as u8)
                 }
             }
             Property::Entity(property) => {
@@ -417,6 +655,12 @@ pub fn new_complete_method(
                     #field_name: EntityProperty::new(#enum_name::#uppercase_variant_name as u8)
                 }
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    #field_name: NestedProperty::new(#field_name)
+                }
+            }
         };
 
         let new_output_result = quote! {
@@ -458,11 +702,11 @@ pub fn read_method(
     for property in properties.iter() {
         let new_output_right = match property {
             Property::Normal(property) => {
+                let property_type = &property.property_type;
                 let field_name = &property.variable_name;
-                let field_type = &property.inner_type;
                 let uppercase_variant_name = &property.uppercase_variable_name;
                 quote! {
-                    let #field_name = Property::<#field_type>::new_read(reader, #enum_name::#uppercase_variant_name as u8)?;
+                    let #field_name = <#property_type>::new_read(reader, #enum_name::#uppercase_variant_name as u8)?;
                 }
             }
             Property::Entity(property) => {
@@ -472,6 +716,13 @@ pub fn read_method(
                     let #field_name = EntityProperty::new_read(reader, #enum_name::#uppercase_variant_name as u8, converter)?;
                 }
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                let field_type = &property.inner_type;
+                quote! {
+                    let #field_name = NestedProperty::<#field_type>::new_read(reader)?;
+                }
+            }
         };
 
         let new_output_result = quote! {
@@ -501,13 +752,13 @@ pub fn read_create_update_method(
     for property in properties.iter() {
         let new_output_right = match property {
             Property::Normal(property) => {
-                let field_type = &property.inner_type;
+                let property_type = &property.property_type;
                 quote! {
                     {
                         let should_read = bool::de(reader)?;
                         should_read.ser(&mut update_writer);
                         if should_read {
-                            Property::<#field_type>::read_write(reader, &mut update_writer)?;
+                            <#property_type>::read_write(reader, &mut update_writer)?;
                         }
                     }
                 }
@@ -523,6 +774,12 @@ pub fn read_create_update_method(
                     }
                 }
             }
+            Property::Nested(property) => {
+                let field_type = &property.inner_type;
+                quote! {
+                    NestedProperty::<#field_type>::read_write_update(reader, &mut update_writer)?;
+                }
+            }
         };
 
         let new_output_result = quote! {
@@ -535,7 +792,7 @@ pub fn read_create_update_method(
     quote! {
         pub fn read_create_update(reader: &mut BitReader) -> Result<ComponentUpdate::<#kind_name>, SerdeErr> {
 
-            let mut update_writer = BitWriter::new();
+            let mut update_writer = BitWriter::with_max_size(naia_shared::MAX_MTU_SIZE_BYTES as usize);
 
             #prop_read_writes
 
@@ -553,10 +810,11 @@ fn read_apply_update_method(kind_name: &Ident, properties: &[Property]) -> Token
     for property in properties.iter() {
         let new_output_right = match property {
             Property::Normal(property) => {
+                let property_type = &property.property_type;
                 let field_name = &property.variable_name;
                 quote! {
                     if bool::de(reader)? {
-                        Property::read(&mut self.#field_name, reader)?;
+                        <#property_type>::read(&mut self.#field_name, reader)?;
                     }
                 }
             }
@@ -568,6 +826,12 @@ fn read_apply_update_method(kind_name: &Ident, properties: &[Property]) -> Token
                     }
                 }
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    NestedProperty::read_apply_update(&mut self.#field_name, reader)?;
+                }
+            }
         };
 
         let new_output_result = quote! {
@@ -586,15 +850,71 @@ fn read_apply_update_method(kind_name: &Ident, properties: &[Property]) -> Token
     }
 }
 
+fn read_update_diff_mask_method(
+    kind_name: &Ident,
+    enum_name: &Ident,
+    properties: &[Property],
+    diff_mask_size: &TokenStream,
+) -> TokenStream {
+    let mut output = quote! {};
+
+    for property in properties.iter() {
+        let new_output_right = match property {
+            Property::Normal(property) => {
+                let property_type = &property.property_type;
+                let uppercase_variant_name = &property.uppercase_variable_name;
+                quote! {
+                    if bool::de(reader)? {
+                        diff_mask.set_bit(#enum_name::#uppercase_variant_name as u8, true);
+                        <#property_type>::new_read(reader, 0)?;
+                    }
+                }
+            }
+            Property::Entity(property) => {
+                let uppercase_variant_name = &property.uppercase_variable_name;
+                quote! {
+                    if bool::de(reader)? {
+                        diff_mask.set_bit(#enum_name::#uppercase_variant_name as u8, true);
+                        EntityProperty::new_read(reader, 0, converter)?;
+                    }
+                }
+            }
+            Property::Nested(property) => {
+                let field_type = &property.inner_type;
+                let first_index = &property.first_index;
+                quote! {
+                    NestedProperty::<#field_type>::read_update_diff_mask(reader, &mut diff_mask, #first_index)?;
+                }
+            }
+        };
+
+        let new_output_result = quote! {
+            #output
+            #new_output_right
+        };
+        output = new_output_result;
+    }
+
+    quote! {
+        fn read_update_diff_mask(&self, converter: &dyn NetEntityHandleConverter, update: &ComponentUpdate<#kind_name>) -> Result<DiffMask, SerdeErr> {
+            let reader = &mut update.reader();
+            let mut diff_mask = DiffMask::new(#diff_mask_size);
+            #output
+            Ok(diff_mask)
+        }
+    }
+}
+
 fn write_method(properties: &[Property]) -> TokenStream {
     let mut property_writes = quote! {};
 
     for property in properties.iter() {
         let new_output_right = match property {
             Property::Normal(property) => {
+                let property_type = &property.property_type;
                 let field_name = &property.variable_name;
                 quote! {
-                    Property::write(&self.#field_name, bit_writer);
+                    <#property_type>::write(&self.#field_name, bit_writer);
                 }
             }
             Property::Entity(property) => {
@@ -603,6 +923,12 @@ fn write_method(properties: &[Property]) -> TokenStream {
                     EntityProperty::write(&self.#field_name, bit_writer, converter);
                 }
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    NestedProperty::write(&self.#field_name, bit_writer);
+                }
+            }
         };
 
         let new_output_result = quote! {
@@ -620,18 +946,117 @@ fn write_method(properties: &[Property]) -> TokenStream {
     }
 }
 
+fn write_masked_method(enum_name: &Ident, properties: &[Property]) -> TokenStream {
+    let mut property_writes = quote! {};
+
+    for property in properties.iter() {
+        let new_output_right = match property {
+            // only marked Properties can be hidden, so only their types need a
+            // default value
+            Property::Normal(property) if property.visibility == PropertyVisibility::Public => {
+                let property_type = &property.property_type;
+                let field_name = &property.variable_name;
+                quote! {
+                    <#property_type>::write(&self.#field_name, bit_writer);
+                }
+            }
+            Property::Normal(property) => {
+                let property_type = &property.property_type;
+                let field_name = &property.variable_name;
+                let inner_type = &property.inner_type;
+                let uppercase_variant_name = &property.uppercase_variable_name;
+                quote! {
+                    if let Some(true) = hidden_properties.bit(#enum_name::#uppercase_variant_name as u8) {
+                        <#property_type>::new(<#inner_type as Default>::default(), 0).write(bit_writer);
+                    } else {
+                        <#property_type>::write(&self.#field_name, bit_writer);
+                    }
+                }
+            }
+            Property::Entity(property) => {
+                let field_name = &property.variable_name;
+                let uppercase_variant_name = &property.uppercase_variable_name;
+                quote! {
+                    if let Some(true) = hidden_properties.bit(#enum_name::#uppercase_variant_name as u8) {
+                        EntityProperty::new(#enum_name::#uppercase_variant_name as u8).write(bit_writer, converter);
+                    } else {
+                        EntityProperty::write(&self.#field_name, bit_writer, converter);
+                    }
+                }
+            }
+            // Nested Properties cannot be hidden
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    NestedProperty::write(&self.#field_name, bit_writer);
+                }
+            }
+        };
+
+        let new_output_result = quote! {
+            #property_writes
+            #new_output_right
+        };
+        property_writes = new_output_result;
+    }
+
+    quote! {
+        fn write_masked(&self, hidden_properties: &DiffMask, bit_writer: &mut dyn BitWrite, converter: &dyn NetEntityHandleConverter) {
+            self.kind().ser(bit_writer);
+            #property_writes
+        }
+    }
+}
+
+fn properties_mask_method(
+    method_name: &Ident,
+    enum_name: &Ident,
+    properties: &[Property],
+    diff_mask_size: &TokenStream,
+    visibility: PropertyVisibility,
+) -> TokenStream {
+    let mut output = quote! {};
+
+    for property in properties.iter() {
+        if property.visibility() != visibility {
+            continue;
+        }
+        let uppercase_variant_name = match property.uppercase_variable_name() {
+            Some(uppercase_variant_name) => uppercase_variant_name,
+            None => continue,
+        };
+        let new_output_right = quote! {
+            mask.set_bit(#enum_name::#uppercase_variant_name as u8, true);
+        };
+        let new_output_result = quote! {
+            #output
+            #new_output_right
+        };
+        output = new_output_result;
+    }
+
+    quote! {
+        fn #method_name(&self) -> DiffMask {
+            let mut mask = DiffMask::new(#diff_mask_size);
+            #output
+            mask
+        }
+    }
+}
+
 fn write_update_method(enum_name: &Ident, properties: &[Property]) -> TokenStream {
     let mut output = quote! {};
 
     for property in properties.iter() {
         let new_output_right = match property {
             Property::Normal(property) => {
+                let property_type = &property.property_type;
                 let field_name = &property.variable_name;
                 let uppercase_variant_name = &property.uppercase_variable_name;
                 quote! {
                     if let Some(true) = diff_mask.bit(#enum_name::#uppercase_variant_name as u8) {
                         true.ser(writer);
-                        Property::write(&self.#field_name, writer);
+                        <#property_type>::write(&self.#field_name, writer);
                     } else {
                         false.ser(writer);
                     }
@@ -649,6 +1074,13 @@ fn write_update_method(enum_name: &Ident, properties: &[Property]) -> TokenStrea
                     }
                 }
             }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                let first_index = &property.first_index;
+                quote! {
+                    NestedProperty::write_update(&self.#field_name, diff_mask, #first_index, writer);
+                }
+            }
         };
 
         let new_output_result = quote! {
@@ -665,6 +1097,191 @@ fn write_update_method(enum_name: &Ident, properties: &[Property]) -> TokenStrea
     }
 }
 
+fn write_update_delta_method(protocol_name: &Ident, properties: &[Property]) -> TokenStream {
+    let mut output = quote! {};
+
+    for property in properties.iter() {
+        let new_output_right = match property {
+            Property::Normal(property) => {
+                let property_type = &property.property_type;
+                let field_name = &property.variable_name;
+                quote! {
+                    if <#property_type>::equals(&self.#field_name, &baseline.#field_name) {
+                        false.ser(writer);
+                    } else {
+                        true.ser(writer);
+                        <#property_type>::write_delta(&self.#field_name, &baseline.#field_name, writer);
+                    }
+                }
+            }
+            Property::Entity(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    if EntityProperty::equals(&self.#field_name, &baseline.#field_name) {
+                        false.ser(writer);
+                    } else {
+                        true.ser(writer);
+                        EntityProperty::write(&self.#field_name, writer, converter);
+                    }
+                }
+            }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    NestedProperty::write_update_delta(&self.#field_name, &baseline.#field_name, writer);
+                }
+            }
+        };
+
+        let new_output_result = quote! {
+            #output
+            #new_output_right
+        };
+        output = new_output_result;
+    }
+
+    quote! {
+        fn write_update_delta(&self, baseline: &#protocol_name, writer: &mut dyn BitWrite, converter: &dyn NetEntityHandleConverter) {
+            let baseline = baseline
+                .cast_ref::<Self>()
+                .expect("baseline should be the same kind of Component");
+            #output
+        }
+    }
+}
+
+fn delta_sent_state_method(
+    protocol_name: &Ident,
+    replica_name: &Ident,
+    properties: &[Property],
+) -> TokenStream {
+    let mut output = quote! {};
+
+    for property in properties.iter() {
+        let new_output_right = match property {
+            Property::Normal(property) => {
+                let property_type = &property.property_type;
+                let field_name = &property.variable_name;
+                quote! {
+                    if <#property_type>::equals(&self.#field_name, &baseline.#field_name) {
+                        state.#field_name = baseline.#field_name.clone();
+                    }
+                }
+            }
+            Property::Entity(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    if EntityProperty::equals(&self.#field_name, &baseline.#field_name) {
+                        state.#field_name = baseline.#field_name.clone();
+                    }
+                }
+            }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                quote! {
+                    state.#field_name = NestedProperty::delta_sent_state(&self.#field_name, &baseline.#field_name);
+                }
+            }
+        };
+
+        let new_output_result = quote! {
+            #output
+            #new_output_right
+        };
+        output = new_output_result;
+    }
+
+    quote! {
+        fn delta_sent_state(&self, baseline: &#protocol_name) -> #protocol_name {
+            let baseline = baseline
+                .cast_ref::<Self>()
+                .expect("baseline should be the same kind of Component");
+            let mut state = self.clone();
+            #output
+            return #protocol_name::#replica_name(state);
+        }
+    }
+}
+
+fn read_update_delta_method(
+    protocol_name: &Ident,
+    replica_name: &Ident,
+    enum_name: &Ident,
+    properties: &[Property],
+    diff_mask_size: &TokenStream,
+) -> TokenStream {
+    let mut prop_names = quote! {};
+    for property in properties.iter() {
+        let field_name = property.variable_name();
+        let new_output_right = quote! {
+            #field_name
+        };
+        let new_output_result = quote! {
+            #prop_names
+            #new_output_right,
+        };
+        prop_names = new_output_result;
+    }
+
+    let mut prop_reads = quote! {};
+    for property in properties.iter() {
+        let new_output_right = match property {
+            Property::Normal(property) => {
+                let property_type = &property.property_type;
+                let field_name = &property.variable_name;
+                let uppercase_variant_name = &property.uppercase_variable_name;
+                quote! {
+                    let #field_name = if bool::de(reader)? {
+                        diff_mask.set_bit(#enum_name::#uppercase_variant_name as u8, true);
+                        <#property_type>::new_read_delta(&self.#field_name, reader, #enum_name::#uppercase_variant_name as u8)?
+                    } else {
+                        <#property_type>::new((*self.#field_name).clone(), #enum_name::#uppercase_variant_name as u8)
+                    };
+                }
+            }
+            Property::Entity(property) => {
+                let field_name = &property.variable_name;
+                let uppercase_variant_name = &property.uppercase_variable_name;
+                quote! {
+                    let #field_name = if bool::de(reader)? {
+                        diff_mask.set_bit(#enum_name::#uppercase_variant_name as u8, true);
+                        EntityProperty::new_read(reader, #enum_name::#uppercase_variant_name as u8, converter)?
+                    } else {
+                        let mut #field_name = EntityProperty::new(#enum_name::#uppercase_variant_name as u8);
+                        #field_name.mirror(&self.#field_name);
+                        #field_name
+                    };
+                }
+            }
+            Property::Nested(property) => {
+                let field_name = &property.variable_name;
+                let field_type = &property.inner_type;
+                let first_index = &property.first_index;
+                quote! {
+                    let #field_name = NestedProperty::<#field_type>::new_read_update_delta(&self.#field_name, reader, &mut diff_mask, #first_index)?;
+                }
+            }
+        };
+
+        let new_output_result = quote! {
+            #prop_reads
+            #new_output_right
+        };
+        prop_reads = new_output_result;
+    }
+
+    quote! {
+        fn read_update_delta(&self, reader: &mut BitReader, converter: &dyn NetEntityHandleConverter) -> Result<(#protocol_name, DiffMask), SerdeErr> {
+            let mut diff_mask = DiffMask::new(#diff_mask_size);
+            #prop_reads
+
+            return Ok((#protocol_name::#replica_name(#replica_name {
+                #prop_names
+            }), diff_mask));
+        }
+    }
+}
+
 fn has_entity_properties_method(properties: &[Property]) -> TokenStream {
     for property in properties.iter() {
         if let Property::Entity(_) = property {
@@ -0,0 +1,397 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, Ident, PathArguments, Type,
+};
+
+pub fn replicate_nested_impl(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    // Helper Properties
+    let properties = properties(&input);
+    let property_count = properties.len() as u8;
+
+    // Names
+    let struct_name = input.ident;
+
+    // Methods
+    let new_complete_method = new_complete_method(&properties);
+    let clone_method = clone_method(&properties);
+    let set_mutator_method = set_mutator_method(&properties);
+    let mirror_method = mirror_method(&properties);
+    let equals_method = equals_method(&properties);
+    let write_method = write_method(&properties);
+    let new_read_method = new_read_method(&properties);
+    let write_update_method = write_update_method(&properties);
+    let read_apply_update_method = read_apply_update_method(&properties);
+    let read_write_update_method = read_write_update_method(&properties);
+    let read_update_diff_mask_method = read_update_diff_mask_method(&properties);
+    let write_update_delta_method = write_update_delta_method(&properties);
+    let delta_sent_state_method = delta_sent_state_method(&properties);
+    let new_read_update_delta_method = new_read_update_delta_method(&properties);
+
+    // Paths are fully qualified, as nested structs are often declared in the
+    // same module as the Component which contains them
+    let gen = quote! {
+        impl #struct_name {
+            #new_complete_method
+        }
+        impl naia_shared::ReplicateNested for #struct_name {
+            const PROPERTY_COUNT: u8 = #property_count;
+            #set_mutator_method
+            #mirror_method
+            #equals_method
+            #write_method
+            #new_read_method
+            #write_update_method
+            #read_apply_update_method
+            #read_write_update_method
+            #read_update_diff_mask_method
+            #write_update_delta_method
+            #delta_sent_state_method
+            #new_read_update_delta_method
+        }
+        impl Clone for #struct_name {
+            #clone_method
+        }
+    };
+
+    proc_macro::TokenStream::from(gen)
+}
+
+pub struct NestedLeaf {
+    pub variable_name: Ident,
+    pub inner_type: Type,
+    pub index: u8,
+}
+
+fn properties(input: &DeriveInput) -> Vec<NestedLeaf> {
+    let mut fields = Vec::new();
+
+    if let Data::Struct(data_struct) = &input.data {
+        if let Fields::Named(fields_named) = &data_struct.fields {
+            for field in fields_named.named.iter() {
+                if let Some(variable_name) = &field.ident {
+                    if let Type::Path(type_path) = &field.ty {
+                        if let Some(property_seg) = type_path.path.segments.last() {
+                            if property_seg.ident == "Property" {
+                                if let PathArguments::AngleBracketed(angle_args) =
+                                    &property_seg.arguments
+                                {
+                                    if let Some(GenericArgument::Type(inner_type)) =
+                                        angle_args.args.first()
+                                    {
+                                        fields.push(NestedLeaf {
+                                            variable_name: variable_name.clone(),
+                                            inner_type: inner_type.clone(),
+                                            index: fields.len() as u8,
+                                        });
+                                        continue;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    panic!("Every field of a 'ReplicateNested' struct must be a 'Property<T>'");
+                }
+            }
+        }
+    }
+
+    fields
+}
+
+fn new_complete_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut args = quote! {};
+    let mut fields = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        let field_type = &property.inner_type;
+        args = quote! {
+            #args
+            #field_name: #field_type,
+        };
+        fields = quote! {
+            #fields
+            #field_name: naia_shared::Property::<#field_type>::new(#field_name, 0),
+        };
+    }
+
+    quote! {
+        pub fn new_complete(#args) -> Self {
+            Self {
+                #fields
+            }
+        }
+    }
+}
+
+fn clone_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output
+            (*self.#field_name).clone(),
+        };
+    }
+
+    quote! {
+        fn clone(&self) -> Self {
+            Self::new_complete(#output)
+        }
+    }
+}
+
+fn set_mutator_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        let index = property.index;
+        output = quote! {
+            #output
+            self.#field_name.set_mutator_index(first_index + #index);
+            self.#field_name.set_mutator(mutator);
+        };
+    }
+
+    quote! {
+        fn set_mutator(&mut self, mutator: &naia_shared::PropertyMutator, first_index: u8) {
+            #output
+        }
+    }
+}
+
+fn mirror_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output
+            self.#field_name.mirror(&other.#field_name);
+        };
+    }
+
+    quote! {
+        fn mirror(&mut self, other: &Self) {
+            #output
+        }
+    }
+}
+
+fn equals_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! { true };
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output && self.#field_name.equals(&other.#field_name)
+        };
+    }
+
+    quote! {
+        fn equals(&self, other: &Self) -> bool {
+            #output
+        }
+    }
+}
+
+fn write_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output
+            self.#field_name.write(writer);
+        };
+    }
+
+    quote! {
+        fn write(&self, writer: &mut dyn naia_shared::serde::BitWrite) {
+            #output
+        }
+    }
+}
+
+fn new_read_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        let field_type = &property.inner_type;
+        output = quote! {
+            #output
+            #field_name: naia_shared::Property::<#field_type>::new_read(reader, 0)?,
+        };
+    }
+
+    quote! {
+        fn new_read(reader: &mut naia_shared::serde::BitReader) -> Result<Self, naia_shared::serde::SerdeErr> {
+            Ok(Self {
+                #output
+            })
+        }
+    }
+}
+
+fn write_update_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        let index = property.index;
+        output = quote! {
+            #output
+            if let Some(true) = diff_mask.bit(first_index + #index) {
+                naia_shared::serde::Serde::ser(&true, writer);
+                self.#field_name.write(writer);
+            } else {
+                naia_shared::serde::Serde::ser(&false, writer);
+            }
+        };
+    }
+
+    quote! {
+        fn write_update(&self, diff_mask: &naia_shared::DiffMask, first_index: u8, writer: &mut dyn naia_shared::serde::BitWrite) {
+            #output
+        }
+    }
+}
+
+fn read_apply_update_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output
+            if <bool as naia_shared::serde::Serde>::de(reader)? {
+                self.#field_name.read(reader)?;
+            }
+        };
+    }
+
+    quote! {
+        fn read_apply_update(&mut self, reader: &mut naia_shared::serde::BitReader) -> Result<(), naia_shared::serde::SerdeErr> {
+            #output
+            Ok(())
+        }
+    }
+}
+
+fn read_write_update_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_type = &property.inner_type;
+        output = quote! {
+            #output
+            {
+                let should_read = <bool as naia_shared::serde::Serde>::de(reader)?;
+                naia_shared::serde::Serde::ser(&should_read, writer);
+                if should_read {
+                    naia_shared::Property::<#field_type>::read_write(reader, writer)?;
+                }
+            }
+        };
+    }
+
+    quote! {
+        fn read_write_update(reader: &mut naia_shared::serde::BitReader, writer: &mut naia_shared::serde::BitWriter) -> Result<(), naia_shared::serde::SerdeErr> {
+            #output
+            Ok(())
+        }
+    }
+}
+
+fn read_update_diff_mask_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_type = &property.inner_type;
+        let index = property.index;
+        output = quote! {
+            #output
+            if <bool as naia_shared::serde::Serde>::de(reader)? {
+                diff_mask.set_bit(first_index + #index, true);
+                naia_shared::Property::<#field_type>::new_read(reader, 0)?;
+            }
+        };
+    }
+
+    quote! {
+        fn read_update_diff_mask(reader: &mut naia_shared::serde::BitReader, diff_mask: &mut naia_shared::DiffMask, first_index: u8) -> Result<(), naia_shared::serde::SerdeErr> {
+            #output
+            Ok(())
+        }
+    }
+}
+
+fn write_update_delta_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output
+            if self.#field_name.equals(&baseline.#field_name) {
+                naia_shared::serde::Serde::ser(&false, writer);
+            } else {
+                naia_shared::serde::Serde::ser(&true, writer);
+                self.#field_name.write_delta(&baseline.#field_name, writer);
+            }
+        };
+    }
+
+    quote! {
+        fn write_update_delta(&self, baseline: &Self, writer: &mut dyn naia_shared::serde::BitWrite) {
+            #output
+        }
+    }
+}
+
+fn delta_sent_state_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut output = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        output = quote! {
+            #output
+            if self.#field_name.equals(&baseline.#field_name) {
+                (*baseline.#field_name).clone()
+            } else {
+                (*self.#field_name).clone()
+            },
+        };
+    }
+
+    quote! {
+        fn delta_sent_state(&self, baseline: &Self) -> Self {
+            Self::new_complete(#output)
+        }
+    }
+}
+
+fn new_read_update_delta_method(properties: &[NestedLeaf]) -> TokenStream {
+    let mut prop_reads = quote! {};
+    let mut prop_names = quote! {};
+    for property in properties.iter() {
+        let field_name = &property.variable_name;
+        let field_type = &property.inner_type;
+        let index = property.index;
+        prop_reads = quote! {
+            #prop_reads
+            let #field_name = if <bool as naia_shared::serde::Serde>::de(reader)? {
+                diff_mask.set_bit(first_index + #index, true);
+                naia_shared::Property::<#field_type>::new_read_delta(&baseline.#field_name, reader, 0)?
+            } else {
+                naia_shared::Property::<#field_type>::new((*baseline.#field_name).clone(), 0)
+            };
+        };
+        prop_names = quote! {
+            #prop_names
+            #field_name,
+        };
+    }
+
+    quote! {
+        fn new_read_update_delta(baseline: &Self, reader: &mut naia_shared::serde::BitReader, diff_mask: &mut naia_shared::DiffMask, first_index: u8) -> Result<Self, naia_shared::serde::SerdeErr> {
+            #prop_reads
+            Ok(Self {
+                #prop_names
+            })
+        }
+    }
+}
@@ -121,6 +121,16 @@ pub fn next_ident(source: &mut Peekable<impl Iterator<Item = TokenTree>>) -> Opt
     }
 }
 
+pub fn next_literal(source: &mut Peekable<impl Iterator<Item = TokenTree>>) -> Option<String> {
+    if let Some(TokenTree::Literal(literal)) = source.peek() {
+        let literal = format!("{}", literal);
+        source.next();
+        Some(literal)
+    } else {
+        None
+    }
+}
+
 pub fn next_group(source: &mut Peekable<impl Iterator<Item = TokenTree>>) -> Option<Group> {
     if let Some(TokenTree::Group(_)) = source.peek() {
         let group = match source.next().expect("expected to read another token") {
@@ -168,6 +178,19 @@ fn next_type<T: Iterator<Item = TokenTree>>(source: &mut Peekable<T>) -> Option<
         return Some(tuple_type);
     }
 
+    // read a const generic argument, like the -1 in Foo<-1>
+    let negative = next_exact_punct(source, "-").is_some();
+    if let Some(literal) = next_literal(source) {
+        return Some(Type {
+            path: if negative {
+                format!("-{}", literal)
+            } else {
+                literal
+            },
+            is_option: false,
+        });
+    }
+
     // read a path like a::b::c::d
     let mut ty = next_ident(source)?;
     while next_exact_punct(source, ":").is_some() {
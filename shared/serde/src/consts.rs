@@ -1,2 +1,3 @@
-// This is the MTU of UDP packets
+// This is the default MTU of UDP packets, BitWriters with a different
+// capacity can be created with `BitWriter::with_max_size()`
 pub const MAX_BUFFER_SIZE: usize = 508;
@@ -1,5 +1,6 @@
 use crate::{
     error::SerdeErr,
+    integer::{SignedVariableInteger, UnsignedInteger},
     reader_writer::{BitReader, BitWrite},
     serde::Serde,
 };
@@ -158,9 +159,81 @@ mod char_tests {
 
 // Integers & Floating-point Numbers //
 
-macro_rules! impl_serde_for {
+// Integers are delta-encoded as the variable-length difference from their
+// baseline, so that slowly changing values take only a few bits
+macro_rules! impl_integer_delta {
     ($impl_type:ident) => {
+        fn ser_delta(&self, baseline: &Self, writer: &mut dyn BitWrite) {
+            SignedVariableInteger::<4>::new(*self as i128 - *baseline as i128).ser(writer);
+        }
+
+        fn de_delta(baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+            let delta = SignedVariableInteger::<4>::de(reader)?.get();
+            $impl_type::try_from(*baseline as i128 + delta).map_err(|_| SerdeErr {})
+        }
+    };
+}
+
+// Floats are delta-encoded losslessly on their bit patterns. Only the bits
+// which differ from the baseline are written, skipping the leading & trailing
+// bits which are the same. Small changes tend to leave the sign, exponent &
+// high bits of the mantissa untouched, and unchanged values take only the
+// leading count. Quantized types are cheaper still, where precision allows
+macro_rules! impl_float_delta {
+    ($impl_type:ident, $leading_bits:literal, $width_bits:literal) => {
+        fn ser_delta(&self, baseline: &Self, writer: &mut dyn BitWrite) {
+            const BITS: u32 = (std::mem::size_of::<$impl_type>() * 8) as u32;
+
+            let changed = self.to_bits() ^ baseline.to_bits();
+            let leading = changed.leading_zeros();
+            UnsignedInteger::<$leading_bits>::new(leading).ser(writer);
+            if leading == BITS {
+                return;
+            }
+
+            let trailing = changed.trailing_zeros();
+            let width = BITS - leading - trailing;
+            UnsignedInteger::<$width_bits>::new(width - 1).ser(writer);
+            let changed = changed >> trailing;
+            for index in 0..width {
+                writer.write_bit(changed & (1 << index) != 0);
+            }
+        }
+
+        fn de_delta(baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+            const BITS: u32 = (std::mem::size_of::<$impl_type>() * 8) as u32;
+
+            let leading = UnsignedInteger::<$leading_bits>::de(reader)?.get() as u32;
+            if leading > BITS {
+                return Err(SerdeErr {});
+            }
+            if leading == BITS {
+                return Ok(*baseline);
+            }
+
+            let width = UnsignedInteger::<$width_bits>::de(reader)?.get() as u32 + 1;
+            if leading + width > BITS {
+                return Err(SerdeErr {});
+            }
+            let mut changed = 0;
+            for index in 0..width {
+                if reader.read_bit()? {
+                    changed |= 1 << index;
+                }
+            }
+            let trailing = BITS - leading - width;
+            Ok($impl_type::from_bits(
+                baseline.to_bits() ^ (changed << trailing),
+            ))
+        }
+    };
+}
+
+macro_rules! impl_serde_for {
+    ($impl_type:ident $(, $delta_impl:ident $(, $delta_arg:literal)*)?) => {
         impl Serde for $impl_type {
+            $($delta_impl!($impl_type $(, $delta_arg)*);)?
+
             fn ser(&self, writer: &mut dyn BitWrite) {
                 let du8 = unsafe {
                     std::mem::transmute::<&$impl_type, &[u8; std::mem::size_of::<$impl_type>()]>(
@@ -193,17 +266,19 @@ macro_rules! impl_serde_for {
 }
 
 // number primitives
-impl_serde_for!(u16);
-impl_serde_for!(u32);
-impl_serde_for!(u64);
-impl_serde_for!(i16);
-impl_serde_for!(i32);
-impl_serde_for!(i64);
-impl_serde_for!(f32);
-impl_serde_for!(f64);
+impl_serde_for!(u16, impl_integer_delta);
+impl_serde_for!(u32, impl_integer_delta);
+impl_serde_for!(u64, impl_integer_delta);
+impl_serde_for!(i16, impl_integer_delta);
+impl_serde_for!(i32, impl_integer_delta);
+impl_serde_for!(i64, impl_integer_delta);
+impl_serde_for!(f32, impl_float_delta, 6, 5);
+impl_serde_for!(f64, impl_float_delta, 7, 6);
 
 // u8
 impl Serde for u8 {
+    impl_integer_delta!(u8);
+
     fn ser(&self, writer: &mut dyn BitWrite) {
         writer.write_byte(*self);
     }
@@ -215,6 +290,8 @@ impl Serde for u8 {
 
 // i8
 impl Serde for i8 {
+    impl_integer_delta!(i8);
+
     fn ser(&self, writer: &mut dyn BitWrite) {
         let du8 = unsafe { std::mem::transmute::<&i8, &u8>(self) };
         writer.write_byte(*du8);
@@ -236,6 +313,8 @@ impl Serde for i8 {
 
 // usize
 impl Serde for usize {
+    impl_integer_delta!(usize);
+
     fn ser(&self, writer: &mut dyn BitWrite) {
         let u64usize = *self as u64;
         let du8 = unsafe { std::mem::transmute::<&u64, &[u8; 8]>(&u64usize) };
@@ -263,6 +342,8 @@ impl Serde for usize {
 
 // isize
 impl Serde for isize {
+    impl_integer_delta!(isize);
+
     fn ser(&self, writer: &mut dyn BitWrite) {
         let u64usize = *self as u64;
         let du8 = unsafe { std::mem::transmute::<&u64, &[u8; 8]>(&u64usize) };
@@ -332,3 +413,75 @@ mod number_tests {
     test_serde_for!(f32, test_f32);
     test_serde_for!(f64, test_f64);
 }
+
+#[cfg(test)]
+mod delta_tests {
+    use crate::{
+        reader_writer::{BitReader, BitWrite, BitWriter},
+        serde::Serde,
+    };
+
+    #[test]
+    fn integer_deltas_are_small() {
+        // Write
+        let mut writer = BitWriter::new();
+
+        let baseline_1: u32 = 1_000_000;
+        let in_1: u32 = 1_000_003;
+        let baseline_2: i16 = 12;
+        let in_2: i16 = -20;
+
+        in_1.ser_delta(&baseline_1, &mut writer);
+        in_2.ser_delta(&baseline_2, &mut writer);
+
+        assert!(writer.bit_count() < 32);
+
+        let (buffer_length, buffer) = writer.flush();
+
+        // Read
+        let mut reader = BitReader::new(&buffer[..buffer_length]);
+
+        let out_1 = u32::de_delta(&baseline_1, &mut reader).unwrap();
+        let out_2 = i16::de_delta(&baseline_2, &mut reader).unwrap();
+
+        assert_eq!(in_1, out_1);
+        assert_eq!(in_2, out_2);
+    }
+
+    #[test]
+    fn float_deltas_are_small_and_lossless() {
+        // Write
+        let mut writer = BitWriter::new();
+
+        let baseline_1: f32 = 100.0;
+        let in_1: f32 = 100.0;
+        let baseline_2: f32 = 100.0;
+        let in_2: f32 = 100.5;
+        let baseline_3: f64 = 12.75;
+        let in_3: f64 = -0.1;
+        let baseline_4: f32 = 1.0;
+        let in_4: f32 = f32::NAN;
+
+        in_1.ser_delta(&baseline_1, &mut writer);
+        assert_eq!(writer.bit_count(), 6);
+        in_2.ser_delta(&baseline_2, &mut writer);
+        assert!(writer.bit_count() < 6 + 32);
+        in_3.ser_delta(&baseline_3, &mut writer);
+        in_4.ser_delta(&baseline_4, &mut writer);
+
+        let (buffer_length, buffer) = writer.flush();
+
+        // Read
+        let mut reader = BitReader::new(&buffer[..buffer_length]);
+
+        let out_1 = f32::de_delta(&baseline_1, &mut reader).unwrap();
+        let out_2 = f32::de_delta(&baseline_2, &mut reader).unwrap();
+        let out_3 = f64::de_delta(&baseline_3, &mut reader).unwrap();
+        let out_4 = f32::de_delta(&baseline_4, &mut reader).unwrap();
+
+        assert_eq!(in_1.to_bits(), out_1.to_bits());
+        assert_eq!(in_2.to_bits(), out_2.to_bits());
+        assert_eq!(in_3.to_bits(), out_3.to_bits());
+        assert_eq!(in_4.to_bits(), out_4.to_bits());
+    }
+}
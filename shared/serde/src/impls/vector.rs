@@ -19,7 +19,8 @@ impl<T: Serde> Serde for Vec<T> {
     fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
         let length_int = UnsignedVariableInteger::<5>::de(reader)?;
         let length_usize = length_int.get() as usize;
-        let mut output: Vec<T> = Vec::with_capacity(length_usize);
+        // the length is read from the remote host, so only grow as items are read
+        let mut output: Vec<T> = Vec::new();
         for _ in 0..length_usize {
             output.push(T::de(reader)?)
         }
@@ -39,7 +40,8 @@ impl<T: Serde> Serde for VecDeque<T> {
     fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
         let length_int = UnsignedVariableInteger::<5>::de(reader)?;
         let length_usize = length_int.get() as usize;
-        let mut output: VecDeque<T> = VecDeque::with_capacity(length_usize);
+        // the length is read from the remote host, so only grow as items are read
+        let mut output: VecDeque<T> = VecDeque::new();
         for _ in 0..length_usize {
             output.push_back(T::de(reader)?)
         }
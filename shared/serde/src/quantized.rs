@@ -0,0 +1,490 @@
+use crate::{
+    error::SerdeErr,
+    integer::{SignedVariableInteger, UnsignedInteger},
+    reader_writer::{BitReader, BitWrite},
+    serde::Serde,
+};
+
+// QuantizedFloat //
+
+/// A float within the range `MIN..=MAX`, written as a fixed-point number of
+/// `BITS` bits (at most 32). Values outside of the range are clamped to it.
+/// Within the range, a value is stored as the nearest of `2^BITS` evenly
+/// spaced steps, and so is read back within `max_error()` of the value given,
+/// which is `(MAX - MIN) / (2^BITS - 1) / 2`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuantizedFloat<const MIN: i32, const MAX: i32, const BITS: u8> {
+    step: u64,
+}
+
+impl<const MIN: i32, const MAX: i32, const BITS: u8> QuantizedFloat<MIN, MAX, BITS> {
+    pub fn new(value: f32) -> Self {
+        if MIN >= MAX {
+            panic!("QuantizedFloat's MIN must be less than its MAX!");
+        }
+        if BITS == 0 || BITS > 32 {
+            panic!("QuantizedFloat must have between 1 and 32 bits!");
+        }
+
+        let mut output = Self { step: 0 };
+        output.set(value);
+        output
+    }
+
+    pub fn get(&self) -> f32 {
+        (MIN as f64 + self.step as f64 * Self::step_size()) as f32
+    }
+
+    pub fn set(&mut self, value: f32) {
+        let clamped = (value as f64).clamp(MIN as f64, MAX as f64);
+        self.step = ((clamped - MIN as f64) / Self::step_size()).round() as u64;
+    }
+
+    /// The largest difference between a value within range, and the value
+    /// it is read back as
+    pub fn max_error() -> f32 {
+        (Self::step_size() / 2.0) as f32
+    }
+
+    fn max_step() -> u64 {
+        (1 << BITS) - 1
+    }
+
+    fn step_size() -> f64 {
+        (MAX as f64 - MIN as f64) / Self::max_step() as f64
+    }
+}
+
+impl<const MIN: i32, const MAX: i32, const BITS: u8> Serde for QuantizedFloat<MIN, MAX, BITS> {
+    fn ser(&self, writer: &mut dyn BitWrite) {
+        UnsignedInteger::<BITS>::new(self.step).ser(writer);
+    }
+
+    fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        let step = UnsignedInteger::<BITS>::de(reader)?.get() as u64;
+        Ok(Self { step })
+    }
+
+    // Steps are delta-encoded like integers, so that slowly changing values
+    // take only a few bits
+    fn ser_delta(&self, baseline: &Self, writer: &mut dyn BitWrite) {
+        SignedVariableInteger::<4>::new(self.step as i128 - baseline.step as i128).ser(writer);
+    }
+
+    fn de_delta(baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        let delta = SignedVariableInteger::<4>::de(reader)?.get();
+        let step = baseline.step as i128 + delta;
+        if step < 0 || step > Self::max_step() as i128 {
+            return Err(SerdeErr {});
+        }
+        Ok(Self { step: step as u64 })
+    }
+}
+
+// Smallest-three encoding //
+
+// Every component of a unit-length vector other than its largest lies within
+// ±1/√2, so only those are written, and the largest is recovered from them
+const SMALLEST_BOUND: f64 = std::f64::consts::FRAC_1_SQRT_2;
+
+fn quantize_smallest(value: f64, bits: u8) -> u64 {
+    let max_step = ((1_u64 << bits) - 1) as f64;
+    let clamped = value.clamp(-SMALLEST_BOUND, SMALLEST_BOUND);
+    ((clamped + SMALLEST_BOUND) / (2.0 * SMALLEST_BOUND) * max_step).round() as u64
+}
+
+fn dequantize_smallest(step: u64, bits: u8) -> f64 {
+    let max_step = ((1_u64 << bits) - 1) as f64;
+    step as f64 / max_step * (2.0 * SMALLEST_BOUND) - SMALLEST_BOUND
+}
+
+fn smallest_max_error(bits: u8) -> f32 {
+    (SMALLEST_BOUND / ((1_u64 << bits) - 1) as f64) as f32
+}
+
+// Returns the index of the component with the largest magnitude
+fn largest_index(components: &[f64]) -> usize {
+    let mut largest = 0;
+    for index in 1..components.len() {
+        if components[index].abs() > components[largest].abs() {
+            largest = index;
+        }
+    }
+    largest
+}
+
+// Normalizes the given components, falling back to the given default if they
+// have no length
+fn normalize<const N: usize>(components: [f32; N], default: [f64; N]) -> [f64; N] {
+    let mut output = components.map(|component| component as f64);
+    let length = output
+        .iter()
+        .map(|component| component * component)
+        .sum::<f64>()
+        .sqrt();
+    if length == 0.0 || !length.is_finite() {
+        return default;
+    }
+    for component in &mut output {
+        *component /= length;
+    }
+    output
+}
+
+// QuantizedQuaternion //
+
+/// A rotation quaternion, written with smallest-three encoding: the index of
+/// its largest component in 2 bits, then each of the other three components
+/// in `BITS` bits (at most 32). The quaternion is normalized when set. Each of
+/// the three smallest components is read back within `max_error()` of its
+/// normalized value, which is `1 / √2 / (2^BITS - 1)`, and the largest is
+/// recovered from them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuantizedQuaternion<const BITS: u8> {
+    largest: u8,
+    steps: [u64; 3],
+}
+
+impl<const BITS: u8> QuantizedQuaternion<BITS> {
+    /// Creates a new QuantizedQuaternion from the components (x, y, z, w)
+    pub fn new(quaternion: [f32; 4]) -> Self {
+        if BITS == 0 || BITS > 32 {
+            panic!("QuantizedQuaternion must have between 1 and 32 bits!");
+        }
+
+        let mut output = Self {
+            largest: 0,
+            steps: [0; 3],
+        };
+        output.set(quaternion);
+        output
+    }
+
+    /// Returns the components (x, y, z, w)
+    pub fn get(&self) -> [f32; 4] {
+        let largest = self.largest as usize;
+        let mut output = [0.0; 4];
+        let mut sum_of_squares = 0.0;
+        let mut step_index = 0;
+        for (index, component) in output.iter_mut().enumerate() {
+            if index == largest {
+                continue;
+            }
+            let value = dequantize_smallest(self.steps[step_index], BITS);
+            sum_of_squares += value * value;
+            *component = value as f32;
+            step_index += 1;
+        }
+        output[largest] = (1.0 - sum_of_squares).max(0.0).sqrt() as f32;
+        output
+    }
+
+    /// Sets the components (x, y, z, w)
+    pub fn set(&mut self, quaternion: [f32; 4]) {
+        let mut components = normalize(quaternion, [0.0, 0.0, 0.0, 1.0]);
+        let largest = largest_index(&components);
+
+        // a quaternion and its negation are the same rotation, so the largest
+        // component is always made positive, and its sign need not be written
+        if components[largest] < 0.0 {
+            for component in &mut components {
+                *component = -*component;
+            }
+        }
+
+        self.largest = largest as u8;
+        let mut step_index = 0;
+        for (index, component) in components.iter().enumerate() {
+            if index == largest {
+                continue;
+            }
+            self.steps[step_index] = quantize_smallest(*component, BITS);
+            step_index += 1;
+        }
+    }
+
+    /// The largest difference between each of the three smallest normalized
+    /// components, and the values they are read back as
+    pub fn max_error() -> f32 {
+        smallest_max_error(BITS)
+    }
+}
+
+impl<const BITS: u8> Serde for QuantizedQuaternion<BITS> {
+    fn ser(&self, writer: &mut dyn BitWrite) {
+        UnsignedInteger::<2>::new(self.largest).ser(writer);
+        for step in &self.steps {
+            UnsignedInteger::<BITS>::new(*step).ser(writer);
+        }
+    }
+
+    fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        let largest = UnsignedInteger::<2>::de(reader)?.get() as u8;
+        let mut steps = [0; 3];
+        for step in &mut steps {
+            *step = UnsignedInteger::<BITS>::de(reader)?.get() as u64;
+        }
+        Ok(Self { largest, steps })
+    }
+}
+
+// QuantizedUnitVector //
+
+/// A direction in 3D space, written with smallest-three encoding: the index of
+/// its largest component in 2 bits and that component's sign in 1 bit, then
+/// each of the other two components in `BITS` bits (at most 32). The vector is
+/// normalized when set. Each of the two smallest components is read back within
+/// `max_error()` of its normalized value, which is `1 / √2 / (2^BITS - 1)`,
+/// and the largest is recovered from them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct QuantizedUnitVector<const BITS: u8> {
+    largest: u8,
+    negative: bool,
+    steps: [u64; 2],
+}
+
+impl<const BITS: u8> QuantizedUnitVector<BITS> {
+    /// Creates a new QuantizedUnitVector from the components (x, y, z)
+    pub fn new(vector: [f32; 3]) -> Self {
+        if BITS == 0 || BITS > 32 {
+            panic!("QuantizedUnitVector must have between 1 and 32 bits!");
+        }
+
+        let mut output = Self {
+            largest: 0,
+            negative: false,
+            steps: [0; 2],
+        };
+        output.set(vector);
+        output
+    }
+
+    /// Returns the components (x, y, z)
+    pub fn get(&self) -> [f32; 3] {
+        let largest = self.largest as usize;
+        let mut output = [0.0; 3];
+        let mut sum_of_squares = 0.0;
+        let mut step_index = 0;
+        for (index, component) in output.iter_mut().enumerate() {
+            if index == largest {
+                continue;
+            }
+            let value = dequantize_smallest(self.steps[step_index], BITS);
+            sum_of_squares += value * value;
+            *component = value as f32;
+            step_index += 1;
+        }
+        let largest_value = (1.0 - sum_of_squares).max(0.0).sqrt() as f32;
+        output[largest] = if self.negative {
+            -largest_value
+        } else {
+            largest_value
+        };
+        output
+    }
+
+    /// Sets the components (x, y, z)
+    pub fn set(&mut self, vector: [f32; 3]) {
+        let components = normalize(vector, [0.0, 0.0, 1.0]);
+        let largest = largest_index(&components);
+
+        self.largest = largest as u8;
+        self.negative = components[largest] < 0.0;
+        let mut step_index = 0;
+        for (index, component) in components.iter().enumerate() {
+            if index == largest {
+                continue;
+            }
+            self.steps[step_index] = quantize_smallest(*component, BITS);
+            step_index += 1;
+        }
+    }
+
+    /// The largest difference between each of the two smallest normalized
+    /// components, and the values they are read back as
+    pub fn max_error() -> f32 {
+        smallest_max_error(BITS)
+    }
+}
+
+impl<const BITS: u8> Serde for QuantizedUnitVector<BITS> {
+    fn ser(&self, writer: &mut dyn BitWrite) {
+        UnsignedInteger::<2>::new(self.largest).ser(writer);
+        self.negative.ser(writer);
+        for step in &self.steps {
+            UnsignedInteger::<BITS>::new(*step).ser(writer);
+        }
+    }
+
+    fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        let largest = UnsignedInteger::<2>::de(reader)?.get() as u8;
+        if largest > 2 {
+            return Err(SerdeErr {});
+        }
+        let negative = bool::de(reader)?;
+        let mut steps = [0; 2];
+        for step in &mut steps {
+            *step = UnsignedInteger::<BITS>::de(reader)?.get() as u64;
+        }
+        Ok(Self {
+            largest,
+            negative,
+            steps,
+        })
+    }
+}
+
+// Tests
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        quantized::{QuantizedFloat, QuantizedQuaternion, QuantizedUnitVector},
+        reader_writer::{BitReader, BitWrite, BitWriter},
+        serde::Serde,
+    };
+
+    #[test]
+    fn read_write_float() {
+        type Position = QuantizedFloat<-100, 100, 12>;
+
+        // Write
+        let mut writer = BitWriter::new();
+
+        let in_1 = Position::new(12.345);
+        let in_2 = Position::new(-100.0);
+        let in_3 = Position::new(250.0);
+
+        in_1.ser(&mut writer);
+        in_2.ser(&mut writer);
+        in_3.ser(&mut writer);
+
+        assert_eq!(writer.bit_count(), 36);
+
+        let (buffer_length, buffer) = writer.flush();
+
+        // Read
+        let mut reader = BitReader::new(&buffer[..buffer_length]);
+
+        let out_1 = Position::de(&mut reader).unwrap();
+        let out_2 = Position::de(&mut reader).unwrap();
+        let out_3 = Position::de(&mut reader).unwrap();
+
+        assert_eq!(in_1, out_1);
+        assert!((out_1.get() - 12.345).abs() <= Position::max_error());
+        assert_eq!(out_2.get(), -100.0);
+        // out of range values are clamped
+        assert_eq!(out_3.get(), 100.0);
+    }
+
+    #[test]
+    fn float_error_is_bounded() {
+        type Position = QuantizedFloat<-10, 10, 8>;
+
+        for index in 0..1000 {
+            let value = -10.0 + index as f32 * 0.02;
+            let error = (Position::new(value).get() - value).abs();
+            assert!(error <= Position::max_error() + f32::EPSILON * 10.0);
+        }
+    }
+
+    #[test]
+    fn float_deltas_are_small() {
+        type Position = QuantizedFloat<-1000, 1000, 20>;
+
+        // Write
+        let mut writer = BitWriter::new();
+
+        let baseline = Position::new(500.0);
+        let in_1 = Position::new(500.01);
+
+        in_1.ser_delta(&baseline, &mut writer);
+
+        assert!(writer.bit_count() < 20);
+
+        let (buffer_length, buffer) = writer.flush();
+
+        // Read
+        let mut reader = BitReader::new(&buffer[..buffer_length]);
+
+        let out_1 = Position::de_delta(&baseline, &mut reader).unwrap();
+
+        assert_eq!(in_1, out_1);
+    }
+
+    #[test]
+    fn read_write_quaternion() {
+        type Rotation = QuantizedQuaternion<10>;
+
+        // a rotation about an arbitrary axis, with its largest component negative
+        let half_angle: f32 = 1.2;
+        let axis = [0.267_261_24, 0.534_522_5, 0.801_783_7];
+        let sin = half_angle.sin();
+        let quaternion = [
+            -axis[0] * sin,
+            -axis[1] * sin,
+            -axis[2] * sin,
+            -half_angle.cos(),
+        ];
+
+        // Write
+        let mut writer = BitWriter::new();
+
+        let in_1 = Rotation::new(quaternion);
+
+        in_1.ser(&mut writer);
+
+        assert_eq!(writer.bit_count(), 32);
+
+        let (buffer_length, buffer) = writer.flush();
+
+        // Read
+        let mut reader = BitReader::new(&buffer[..buffer_length]);
+
+        let out_1 = Rotation::de(&mut reader).unwrap();
+
+        assert_eq!(in_1, out_1);
+
+        // the read quaternion is the same rotation, possibly negated
+        let output = out_1.get();
+        let dot: f32 = (0..4).map(|index| output[index] * quaternion[index]).sum();
+        for index in 0..4 {
+            let expected = quaternion[index] * dot.signum();
+            assert!((output[index] - expected).abs() <= 4.0 * Rotation::max_error());
+        }
+    }
+
+    #[test]
+    fn read_write_unit_vector() {
+        type Direction = QuantizedUnitVector<9>;
+
+        // Write
+        let mut writer = BitWriter::new();
+
+        let in_1 = Direction::new([3.0, -4.0, 12.0]);
+        let in_2 = Direction::new([0.0, -2.0, 0.0]);
+
+        in_1.ser(&mut writer);
+        in_2.ser(&mut writer);
+
+        assert_eq!(writer.bit_count(), 42);
+
+        let (buffer_length, buffer) = writer.flush();
+
+        // Read
+        let mut reader = BitReader::new(&buffer[..buffer_length]);
+
+        let out_1 = Direction::de(&mut reader).unwrap();
+        let out_2 = Direction::de(&mut reader).unwrap();
+
+        assert_eq!(in_1, out_1);
+        assert_eq!(in_2, out_2);
+
+        let expected = [3.0 / 13.0, -4.0 / 13.0, 12.0 / 13.0];
+        let output = out_1.get();
+        for index in 0..3 {
+            assert!((output[index] - expected[index]).abs() <= 4.0 * Direction::max_error());
+        }
+        assert!((out_2.get()[1] + 1.0).abs() <= 4.0 * Direction::max_error());
+    }
+}
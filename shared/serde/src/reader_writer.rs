@@ -40,24 +40,39 @@ impl BitWrite for BitCounter {
 pub struct BitWriter {
     scratch: u8,
     scratch_index: u8,
-    buffer: [u8; MAX_BUFFER_SIZE],
+    buffer: Vec<u8>,
     buffer_index: usize,
 }
 
 impl BitWriter {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
+        Self::with_max_size(MAX_BUFFER_SIZE)
+    }
+
+    /// Creates a BitWriter which can hold up to `max_size` bytes. Bit counts
+    /// are measured with a u16, so this can't be more than 8191 bytes.
+    pub fn with_max_size(max_size: usize) -> Self {
+        if max_size > (u16::MAX / 8) as usize {
+            panic!("BitWriter can't hold more than {} bytes", u16::MAX / 8);
+        }
+
         Self {
             scratch: 0,
             scratch_index: 0,
-            buffer: [0; MAX_BUFFER_SIZE],
+            buffer: vec![0; max_size],
             buffer_index: 0,
         }
     }
+
+    /// Returns the maximum number of bits this BitWriter can hold
+    pub fn max_bits(&self) -> u16 {
+        (self.buffer.len() * 8) as u16
+    }
 }
 
 impl BitWriter {
-    pub fn flush(&mut self) -> (usize, [u8; MAX_BUFFER_SIZE]) {
+    pub fn flush(&mut self) -> (usize, Vec<u8>) {
         if self.scratch_index > 0 {
             self.buffer[self.buffer_index] =
                 (self.scratch << (8 - self.scratch_index)).reverse_bits();
@@ -70,10 +85,7 @@ impl BitWriter {
         self.scratch_index = 0;
         self.scratch = 0;
 
-        let mut output_buffer = [0; MAX_BUFFER_SIZE];
-        output_buffer.clone_from_slice(&self.buffer[0..MAX_BUFFER_SIZE]);
-
-        (output_length, output_buffer)
+        (output_length, self.buffer[..output_length].to_vec())
     }
 }
 
@@ -10,4 +10,17 @@ pub trait Serde: Sized + Clone + PartialEq {
 
     /// Parse Self from a BitReader
     fn de(reader: &mut BitReader) -> Result<Self, SerdeErr>;
+
+    /// Serialize Self to a BitWriter, as a change from a baseline value which
+    /// the reader already has. Unless overridden, the whole value is written.
+    /// Integers, floats & quantized types are delta-encoded
+    fn ser_delta(&self, _baseline: &Self, writer: &mut dyn BitWrite) {
+        self.ser(writer);
+    }
+
+    /// Parse Self from a BitReader, given the baseline value it was written
+    /// against with `ser_delta`
+    fn de_delta(_baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        Self::de(reader)
+    }
 }
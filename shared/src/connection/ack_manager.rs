@@ -51,7 +51,7 @@ impl AckManager {
         &mut self,
         header: &StandardHeader,
         message_manager: &mut MessageManager<P, C>,
-        packet_notifiable: &mut Option<&mut dyn PacketNotifiable>,
+        packet_notifiables: &mut [&mut dyn PacketNotifiable],
     ) {
         let sender_packet_index = header.sender_packet_index;
         let sender_ack_index = header.sender_ack_index;
@@ -69,7 +69,7 @@ impl AckManager {
         // the current `sender_ack_index` was (clearly) received so we should remove it
         if let Some(sent_packet) = self.sent_packets.get(&sender_ack_index) {
             if sent_packet.packet_type == PacketType::Data {
-                self.notify_packet_delivered(sender_ack_index, message_manager, packet_notifiable);
+                self.notify_packet_delivered(sender_ack_index, message_manager, packet_notifiables);
             }
 
             self.sent_packets.remove(&sender_ack_index);
@@ -86,7 +86,7 @@ impl AckManager {
                         self.notify_packet_delivered(
                             sent_packet_index,
                             message_manager,
-                            packet_notifiable,
+                            packet_notifiables,
                         );
                     }
 
@@ -131,10 +131,10 @@ impl AckManager {
         &self,
         sent_packet_index: PacketIndex,
         message_manager: &mut MessageManager<P, C>,
-        packet_notifiable: &mut Option<&mut dyn PacketNotifiable>,
+        packet_notifiables: &mut [&mut dyn PacketNotifiable],
     ) {
         message_manager.notify_packet_delivered(sent_packet_index);
-        if let Some(notifiable) = packet_notifiable {
+        for notifiable in packet_notifiables.iter_mut() {
             notifiable.notify_packet_delivered(sent_packet_index);
         }
     }
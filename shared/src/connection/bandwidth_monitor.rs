@@ -1,8 +1,10 @@
 use std::time::Duration;
 
 pub struct BandwidthMonitor {
-    time_queue: TimeQueue<usize>,
-    total_bytes: u16,
+    // (bytes sent over the wire, bytes before compression)
+    time_queue: TimeQueue<(usize, usize)>,
+    total_bytes: usize,
+    total_uncompressed_bytes: usize,
     to_kbps_factor: f32,
 }
 
@@ -11,15 +13,23 @@ impl BandwidthMonitor {
         BandwidthMonitor {
             time_queue: TimeQueue::new(bandwidth_measure_duration),
             total_bytes: 0,
+            total_uncompressed_bytes: 0,
             to_kbps_factor: 0.008 / bandwidth_measure_duration.as_secs_f32(),
         }
     }
 
     pub fn record_packet(&mut self, bytes: usize) {
+        self.record_compressed_packet(bytes, bytes);
+    }
+
+    /// Records a packet of `bytes` size which was `uncompressed_bytes` in size
+    /// before compression
+    pub fn record_compressed_packet(&mut self, bytes: usize, uncompressed_bytes: usize) {
         self.clear_expired_packets();
 
-        self.total_bytes += bytes as u16;
-        self.time_queue.add_item(bytes);
+        self.total_bytes += bytes;
+        self.total_uncompressed_bytes += uncompressed_bytes;
+        self.time_queue.add_item((bytes, uncompressed_bytes));
     }
 
     pub fn bandwidth(&mut self) -> f32 {
@@ -28,9 +38,21 @@ impl BandwidthMonitor {
         self.total_bytes as f32 * self.to_kbps_factor
     }
 
+    /// Ratio of bytes sent over the wire to bytes before compression, over the
+    /// measured duration. Lower is better, 1.0 means no savings at all.
+    pub fn compression_ratio(&mut self) -> f32 {
+        self.clear_expired_packets();
+
+        if self.total_uncompressed_bytes == 0 {
+            return 1.0;
+        }
+        self.total_bytes as f32 / self.total_uncompressed_bytes as f32
+    }
+
     fn clear_expired_packets(&mut self) {
-        while let Some(bytes) = self.time_queue.pop_item() {
-            self.total_bytes -= bytes as u16;
+        while let Some((bytes, uncompressed_bytes)) = self.time_queue.pop_item() {
+            self.total_bytes -= bytes;
+            self.total_uncompressed_bytes -= uncompressed_bytes;
         }
     }
 }
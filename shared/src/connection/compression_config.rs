@@ -31,4 +31,14 @@ pub enum CompressionMode {
     /// (packets) to train on. Obviously, the more samples trained on, the
     /// better theoretical compression.
     Training(usize),
+    /// Compression mode using a custom dictionary which is provided by the
+    /// Server at runtime, via `Server::set_compression_dictionary()`. The
+    /// dictionary is shipped to Clients during the handshake, and can be
+    /// replaced at any time without reconnecting.
+    /// 1st i32 parameter here is the compression level from -7 (fastest) to 22
+    /// (smallest).
+    ServerDictionary(i32),
+    /// Compression mode using lz4, which trades some compression ratio for a
+    /// much lower CPU cost. Requires the `lz4_support` feature.
+    Lz4,
 }
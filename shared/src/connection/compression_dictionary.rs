@@ -0,0 +1,209 @@
+use naia_serde::{BitReader, BitWriter, Serde, SerdeErr, UnsignedInteger};
+
+use crate::constants::MTU_SIZE_BYTES;
+
+use super::{packet_type::PacketType, standard_header::StandardHeader};
+
+/// Number of dictionary bytes sent in each `ServerDictionaryChunk` packet
+pub const DICTIONARY_CHUNK_SIZE: usize = 400;
+
+/// Size of a `ClientDictionaryRequest` packet. Requests are padded to be larger
+/// than any `ServerDictionaryChunk` packet, so that answering them can't be
+/// used to amplify traffic towards a spoofed address. One byte is left for the
+/// compression flag, so that requests still fit within `MTU_SIZE_BYTES`
+pub const DICTIONARY_REQUEST_BYTES: usize = MTU_SIZE_BYTES as usize - 1;
+
+/// Dictionary versions are written into 7 bits of each compressed packet's
+/// flag byte. Version 0 is reserved for compression without a shared
+/// dictionary.
+pub const MAX_DICTIONARY_VERSION: u8 = 127;
+
+/// Largest dictionary which a Client will accept, in bytes
+pub const MAX_DICTIONARY_SIZE: usize = 1 << 20;
+
+pub type DictionaryVersion = UnsignedInteger<7>;
+
+/// A versioned compression dictionary, distributed by the Server to Clients
+#[derive(Clone)]
+pub struct CompressionDictionary {
+    pub version: u8,
+    pub bytes: Vec<u8>,
+}
+
+impl CompressionDictionary {
+    pub fn new(version: u8, bytes: Vec<u8>) -> Self {
+        if version == 0 || version > MAX_DICTIONARY_VERSION {
+            panic!(
+                "Dictionary version must be between 1 and {}",
+                MAX_DICTIONARY_VERSION
+            );
+        }
+
+        Self { version, bytes }
+    }
+
+    /// Version to use for the next dictionary, after this one
+    pub fn next_version(&self) -> u8 {
+        (self.version % MAX_DICTIONARY_VERSION) + 1
+    }
+
+    pub fn chunk_count(&self) -> u16 {
+        self.bytes.len().div_ceil(DICTIONARY_CHUNK_SIZE) as u16
+    }
+
+    /// Writes a `ServerDictionaryChunk` packet containing the chunk at the
+    /// given index
+    pub fn write_chunk(&self, index: u16) -> BitWriter {
+        let mut writer = BitWriter::new();
+        StandardHeader::new(PacketType::ServerDictionaryChunk, 0, 0, 0).ser(&mut writer);
+
+        DictionaryVersion::new(self.version).ser(&mut writer);
+        (self.bytes.len() as u32).ser(&mut writer);
+        index.ser(&mut writer);
+
+        let start = (index as usize * DICTIONARY_CHUNK_SIZE).min(self.bytes.len());
+        let end = (start + DICTIONARY_CHUNK_SIZE).min(self.bytes.len());
+        self.bytes[start..end].to_vec().ser(&mut writer);
+
+        writer
+    }
+}
+
+/// Reassembles a CompressionDictionary from incoming `ServerDictionaryChunk`
+/// packets
+#[derive(Default)]
+pub struct DictionaryReceiver {
+    version: u8,
+    length: usize,
+    chunks: Vec<Option<Vec<u8>>>,
+    // version of the last dictionary which was completely received
+    completed_version: u8,
+}
+
+impl DictionaryReceiver {
+    /// Reads an incoming chunk, discarding any partially received dictionary
+    /// of a different version. Returns the completed dictionary once all chunks
+    /// have arrived.
+    pub fn read_chunk(
+        &mut self,
+        reader: &mut BitReader,
+    ) -> Result<Option<CompressionDictionary>, SerdeErr> {
+        let version: u8 = DictionaryVersion::de(reader)?.get() as u8;
+        let length = u32::de(reader)? as usize;
+        let index = u16::de(reader)? as usize;
+        let bytes = Vec::<u8>::de(reader)?;
+
+        if version == 0 || version == self.completed_version || length > MAX_DICTIONARY_SIZE {
+            return Ok(None);
+        }
+
+        if version != self.version || length != self.length {
+            let chunk_count = length.div_ceil(DICTIONARY_CHUNK_SIZE);
+            self.version = version;
+            self.length = length;
+            self.chunks = vec![None; chunk_count];
+        }
+
+        // every chunk is full-sized, except for the last one
+        let expected_size =
+            (length - (index * DICTIONARY_CHUNK_SIZE).min(length)).min(DICTIONARY_CHUNK_SIZE);
+        if bytes.len() != expected_size {
+            return Err(SerdeErr);
+        }
+        if let Some(chunk) = self.chunks.get_mut(index) {
+            *chunk = Some(bytes);
+        }
+
+        if self.chunks.iter().any(|chunk| chunk.is_none()) {
+            return Ok(None);
+        }
+
+        let mut dictionary_bytes = Vec::with_capacity(self.length);
+        for chunk in self.chunks.drain(..) {
+            dictionary_bytes.extend_from_slice(&chunk.unwrap());
+        }
+
+        self.completed_version = self.version;
+        Ok(Some(CompressionDictionary::new(
+            self.version,
+            dictionary_bytes,
+        )))
+    }
+
+    /// Starts requesting the given dictionary version, unless it is already
+    /// being received or has been completed
+    pub fn expect_version(&mut self, version: u8) {
+        if version == self.version || version == self.completed_version {
+            return;
+        }
+
+        self.version = version;
+        self.length = 0;
+        self.chunks = Vec::new();
+    }
+
+    /// Returns the version being requested and the index of the next chunk
+    /// which is needed. An index past the last chunk indicates that the
+    /// dictionary has been completely received.
+    pub fn next_request(&self) -> (u8, u16) {
+        if self.version == self.completed_version {
+            return (self.version, u16::MAX);
+        }
+
+        // nothing received yet, start with the first chunk
+        let index = self
+            .chunks
+            .iter()
+            .position(|chunk| chunk.is_none())
+            .unwrap_or(0);
+        (self.version, index as u16)
+    }
+
+    pub fn completed_version(&self) -> u8 {
+        self.completed_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use naia_serde::{BitReader, Serde};
+
+    use super::{
+        CompressionDictionary, DictionaryReceiver, DICTIONARY_CHUNK_SIZE, DICTIONARY_REQUEST_BYTES,
+    };
+    use crate::{PacketType, StandardHeader};
+
+    #[test]
+    fn reassembles_chunks_in_any_order() {
+        let bytes: Vec<u8> = (0..(DICTIONARY_CHUNK_SIZE * 2 + 10))
+            .map(|i| i as u8)
+            .collect();
+        let dictionary = CompressionDictionary::new(3, bytes.clone());
+        assert_eq!(dictionary.chunk_count(), 3);
+
+        let mut receiver = DictionaryReceiver::default();
+        let mut completed = None;
+        for index in [2, 0, 1] {
+            let mut writer = dictionary.write_chunk(index);
+            let (length, buffer) = writer.flush();
+            let mut reader = BitReader::new(&buffer[..length]);
+            let header = StandardHeader::de(&mut reader).unwrap();
+            assert_eq!(header.packet_type, PacketType::ServerDictionaryChunk);
+            completed = receiver.read_chunk(&mut reader).unwrap();
+        }
+
+        let completed = completed.expect("dictionary should be complete");
+        assert_eq!(completed.version, 3);
+        assert_eq!(completed.bytes, bytes);
+        assert_eq!(receiver.next_request(), (3, u16::MAX));
+    }
+
+    #[test]
+    fn chunks_are_smaller_than_requests() {
+        let dictionary = CompressionDictionary::new(1, vec![7; DICTIONARY_CHUNK_SIZE]);
+        let (length, _) = dictionary.write_chunk(0).flush();
+
+        // leaving room for the compression flag byte
+        assert!(length < DICTIONARY_REQUEST_BYTES);
+    }
+}
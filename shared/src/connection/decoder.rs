@@ -1,69 +1,158 @@
-cfg_if! {
-    if #[cfg(feature = "zstd_support")]
-    {
-        use zstd::bulk::Decompressor;
+use std::collections::VecDeque;
 
-        use super::compression_config::CompressionMode;
+use log::warn;
 
-        pub struct Decoder {
-            result: Vec<u8>,
-            decoder: Option<Decompressor<'static>>,
-        }
+#[cfg(feature = "zstd_support")]
+use zstd::bulk::Decompressor;
 
-        impl Decoder {
-            pub fn new(compression_mode: CompressionMode) -> Self {
-                let decoder = match compression_mode {
-                    CompressionMode::Training(_) => None,
-                    CompressionMode::Default(_) => {
-                        Some(Decompressor::new().expect("error creating Decompressor"))
-                    }
-                    CompressionMode::Dictionary(_, dictionary) => Some(
-                        Decompressor::with_dictionary(&dictionary).expect("error creating Decompressor"),
-                    ),
-                };
-
-                Self {
-                    decoder,
-                    result: Vec::new(),
-                }
+use super::{
+    compression_config::CompressionMode,
+    compression_dictionary::CompressionDictionary,
+    encoder::{COMPRESSED_FLAG, UNCOMPRESSED_FLAG},
+};
+
+/// How many dictionary versions to keep decoders for, so that packets which
+/// were compressed just before a dictionary swap can still be read
+#[cfg(feature = "zstd_support")]
+const DICTIONARY_HISTORY_SIZE: usize = 2;
+
+pub struct Decoder {
+    result: Vec<u8>,
+    decoder: DecoderType,
+    // (dictionary version, decoder), if using CompressionMode::ServerDictionary
+    dictionary_decoders: Option<VecDeque<(u8, DecoderType)>>,
+}
+
+impl Decoder {
+    pub fn new(compression_mode: CompressionMode) -> Self {
+        let dictionary_decoders = match compression_mode {
+            CompressionMode::ServerDictionary(_) => Some(VecDeque::new()),
+            _ => None,
+        };
+
+        let decoder = match compression_mode {
+            #[cfg(feature = "zstd_support")]
+            CompressionMode::Default(_) => {
+                DecoderType::Zstd(Decompressor::new().expect("error creating Decompressor"))
             }
+            #[cfg(feature = "zstd_support")]
+            CompressionMode::Dictionary(_, dictionary) => DecoderType::Zstd(
+                Decompressor::with_dictionary(&dictionary).expect("error creating Decompressor"),
+            ),
+            #[cfg(feature = "lz4_support")]
+            CompressionMode::Lz4 => DecoderType::Lz4,
+            #[allow(unreachable_patterns)]
+            _ => DecoderType::Passthrough,
+        };
 
-            pub fn decode(&mut self, payload: &[u8]) -> &[u8] {
-                if let Some(decoder) = &mut self.decoder {
-                    self.result = decoder
-                        .decompress(
-                            payload,
-                            Decompressor::<'static>::upper_bound(payload)
-                                .expect("upper bound decode error"),
-                        )
-                        .expect("decode error");
-                    return &self.result;
-                } else {
-                    self.result = payload.to_vec();
-                    return &self.result;
-                }
+        Self {
+            decoder,
+            dictionary_decoders,
+            result: Vec::new(),
+        }
+    }
+
+    /// Makes a dictionary available for decompressing incoming packets tagged
+    /// with its version, alongside the most recent previous one. Does nothing
+    /// unless using `CompressionMode::ServerDictionary`.
+    #[allow(unused_variables)]
+    pub fn add_dictionary(&mut self, dictionary: &CompressionDictionary) {
+        #[cfg(feature = "zstd_support")]
+        if let Some(dictionary_decoders) = &mut self.dictionary_decoders {
+            dictionary_decoders.retain(|(version, _)| *version != dictionary.version);
+            dictionary_decoders.push_back((
+                dictionary.version,
+                DecoderType::Zstd(
+                    Decompressor::with_dictionary(&dictionary.bytes)
+                        .expect("error creating Decompressor"),
+                ),
+            ));
+            while dictionary_decoders.len() > DICTIONARY_HISTORY_SIZE {
+                dictionary_decoders.pop_front();
             }
         }
     }
-    else
-    {
-        use super::compression_config::CompressionMode;
 
-        pub struct Decoder {
-            result: Vec<u8>,
+    /// Decodes a packet written by an `Encoder`. A packet which cannot be
+    /// decoded results in an empty payload, which will then be discarded as
+    /// malformed.
+    pub fn decode(&mut self, payload: &[u8]) -> &[u8] {
+        self.result.clear();
+
+        let (flag, body) = match payload.split_first() {
+            Some((flag, body)) => (*flag, body),
+            None => return &self.result,
+        };
+
+        if flag == UNCOMPRESSED_FLAG {
+            self.result.extend_from_slice(body);
+            return &self.result;
         }
+        if flag & COMPRESSED_FLAG == 0 {
+            warn!("Incoming packet has an invalid compression flag");
+            return &self.result;
+        }
+
+        let dictionary_version = flag >> 1;
+        let decoder = if dictionary_version == 0 {
+            Some(&mut self.decoder)
+        } else {
+            self.dictionary_decoders.as_mut().and_then(|decoders| {
+                decoders
+                    .iter_mut()
+                    .find(|(version, _)| *version == dictionary_version)
+                    .map(|(_, decoder)| decoder)
+            })
+        };
+
+        match decoder.and_then(|decoder| decoder.decompress(body)) {
+            Some(decompressed) => self.result = decompressed,
+            None => warn!("Unable to decompress incoming packet"),
+        }
+
+        &self.result
+    }
+}
+
+pub enum DecoderType {
+    #[cfg(feature = "zstd_support")]
+    Zstd(Decompressor<'static>),
+    #[cfg(feature = "lz4_support")]
+    Lz4,
+    /// Used when the backend for the configured CompressionMode is not
+    /// enabled, or while training a dictionary
+    Passthrough,
+}
 
-        impl Decoder {
-            pub fn new(_: CompressionMode) -> Self {
-                Self {
-                    result: Vec::new(),
+impl DecoderType {
+    #[allow(unused_variables)]
+    fn decompress(&mut self, body: &[u8]) -> Option<Vec<u8>> {
+        match self {
+            #[cfg(feature = "zstd_support")]
+            DecoderType::Zstd(decoder) => {
+                // the content size comes from the frame header, check it
+                // before allocating anything. Frames without one are rejected
+                // too, as the size is reported as u64::MAX
+                let size = zstd::zstd_safe::get_frame_content_size(body);
+                if size > crate::constants::MAX_MTU_SIZE_BYTES as u64 {
+                    return None;
                 }
+                decoder.decompress(body, size as usize).ok()
             }
-
-            pub fn decode(&mut self, payload: &[u8]) -> &[u8] {
-                self.result = payload.to_vec();
-                &self.result
+            #[cfg(feature = "lz4_support")]
+            DecoderType::Lz4 => {
+                // the uncompressed size is prepended as a little-endian u32,
+                // check it before allocating anything
+                if body.len() < 4 {
+                    return None;
+                }
+                let size = u32::from_le_bytes([body[0], body[1], body[2], body[3]]) as usize;
+                if size > crate::constants::MAX_MTU_SIZE_BYTES as usize {
+                    return None;
+                }
+                lz4_flex::block::decompress(&body[4..], size).ok()
             }
+            DecoderType::Passthrough => None,
         }
     }
 }
@@ -1,146 +1,254 @@
-cfg_if! {
-    if #[cfg(feature = "zstd_support")]
-    {
-        use std::fs;
+#[cfg(feature = "zstd_support")]
+use log::info;
+
+#[cfg(feature = "zstd_support")]
+use zstd::{bulk::Compressor, dict::from_continuous};
+
+use super::{compression_config::CompressionMode, compression_dictionary::CompressionDictionary};
+
+/// Leading byte of an encoded packet whose payload was sent as-is
+pub const UNCOMPRESSED_FLAG: u8 = 0;
+/// Lowest bit of the leading byte of an encoded packet whose payload was
+/// compressed. The remaining 7 bits hold the version of the dictionary used, if
+/// any.
+pub const COMPRESSED_FLAG: u8 = 1;
+
+/// Compresses outgoing packets. Each packet is compressed on its own, without
+/// reference to earlier packets, as packets may be lost or arrive out of order
+pub struct Encoder {
+    result: Vec<u8>,
+    encoder: EncoderType,
+    // compression level, if using CompressionMode::ServerDictionary
+    #[cfg_attr(not(feature = "zstd_support"), allow(dead_code))]
+    dictionary_level: Option<i32>,
+    dictionary_version: u8,
+}
 
-        use log::info;
+impl Encoder {
+    pub fn new(compression_mode: CompressionMode) -> Self {
+        let dictionary_level = match compression_mode {
+            CompressionMode::ServerDictionary(compression_level) => Some(compression_level),
+            _ => None,
+        };
+
+        let encoder = match compression_mode {
+            #[cfg(feature = "zstd_support")]
+            CompressionMode::Training(sample_size) => {
+                EncoderType::DictionaryTrainer(DictionaryTrainer::new(sample_size))
+            }
+            #[cfg(feature = "zstd_support")]
+            CompressionMode::Default(compression_level) => EncoderType::Zstd(
+                Compressor::new(compression_level).expect("error creating Compressor"),
+            ),
+            #[cfg(feature = "zstd_support")]
+            CompressionMode::Dictionary(compression_level, dictionary) => EncoderType::Zstd(
+                Compressor::with_dictionary(compression_level, &dictionary)
+                    .expect("error creating Compressor with dictionary"),
+            ),
+            #[cfg(feature = "lz4_support")]
+            CompressionMode::Lz4 => EncoderType::Lz4,
+            // until the Server provides a dictionary, packets are sent uncompressed
+            #[allow(unreachable_patterns)]
+            _ => EncoderType::Passthrough,
+        };
+
+        Self {
+            result: Vec::new(),
+            encoder,
+            dictionary_level,
+            dictionary_version: 0,
+        }
+    }
 
-        use zstd::{bulk::Compressor, dict::from_continuous};
+    /// Starts compressing with the given dictionary, tagging every packet with
+    /// its version so the receiver knows which dictionary to decompress with.
+    /// Does nothing unless using `CompressionMode::ServerDictionary`.
+    #[allow(unused_variables)]
+    pub fn set_dictionary(&mut self, dictionary: &CompressionDictionary) {
+        #[cfg(feature = "zstd_support")]
+        if let Some(compression_level) = self.dictionary_level {
+            self.encoder = EncoderType::Zstd(
+                Compressor::with_dictionary(compression_level, &dictionary.bytes)
+                    .expect("error creating Compressor with dictionary"),
+            );
+            self.dictionary_version = dictionary.version;
+        }
+    }
 
-        use super::compression_config::CompressionMode;
+    /// Version of the dictionary currently being compressed with, or 0 if none
+    pub fn dictionary_version(&self) -> u8 {
+        self.dictionary_version
+    }
 
-        pub struct Encoder {
-            result: Vec<u8>,
-            encoder: EncoderType,
+    /// Returns the dictionary trained while in `CompressionMode::Training`,
+    /// once enough samples have been recorded. Can only be taken once.
+    pub fn take_trained_dictionary(&mut self) -> Option<Vec<u8>> {
+        match &mut self.encoder {
+            #[cfg(feature = "zstd_support")]
+            EncoderType::DictionaryTrainer(trainer) => trainer.take_dictionary(),
+            _ => None,
         }
+    }
 
-        impl Encoder {
-            pub fn new(compression_mode: CompressionMode) -> Self {
-                let encoder = match compression_mode {
-                    CompressionMode::Training(sample_size) => {
-                        EncoderType::DictionaryTrainer(DictionaryTrainer::new(sample_size))
-                    }
-                    CompressionMode::Default(compression_level) => EncoderType::Compressor(
-                        Compressor::new(compression_level).expect("error creating Compressor"),
-                    ),
-                    CompressionMode::Dictionary(compression_level, dictionary) => EncoderType::Compressor(
-                        Compressor::with_dictionary(compression_level, &dictionary)
-                            .expect("error creating Compressor with dictionary"),
-                    ),
-                };
-
-                Self {
-                    result: Vec::new(),
-                    encoder,
-                }
+    /// Encodes a packet payload. The output always starts with a flag byte
+    /// describing whether the rest is compressed, and the payload is left
+    /// uncompressed whenever compression would not make it any smaller.
+    pub fn encode(&mut self, payload: &[u8]) -> &[u8] {
+        let compressed: Option<Vec<u8>> = match &mut self.encoder {
+            #[cfg(feature = "zstd_support")]
+            EncoderType::DictionaryTrainer(trainer) => {
+                trainer.record_bytes(payload);
+                None
             }
-
-            pub fn encode(&mut self, payload: &[u8]) -> &[u8] {
-                // TODO: only use compressed packet if the resulting size would be less!
-                match &mut self.encoder {
-                    EncoderType::DictionaryTrainer(trainer) => {
-                        trainer.record_bytes(payload);
-                        self.result = payload.to_vec();
-                        return &self.result;
-                    }
-                    EncoderType::Compressor(encoder) => {
-                        self.result = encoder.compress(payload).expect("encode error");
-                        return &self.result;
-                    }
-                }
+            #[cfg(feature = "zstd_support")]
+            EncoderType::Zstd(encoder) => Some(encoder.compress(payload).expect("encode error")),
+            #[cfg(feature = "lz4_support")]
+            EncoderType::Lz4 => Some(lz4_flex::block::compress_prepend_size(payload)),
+            EncoderType::Passthrough => None,
+        };
+
+        self.result.clear();
+        match compressed {
+            Some(compressed) if compressed.len() < payload.len() => {
+                self.result
+                    .push(COMPRESSED_FLAG | (self.dictionary_version << 1));
+                self.result.extend_from_slice(&compressed);
+            }
+            _ => {
+                self.result.push(UNCOMPRESSED_FLAG);
+                self.result.extend_from_slice(payload);
             }
         }
 
-        pub enum EncoderType {
-            Compressor(Compressor<'static>),
-            DictionaryTrainer(DictionaryTrainer),
-        }
+        &self.result
+    }
+}
+
+pub enum EncoderType {
+    #[cfg(feature = "zstd_support")]
+    Zstd(Compressor<'static>),
+    #[cfg(feature = "zstd_support")]
+    DictionaryTrainer(DictionaryTrainer),
+    #[cfg(feature = "lz4_support")]
+    Lz4,
+    /// Used when the backend for the configured CompressionMode is not
+    /// enabled, payloads are sent uncompressed
+    Passthrough,
+}
+
+#[cfg(feature = "zstd_support")]
+pub struct DictionaryTrainer {
+    sample_data: Vec<u8>,
+    sample_sizes: Vec<usize>,
+    next_alert_size: usize,
+    target_sample_size: usize,
+    training_complete: bool,
+    dictionary: Option<Vec<u8>>,
+}
 
-        pub struct DictionaryTrainer {
-            sample_data: Vec<u8>,
-            sample_sizes: Vec<usize>,
-            next_alert_size: usize,
-            target_sample_size: usize,
-            training_complete: bool,
+#[cfg(feature = "zstd_support")]
+impl DictionaryTrainer {
+    /// `target_sample_size` here describes the number of samples (packets) to
+    /// train on. Obviously, the more samples trained on, the better
+    /// theoretical compression.
+    pub fn new(target_sample_size: usize) -> Self {
+        Self {
+            target_sample_size,
+            sample_data: Vec::new(),
+            sample_sizes: Vec::new(),
+            next_alert_size: 0,
+            training_complete: false,
+            dictionary: None,
         }
+    }
 
-        impl DictionaryTrainer {
-            /// `target_sample_size` here describes the number of samples (packets) to
-            /// train on. Obviously, the more samples trained on, the better
-            /// theoretical compression.
-            pub fn new(target_sample_size: usize) -> Self {
-                Self {
-                    target_sample_size,
-                    sample_data: Vec::new(),
-                    sample_sizes: Vec::new(),
-                    next_alert_size: 0,
-                    training_complete: false,
-                }
-            }
+    pub fn record_bytes(&mut self, bytes: &[u8]) {
+        if self.training_complete {
+            return;
+        }
 
-            pub fn record_bytes(&mut self, bytes: &[u8]) {
-                if self.training_complete {
-                    return;
-                }
+        self.sample_data.extend_from_slice(bytes);
+        self.sample_sizes.push(bytes.len());
 
-                self.sample_data.extend_from_slice(bytes);
-                self.sample_sizes.push(bytes.len());
+        let current_sample_size = self.sample_sizes.len();
 
-                let current_sample_size = self.sample_sizes.len();
+        if current_sample_size >= self.next_alert_size {
+            let percent =
+                ((self.next_alert_size as f32) / (self.target_sample_size as f32)) * 100.0;
+            info!("Dictionary training: {}% complete", percent);
 
-                if current_sample_size >= self.next_alert_size {
-                    let percent =
-                        ((self.next_alert_size as f32) / (self.target_sample_size as f32)) * 100.0;
-                    info!("Dictionary training: {}% complete", percent);
+            self.next_alert_size += self.target_sample_size / 20;
+        }
 
-                    self.next_alert_size += self.target_sample_size / 20;
-                }
+        if current_sample_size >= self.target_sample_size {
+            info!("Dictionary training complete!");
+            info!(
+                "Samples: {} ({} KB)",
+                self.sample_sizes.len(),
+                self.sample_data.len()
+            );
+            info!("Dictionary processing sample data...");
+
+            // We have enough sample data to train the dictionary!
+            let target_dict_size = self.sample_data.len() / 100;
+            let dictionary =
+                from_continuous(&self.sample_data, &self.sample_sizes, target_dict_size)
+                    .expect("Error while training dictionary");
+
+            info!("Dictionary ready! ({} bytes)", dictionary.len());
+
+            self.dictionary = Some(dictionary);
+            self.training_complete = true;
+        }
+    }
 
-                if current_sample_size >= self.target_sample_size {
-                    info!("Dictionary training complete!");
-                    info!(
-                        "Samples: {} ({} KB)",
-                        self.sample_sizes.len(),
-                        self.sample_data.len()
-                    );
-                    info!("Dictionary processing sample data...");
+    pub fn take_dictionary(&mut self) -> Option<Vec<u8>> {
+        self.dictionary.take()
+    }
+}
 
-                    // We have enough sample data to train the dictionary!
-                    let target_dict_size = self.sample_data.len() / 100;
-                    let dictionary =
-                        from_continuous(&self.sample_data, &self.sample_sizes, target_dict_size)
-                            .expect("Error while training dictionary");
+#[cfg(test)]
+mod tests {
+    use super::{Encoder, UNCOMPRESSED_FLAG};
+    use crate::{CompressionMode, Decoder};
 
-                    // Now need to ... write it to a file I guess
-                    fs::write("dictionary.txt", dictionary)
-                        .expect("Error while writing dictionary to file");
+    #[test]
+    fn small_payload_is_sent_uncompressed() {
+        let mut encoder = Encoder::new(CompressionMode::Lz4);
+        let mut decoder = Decoder::new(CompressionMode::Lz4);
 
-                    info!("Dictionary written to `dictionary.txt`!");
+        let payload = [7, 3, 9];
+        let encoded = encoder.encode(&payload).to_vec();
+        assert_eq!(encoded[0], UNCOMPRESSED_FLAG);
+        assert_eq!(decoder.decode(&encoded), &payload);
+    }
 
-                    self.training_complete = true;
-                }
-            }
-        }
+    #[cfg(feature = "lz4_support")]
+    #[test]
+    fn lz4_round_trip() {
+        let mut encoder = Encoder::new(CompressionMode::Lz4);
+        let mut decoder = Decoder::new(CompressionMode::Lz4);
+
+        let payload = [42; 256];
+        let encoded = encoder.encode(&payload).to_vec();
+        assert_eq!(encoded[0], super::COMPRESSED_FLAG);
+        assert!(encoded.len() < payload.len());
+        assert_eq!(decoder.decode(&encoded), &payload);
     }
-    else
-    {
-        use super::compression_config::CompressionMode;
 
-        pub struct Encoder {
-            result: Vec<u8>
-        }
+    #[cfg(feature = "zstd_support")]
+    #[test]
+    fn oversized_zstd_frames_are_rejected() {
+        let mut decoder = Decoder::new(CompressionMode::Default(0));
 
-        impl Encoder {
-            pub fn new(_: CompressionMode) -> Self {
-                Self {
-                    result: Vec::new(),
-                }
-            }
+        let oversized = [0; crate::constants::MAX_MTU_SIZE_BYTES as usize + 1];
+        let mut encoded = vec![super::COMPRESSED_FLAG];
+        encoded.extend(zstd::bulk::compress(&oversized, 0).unwrap());
+        assert!(decoder.decode(&encoded).is_empty());
 
-            pub fn encode(&mut self, payload: &[u8]) -> &[u8] {
-                self.result = payload.to_vec();
-                &self.result
-            }
-        }
+        let payload = [42; 256];
+        let mut encoded = vec![super::COMPRESSED_FLAG];
+        encoded.extend(zstd::bulk::compress(&payload, 0).unwrap());
+        assert_eq!(decoder.decode(&encoded), &payload);
     }
 }
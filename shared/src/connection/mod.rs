@@ -2,12 +2,17 @@ pub mod ack_manager;
 pub mod bandwidth_monitor;
 pub mod base_connection;
 pub mod compression_config;
+pub mod compression_dictionary;
 pub mod connection_config;
 pub mod decoder;
 pub mod encoder;
+pub mod mtu_config;
+pub mod mtu_manager;
 pub mod packet_notifiable;
 pub mod packet_type;
 pub mod ping_config;
 pub mod ping_manager;
+pub mod reject_reason;
 pub mod sequence_buffer;
+pub mod server_info;
 pub mod standard_header;
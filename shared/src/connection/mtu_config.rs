@@ -0,0 +1,42 @@
+use std::{default::Default, time::Duration};
+
+use crate::constants::MTU_SIZE_BYTES;
+
+/// Contains Config properties which control the maximum size of packets
+#[derive(Clone, Debug)]
+pub struct MtuConfig {
+    /// The largest payload, in bytes, which packets are written up to. This
+    /// should be a size which can be delivered to every remote host
+    pub payload_size_bytes: u16,
+    /// If set, each connection probes for a larger payload size which still
+    /// makes it to the remote host, up to this many bytes
+    pub probe_max_size_bytes: Option<u16>,
+    /// The duration to wait for a probe to be acknowledged before sending
+    /// another one
+    pub probe_interval: Duration,
+}
+
+impl MtuConfig {
+    /// Creates a new MtuConfig
+    pub fn new(
+        payload_size_bytes: u16,
+        probe_max_size_bytes: Option<u16>,
+        probe_interval: Duration,
+    ) -> Self {
+        MtuConfig {
+            payload_size_bytes,
+            probe_max_size_bytes,
+            probe_interval,
+        }
+    }
+}
+
+impl Default for MtuConfig {
+    fn default() -> Self {
+        Self {
+            payload_size_bytes: MTU_SIZE_BYTES,
+            probe_max_size_bytes: None,
+            probe_interval: Duration::from_secs(1),
+        }
+    }
+}
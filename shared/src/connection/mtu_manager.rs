@@ -0,0 +1,168 @@
+use naia_serde::{BitReader, BitWrite, BitWriter, Serde, SerdeErr};
+
+use crate::{backends::Timer, constants::MAX_MTU_SIZE_BYTES};
+
+use super::mtu_config::MtuConfig;
+
+/// Number of unacknowledged probes of the same size before giving up on it
+const MAX_PROBE_ATTEMPTS: u8 = 3;
+
+/// Keeps track of the largest payload size which is known to reach the remote
+/// host, and optionally probes for a larger one by binary search
+pub struct MtuManager {
+    probe_timer: Timer,
+    payload_size: u16,
+    // largest size which has not been ruled out yet
+    search_max: u16,
+    probe_size: Option<u16>,
+    probe_attempts: u8,
+}
+
+impl MtuManager {
+    pub fn new(mtu_config: &MtuConfig) -> Self {
+        let payload_size = mtu_config.payload_size_bytes;
+        let search_max = mtu_config
+            .probe_max_size_bytes
+            .unwrap_or(payload_size)
+            .max(payload_size);
+
+        if search_max > MAX_MTU_SIZE_BYTES {
+            panic!(
+                "MTU size can't be configured above {} bytes",
+                MAX_MTU_SIZE_BYTES
+            );
+        }
+
+        let mut probe_timer = Timer::new(mtu_config.probe_interval);
+        probe_timer.ring_manual();
+
+        MtuManager {
+            probe_timer,
+            payload_size,
+            search_max,
+            probe_size: None,
+            probe_attempts: 0,
+        }
+    }
+
+    /// Largest payload size, in bytes, which outgoing packets can be written up
+    /// to
+    pub fn payload_size_bytes(&self) -> u16 {
+        self.payload_size
+    }
+
+    /// Returns a BitWriter for an outgoing packet, sized to the current
+    /// payload size
+    pub fn packet_writer(&self) -> BitWriter {
+        BitWriter::with_max_size(self.payload_size as usize)
+    }
+
+    /// Returns the size of the next probe to send, if it is time to send one
+    pub fn next_probe_size(&mut self) -> Option<u16> {
+        if !self.probe_timer.ringing() {
+            return None;
+        }
+        self.probe_timer.reset();
+
+        if let Some(probe_size) = self.probe_size {
+            // last probe was never acknowledged
+            self.probe_attempts += 1;
+            if self.probe_attempts >= MAX_PROBE_ATTEMPTS {
+                self.search_max = probe_size - 1;
+                self.probe_size = None;
+                self.probe_attempts = 0;
+            }
+        }
+
+        if self.search_max <= self.payload_size {
+            // search is done
+            self.probe_size = None;
+            return None;
+        }
+
+        let probe_size = self.probe_size.unwrap_or_else(|| {
+            self.payload_size + (self.search_max - self.payload_size).div_ceil(2)
+        });
+        self.probe_size = Some(probe_size);
+        Some(probe_size)
+    }
+
+    /// Writes the body of a probe, padding the packet out to `probe_size`
+    /// bytes. The padding is pseudo-random, so that compression can't shrink
+    /// the packet.
+    pub fn write_probe(probe_size: u16, writer: &mut BitWriter) {
+        probe_size.ser(writer);
+
+        let mut seed: u32 = 0x9E37_79B9 ^ (probe_size as u32);
+        while writer.bit_count() + 8 <= probe_size * 8 {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            writer.write_byte(seed as u8);
+        }
+    }
+
+    /// Reads an incoming probe, returning its size to acknowledge
+    pub fn read_probe(reader: &mut BitReader) -> Result<u16, SerdeErr> {
+        u16::de(reader)
+    }
+
+    /// Process an incoming probe acknowledgement
+    pub fn process_probe_ack(&mut self, reader: &mut BitReader) {
+        if let Ok(acked_size) = u16::de(reader) {
+            if self.probe_size == Some(acked_size) {
+                self.payload_size = acked_size;
+                self.probe_size = None;
+                self.probe_attempts = 0;
+
+                // continue the search right away
+                self.probe_timer.ring_manual();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use naia_serde::{BitReader, BitWrite, BitWriter, Serde};
+
+    use super::MtuManager;
+    use crate::MtuConfig;
+
+    fn ack(manager: &mut MtuManager, size: u16) {
+        let mut writer = BitWriter::new();
+        size.ser(&mut writer);
+        let (length, buffer) = writer.flush();
+        manager.process_probe_ack(&mut BitReader::new(&buffer[..length]));
+    }
+
+    #[test]
+    fn probes_up_to_largest_acknowledged_size() {
+        let config = MtuConfig::new(500, Some(1000), Duration::ZERO);
+        let mut manager = MtuManager::new(&config);
+
+        // pretend the path drops anything above 800 bytes
+        while let Some(probe_size) = manager.next_probe_size() {
+            if probe_size <= 800 {
+                ack(&mut manager, probe_size);
+            }
+        }
+
+        assert_eq!(manager.payload_size_bytes(), 800);
+        assert_eq!(manager.packet_writer().max_bits(), 800 * 8);
+    }
+
+    #[test]
+    fn probe_is_padded_to_size() {
+        let mut writer = BitWriter::with_max_size(600);
+        MtuManager::write_probe(600, &mut writer);
+        assert_eq!(writer.bit_count(), 600 * 8);
+
+        let (length, buffer) = writer.flush();
+        assert_eq!(length, 600);
+        let mut reader = BitReader::new(&buffer[..length]);
+        assert_eq!(MtuManager::read_probe(&mut reader).unwrap(), 600);
+    }
+}
@@ -28,6 +28,23 @@ pub enum PacketType {
     Pong,
     // Used to request a graceful Client disconnect from the Server
     Disconnect,
+    // Sent by the Client to request the next chunk of a compression
+    // dictionary, or to confirm that the dictionary has been received
+    ClientDictionaryRequest,
+    // A chunk of a compression dictionary, sent by the Server during the
+    // handshake or whenever the dictionary is replaced
+    ServerDictionaryChunk,
+    // A packet padded out to a given size, used to discover whether packets of
+    // that size make it to the remote host. Must be responded to with an
+    // MtuProbeAck message
+    MtuProbe,
+    // Acknowledges that an MtuProbe message of a given size was received
+    MtuProbeAck,
+    // An unauthenticated request for information about the Server, which
+    // does not establish a connection
+    ClientInfoRequest,
+    // The Server's response to a ClientInfoRequest
+    ServerInfoResponse,
 }
 
 // Most packets should be Data, so lets compress this a bit more.
@@ -52,6 +69,12 @@ impl crate::serde::Serde for PacketType {
             PacketType::Ping => 6,
             PacketType::Pong => 7,
             PacketType::Disconnect => 8,
+            PacketType::ClientDictionaryRequest => 9,
+            PacketType::ServerDictionaryChunk => 10,
+            PacketType::MtuProbe => 11,
+            PacketType::MtuProbeAck => 12,
+            PacketType::ClientInfoRequest => 13,
+            PacketType::ServerInfoResponse => 14,
         };
 
         UnsignedInteger::<4>::new(index).ser(writer);
@@ -73,6 +96,12 @@ impl crate::serde::Serde for PacketType {
             6 => Ok(PacketType::Ping),
             7 => Ok(PacketType::Pong),
             8 => Ok(PacketType::Disconnect),
+            9 => Ok(PacketType::ClientDictionaryRequest),
+            10 => Ok(PacketType::ServerDictionaryChunk),
+            11 => Ok(PacketType::MtuProbe),
+            12 => Ok(PacketType::MtuProbeAck),
+            13 => Ok(PacketType::ClientInfoRequest),
+            14 => Ok(PacketType::ServerInfoResponse),
             _ => panic!("shouldn't happen, caught above"),
         }
     }
@@ -0,0 +1,35 @@
+use naia_serde::{BitReader, BitWrite, Serde, SerdeErr, UnsignedInteger};
+
+/// The reason given by the Server when it rejects a Client's attempt to
+/// connect
+#[derive(Copy, Debug, Clone, Eq, PartialEq)]
+pub enum RejectReason {
+    /// The Client's auth message was rejected by the Server application
+    Auth,
+    /// The Client uses a different version of the naia protocol than the
+    /// Server
+    VersionMismatch,
+    /// The Server has reached its maximum number of Users
+    ServerFull,
+}
+
+impl Serde for RejectReason {
+    fn ser(&self, writer: &mut dyn BitWrite) {
+        let index = match self {
+            RejectReason::Auth => 0,
+            RejectReason::VersionMismatch => 1,
+            RejectReason::ServerFull => 2,
+        };
+
+        UnsignedInteger::<3>::new(index).ser(writer);
+    }
+
+    fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        match UnsignedInteger::<3>::de(reader)?.get() {
+            0 => Ok(RejectReason::Auth),
+            1 => Ok(RejectReason::VersionMismatch),
+            2 => Ok(RejectReason::ServerFull),
+            _ => Err(SerdeErr),
+        }
+    }
+}
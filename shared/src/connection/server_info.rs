@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use naia_serde::{BitCounter, BitReader, BitWrite, BitWriter, Serde, SerdeErr};
+use naia_socket_shared::Random;
+
+use crate::{
+    constants::MTU_SIZE_BYTES, protocol::entity_property::FakeEntityConverter, Protocolize,
+};
+
+use super::{packet_type::PacketType, standard_header::StandardHeader};
+
+/// Size of a `ClientInfoRequest` packet. Requests are padded to be larger than
+/// any response, so that answering them can't be used to amplify traffic
+/// towards a spoofed address. One byte is left for the compression flag, so
+/// that requests still fit within `MTU_SIZE_BYTES`
+pub const SERVER_INFO_REQUEST_BYTES: usize = MTU_SIZE_BYTES as usize - 1;
+
+/// Largest size of a `ServerInfoResponse` packet, which is one byte less than
+/// a request, leaving room for the compression flag
+const SERVER_INFO_RESPONSE_MAX_BITS: u16 = (SERVER_INFO_REQUEST_BYTES as u16 - 1) * 8;
+
+/// Information about a Server, which can be queried without establishing a
+/// connection
+#[derive(Clone)]
+pub struct ServerInfo<P: Protocolize> {
+    /// The number of Users currently connected to the Server
+    pub user_count: u32,
+    /// The maximum number of Users which can connect to the Server, if limited
+    pub max_users: Option<u32>,
+    /// The duration between Server ticks, if the Server has a tick interval
+    pub tick_interval: Option<Duration>,
+    /// Fingerprint of the Server's Protocol, see
+    /// `Protocolize::protocol_fingerprint()`
+    pub protocol_fingerprint: u64,
+    /// Application-defined info, if the Server has set any
+    pub info: Option<P>,
+}
+
+impl<P: Protocolize> ServerInfo<P> {
+    /// Returns whether the Server was built with the same Protocol as this
+    /// host, and so can be connected to
+    pub fn is_compatible(&self) -> bool {
+        self.protocol_fingerprint == P::protocol_fingerprint()
+    }
+
+    /// Writes a `ClientInfoRequest` packet for the query with the given id,
+    /// padded with random bytes to `SERVER_INFO_REQUEST_BYTES`, so that the
+    /// padding can't be compressed away
+    pub fn write_request(query_id: u64) -> BitWriter {
+        let mut writer = BitWriter::with_max_size(SERVER_INFO_REQUEST_BYTES);
+        StandardHeader::new(PacketType::ClientInfoRequest, 0, 0, 0).ser(&mut writer);
+        query_id.ser(&mut writer);
+
+        while (writer.bit_count() as usize) < SERVER_INFO_REQUEST_BYTES * 8 {
+            writer.write_bit(Random::gen_bool());
+        }
+
+        writer
+    }
+
+    /// Writes a `ServerInfoResponse` packet answering the query with the
+    /// given id
+    pub fn write_response(&self, query_id: u64) -> BitWriter {
+        let mut writer = BitWriter::new();
+        self.write_response_inner(query_id, &mut writer);
+        writer
+    }
+
+    /// Whether a `ServerInfoResponse` packet with this info is no larger than
+    /// the request it answers
+    pub fn fits_in_response(&self) -> bool {
+        let mut counter = BitCounter::new();
+        self.write_response_inner(0, &mut counter);
+        counter.bit_count() <= SERVER_INFO_RESPONSE_MAX_BITS
+    }
+
+    fn write_response_inner(&self, query_id: u64, writer: &mut dyn BitWrite) {
+        StandardHeader::new(PacketType::ServerInfoResponse, 0, 0, 0).ser(writer);
+
+        query_id.ser(writer);
+        self.user_count.ser(writer);
+        self.max_users.ser(writer);
+        self.tick_interval
+            .map(|duration| duration.as_millis() as u32)
+            .ser(writer);
+        self.protocol_fingerprint.ser(writer);
+
+        // write info payload if there is one
+        if let Some(info) = &self.info {
+            true.ser(writer);
+            info.write(writer, &FakeEntityConverter);
+        } else {
+            false.ser(writer);
+        }
+    }
+
+    /// Reads the body of a `ServerInfoResponse` packet, returning the id of
+    /// the query it answers along with the info
+    pub fn read_response(reader: &mut BitReader) -> Result<(u64, Self), SerdeErr> {
+        let query_id = u64::de(reader)?;
+        let user_count = u32::de(reader)?;
+        let max_users = Option::<u32>::de(reader)?;
+        let tick_interval =
+            Option::<u32>::de(reader)?.map(|millis| Duration::from_millis(millis.into()));
+        let protocol_fingerprint = u64::de(reader)?;
+        let info = if bool::de(reader)? {
+            Some(P::read(reader, &FakeEntityConverter)?)
+        } else {
+            None
+        };
+
+        Ok((
+            query_id,
+            Self {
+                user_count,
+                max_users,
+                tick_interval,
+                protocol_fingerprint,
+                info,
+            },
+        ))
+    }
+}
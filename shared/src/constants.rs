@@ -1,7 +1,17 @@
-/// The maximum of bytes that can be used for the payload of a given packet.
+/// The default maximum of bytes that can be used for the payload of a given
+/// packet, see `MtuConfig` to change it.
 /// (See #38 of http://ithare.com/64-network-dos-and-donts-for-game-engines-part-v-udp/)
 pub const MTU_SIZE_BYTES: u16 = 508;
 pub const MTU_SIZE_BITS: u16 = MTU_SIZE_BYTES * 8;
 
+/// The largest payload size which can be configured or discovered by probing.
+/// Leaves room for the compression flag byte, and stays below the 1472 bytes
+/// a native Client socket is able to receive.
+pub const MAX_MTU_SIZE_BYTES: u16 = 1400;
+
+/// Version of the naia protocol, sent by the Client during the handshake. A
+/// Server will reject Clients with a different version.
+pub const PROTOCOL_VERSION: u16 = 1;
+
 // Number of messages to keep in tick buffer
 pub const MESSAGE_HISTORY_SIZE: u16 = 64;
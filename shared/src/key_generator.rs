@@ -1,39 +1,88 @@
-use std::marker::PhantomData;
+use std::{collections::VecDeque, marker::PhantomData, time::Duration};
 
-use std::collections::VecDeque;
+use crate::Instant;
 
-/// Simple implementation of a store that manages a recycling pool of u16 keys
-pub struct KeyGenerator<K: From<u16> + Into<u16> + Copy> {
-    recycled_local_keys: VecDeque<u16>,
-    next_new_local_key: u16,
+/// Simple implementation of a store that manages a recycling pool of u32 keys
+pub struct KeyGenerator<K: From<u32> + Into<u32> + Copy> {
+    recycled_local_keys: VecDeque<(u32, Instant)>,
+    next_new_local_key: Option<u32>,
+    recycle_timeout: Duration,
     phantom: PhantomData<K>,
 }
 
-impl<K: From<u16> + Into<u16> + Copy> Default for KeyGenerator<K> {
+impl<K: From<u32> + Into<u32> + Copy> Default for KeyGenerator<K> {
     fn default() -> Self {
+        Self::new(Duration::ZERO)
+    }
+}
+
+impl<K: From<u32> + Into<u32> + Copy> KeyGenerator<K> {
+    /// Create a new KeyGenerator, which will not reuse a recycled key until
+    /// the given amount of time has passed since it was recycled
+    pub fn new(recycle_timeout: Duration) -> Self {
         Self {
             recycled_local_keys: VecDeque::default(),
-            next_new_local_key: 0,
+            next_new_local_key: Some(0),
+            recycle_timeout,
             phantom: PhantomData,
         }
     }
-}
 
-impl<K: From<u16> + Into<u16> + Copy> KeyGenerator<K> {
-    /// Get a new, unused key
+    /// Get a new, unused key. Panics if every key is in use, or was recycled
+    /// too recently to be reused
     pub fn generate(&mut self) -> K {
-        if let Some(local_key) = self.recycled_local_keys.pop_front() {
-            return K::from(local_key);
+        // recycled keys are in the order they were recycled, so only the
+        // oldest may be ready for reuse
+        if let Some((_, recycled_at)) = self.recycled_local_keys.front() {
+            if recycled_at.elapsed() >= self.recycle_timeout {
+                let (local_key, _) = self.recycled_local_keys.pop_front().unwrap();
+                return K::from(local_key);
+            }
         }
 
-        let output = self.next_new_local_key;
-        self.next_new_local_key += 1;
+        let output = self.next_new_local_key.expect(
+            "KeyGenerator has run out of keys, every key is in use or waiting to be reused",
+        );
+        self.next_new_local_key = output.checked_add(1);
         K::from(output)
     }
 
     /// Recycle a used key, freeing it up
     pub fn recycle_key(&mut self, local_key: &K) {
-        let local_key_u16: u16 = Into::<u16>::into(*local_key);
-        self.recycled_local_keys.push_back(local_key_u16);
+        let local_key_u32: u32 = Into::<u32>::into(*local_key);
+        self.recycled_local_keys
+            .push_back((local_key_u32, Instant::now()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::KeyGenerator;
+
+    #[test]
+    fn recycled_keys_wait_for_timeout() {
+        let mut generator = KeyGenerator::<u32>::new(Duration::from_secs(60));
+
+        let key = generator.generate();
+        generator.recycle_key(&key);
+        assert_ne!(generator.generate(), key);
+
+        let mut generator = KeyGenerator::<u32>::default();
+
+        let key = generator.generate();
+        generator.recycle_key(&key);
+        assert_eq!(generator.generate(), key);
+    }
+
+    #[test]
+    #[should_panic]
+    fn exhaustion_is_detected() {
+        let mut generator = KeyGenerator::<u32>::default();
+        generator.next_new_local_key = Some(u32::MAX);
+
+        assert_eq!(generator.generate(), u32::MAX);
+        generator.generate();
     }
 }
@@ -29,6 +29,7 @@ mod backends;
 mod connection;
 mod messages;
 mod protocol;
+mod world;
 
 mod bigmap;
 mod constants;
@@ -44,13 +45,20 @@ pub use connection::{
     bandwidth_monitor::BandwidthMonitor,
     base_connection::BaseConnection,
     compression_config::{CompressionConfig, CompressionMode},
+    compression_dictionary::{
+        CompressionDictionary, DictionaryReceiver, DictionaryVersion, DICTIONARY_REQUEST_BYTES,
+    },
     connection_config::ConnectionConfig,
     decoder::Decoder,
     encoder::Encoder,
+    mtu_config::MtuConfig,
+    mtu_manager::MtuManager,
     packet_notifiable::PacketNotifiable,
     packet_type::PacketType,
     ping_config::PingConfig,
     ping_manager::{PingIndex, PingManager},
+    reject_reason::RejectReason,
+    server_info::ServerInfo,
     standard_header::StandardHeader,
 };
 pub use messages::{
@@ -76,9 +84,13 @@ pub use protocol::{
         EntityConverter, EntityHandleConverter, EntityProperty, FakeEntityConverter,
         NetEntityConverter, NetEntityHandleConverter,
     },
-    net_entity::NetEntity,
+    nested_property::NestedProperty,
+    net_entity::{NetEntity, OwnedNetEntity},
     property::Property,
+    property_map::PropertyMap,
     property_mutate::{PropertyMutate, PropertyMutator},
+    property_serde::PropertySerde,
+    property_vec::PropertyVec,
     protocol_io::ProtocolIo,
     protocolize::{ProtocolInserter, ProtocolKindType, Protocolize},
     replica_ref::{
@@ -87,10 +99,23 @@ pub use protocol::{
         ReplicaRefWrapper,
     },
     replicate::{Replicate, ReplicateSafe},
+    replicate_nested::ReplicateNested,
+};
+
+pub use world::{
+    authority_channel::{AuthorityAction, AuthorityChannel},
+    delta_baselines::{ReceivedBaselines, SentBaselines},
+    entity_action_event::EntityActionEvent,
+    global_diff_handler::GlobalDiffHandler,
+    host_world_manager::{ActionId, HostWorldManager},
+    mut_channel::{MutChannel, MutReceiver, MutReceiverBuilder, MutSender},
+    user_diff_handler::UserDiffHandler,
 };
 
 pub use bigmap::{BigMap, BigMapKey};
-pub use constants::{MESSAGE_HISTORY_SIZE, MTU_SIZE_BITS, MTU_SIZE_BYTES};
+pub use constants::{
+    MAX_MTU_SIZE_BYTES, MESSAGE_HISTORY_SIZE, MTU_SIZE_BITS, MTU_SIZE_BYTES, PROTOCOL_VERSION,
+};
 pub use key_generator::KeyGenerator;
 pub use shared_config::SharedConfig;
 pub use types::{HostType, MessageId, PacketIndex, ShortMessageId, Tick};
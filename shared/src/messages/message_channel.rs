@@ -5,6 +5,7 @@ use crate::types::MessageId;
 
 pub trait ChannelSender<P>: Send + Sync {
     fn send_message(&mut self, message: P);
+    fn send_message_with_expiry(&mut self, message: P, expires_at: Instant);
     fn collect_messages(&mut self, now: &Instant, rtt_millis: &f32);
     fn has_messages(&self) -> bool;
     fn write_messages(
@@ -13,6 +14,7 @@ pub trait ChannelSender<P>: Send + Sync {
         bit_writer: &mut BitWriter,
     ) -> Option<Vec<MessageId>>;
     fn notify_message_delivered(&mut self, message_id: &MessageId);
+    fn take_expired_messages(&mut self) -> Vec<P>;
 }
 
 pub trait ChannelReceiver<P>: Send + Sync {
@@ -126,6 +126,41 @@ impl<P: Protocolize, C: ChannelIndex> MessageManager<P, C> {
         }
     }
 
+    /// Queues an Message to be transmitted to the remote host, which will be
+    /// dropped from the resend queue if it has not been delivered by
+    /// `expires_at`
+    pub fn send_message_with_expiry(&mut self, channel_index: C, message: P, expires_at: Instant) {
+        if let Some(channel) = self.channel_senders.get_mut(&channel_index) {
+            channel.send_message_with_expiry(message, expires_at);
+        }
+    }
+
+    /// Queues an Message to be transmitted to the remote host, with an optional
+    /// expiry
+    pub fn send_message_with_expiry_opt(
+        &mut self,
+        channel_index: C,
+        message: P,
+        expires_at: Option<Instant>,
+    ) {
+        match expires_at {
+            Some(expires_at) => self.send_message_with_expiry(channel_index, message, expires_at),
+            None => self.send_message(channel_index, message),
+        }
+    }
+
+    /// Returns all Messages which have expired before being delivered to the
+    /// remote host
+    pub fn take_expired_messages(&mut self) -> Vec<(C, P)> {
+        let mut output = Vec::new();
+        for (channel_index, channel) in &mut self.channel_senders {
+            for message in channel.take_expired_messages() {
+                output.push((channel_index.clone(), message));
+            }
+        }
+        output
+    }
+
     pub fn collect_outgoing_messages(&mut self, now: &Instant, rtt_millis: &f32) {
         for channel in self.channel_senders.values_mut() {
             channel.collect_messages(now, rtt_millis);
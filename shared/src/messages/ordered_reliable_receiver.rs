@@ -13,7 +13,8 @@ use super::{
 
 pub struct OrderedReliableReceiver<P> {
     oldest_waiting_message_id: MessageId,
-    waiting_incoming_messages: VecDeque<(MessageId, Option<P>)>,
+    // (id, whether the message has been received, payload if it hasn't expired)
+    waiting_incoming_messages: VecDeque<(MessageId, bool, Option<P>)>,
 }
 
 impl<P> Default for OrderedReliableReceiver<P> {
@@ -26,7 +27,10 @@ impl<P> Default for OrderedReliableReceiver<P> {
 }
 
 impl<P> OrderedReliableReceiver<P> {
-    pub fn buffer_message(&mut self, message_id: MessageId, message: P) {
+    /// Buffers an incoming message. A `None` message is one which expired
+    /// before it could be delivered, and only serves to let later messages
+    /// through.
+    pub fn buffer_message(&mut self, message_id: MessageId, message: Option<P>) {
         // moving from oldest incoming message to newest
         // compare existing slots and see if the message_id has been instantiated
         // already if it has, put the message into the slot
@@ -44,16 +48,18 @@ impl<P> OrderedReliableReceiver<P> {
 
         loop {
             if index < self.waiting_incoming_messages.len() {
-                if let Some((old_message_id, _)) = self.waiting_incoming_messages.get(index) {
+                if let Some((old_message_id, _, _)) = self.waiting_incoming_messages.get(index) {
                     if *old_message_id == message_id {
                         found = true;
                     }
                 }
 
                 if found {
-                    let (_, old_message) = self.waiting_incoming_messages.get_mut(index).unwrap();
-                    if old_message.is_none() {
-                        *old_message = Some(message);
+                    let (_, received, old_message) =
+                        self.waiting_incoming_messages.get_mut(index).unwrap();
+                    if !*received {
+                        *received = true;
+                        *old_message = message;
                     } else {
                         // already received this message
                     }
@@ -64,11 +70,11 @@ impl<P> OrderedReliableReceiver<P> {
 
                 if next_message_id == message_id {
                     self.waiting_incoming_messages
-                        .push_back((next_message_id, Some(message)));
+                        .push_back((next_message_id, true, message));
                     break;
                 } else {
                     self.waiting_incoming_messages
-                        .push_back((next_message_id, None));
+                        .push_back((next_message_id, false, None));
                 }
             }
 
@@ -80,13 +86,14 @@ impl<P> OrderedReliableReceiver<P> {
         let mut output = Vec::new();
         loop {
             let mut has_message = false;
-            if let Some((_, Some(_))) = self.waiting_incoming_messages.front() {
+            if let Some((_, true, _)) = self.waiting_incoming_messages.front() {
                 has_message = true;
             }
             if has_message {
-                let (_, message_opt) = self.waiting_incoming_messages.pop_front().unwrap();
-                let message = message_opt.unwrap();
-                output.push(message);
+                let (_, _, message_opt) = self.waiting_incoming_messages.pop_front().unwrap();
+                if let Some(message) = message_opt {
+                    output.push(message);
+                }
                 self.oldest_waiting_message_id = self.oldest_waiting_message_id.wrapping_add(1);
             } else {
                 break;
@@ -113,3 +120,20 @@ impl<P: Send + Sync> ChannelReceiver<P> for OrderedReliableReceiver<P> {
         self.receive_messages()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::OrderedReliableReceiver;
+
+    #[test]
+    fn expired_message_does_not_stall_later_messages() {
+        let mut receiver = OrderedReliableReceiver::<u8>::default();
+
+        receiver.buffer_message(1, Some(11));
+        receiver.buffer_message(2, Some(12));
+        assert!(receiver.receive_messages().is_empty());
+
+        receiver.buffer_message(0, None);
+        assert_eq!(receiver.receive_messages(), vec![11, 12]);
+    }
+}
@@ -15,7 +15,7 @@ impl<P> ReliableReceiver<P> {
     pub fn read_incoming_messages(
         channel_reader: &dyn ChannelReader<P>,
         reader: &mut BitReader,
-    ) -> Result<Vec<(MessageId, P)>, SerdeErr> {
+    ) -> Result<Vec<(MessageId, Option<P>)>, SerdeErr> {
         let message_count = message_list_header::read(reader)?;
 
         let mut last_read_id: Option<MessageId> = None;
@@ -33,7 +33,7 @@ impl<P> ReliableReceiver<P> {
         channel_reader: &dyn ChannelReader<P>,
         reader: &mut BitReader,
         last_read_id: &Option<MessageId>,
-    ) -> Result<(MessageId, P), SerdeErr> {
+    ) -> Result<(MessageId, Option<P>), SerdeErr> {
         let message_id: MessageId = if let Some(last_id) = last_read_id {
             let id_diff = UnsignedVariableInteger::<3>::de(reader)?.get() as MessageId;
             last_id.wrapping_add(id_diff)
@@ -42,8 +42,13 @@ impl<P> ReliableReceiver<P> {
             MessageId::de(reader)?
         };
 
-        // read payload
-        let new_message = channel_reader.read(reader)?;
+        // read payload, if the message has not expired on the sender's side
+        let has_payload = bool::de(reader)?;
+        let new_message = if has_payload {
+            Some(channel_reader.read(reader)?)
+        } else {
+            None
+        };
 
         Ok((message_id, new_message))
     }
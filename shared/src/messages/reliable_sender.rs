@@ -4,20 +4,32 @@ use naia_serde::{BitCounter, BitWrite, BitWriter, Serde, UnsignedVariableInteger
 
 use naia_socket_shared::Instant;
 
-use crate::{constants::MTU_SIZE_BITS, types::MessageId, wrapping_diff};
+use crate::{types::MessageId, wrapping_diff};
 
 use super::{
     message_channel::{ChannelSender, ChannelWriter},
     message_list_header,
 };
 
+// SendingMessage
+
+struct SendingMessage<P> {
+    message_id: MessageId,
+    last_sent: Option<Instant>,
+    expires_at: Option<Instant>,
+    // None once the message has expired, but is still being sent as an empty
+    // placeholder so that the receiver can move past its MessageId
+    message: Option<P>,
+}
+
 // Sender
 
 pub struct ReliableSender<P: Send + Sync> {
     rtt_resend_factor: f32,
-    sending_messages: VecDeque<Option<(MessageId, Option<Instant>, P)>>,
+    sending_messages: VecDeque<Option<SendingMessage<P>>>,
     next_send_message_id: MessageId,
-    next_send_messages: VecDeque<(MessageId, P)>,
+    next_send_messages: VecDeque<(MessageId, Option<P>)>,
+    expired_messages: Vec<P>,
 }
 
 impl<P: Send + Sync> ReliableSender<P> {
@@ -27,16 +39,27 @@ impl<P: Send + Sync> ReliableSender<P> {
             next_send_message_id: 0,
             sending_messages: VecDeque::new(),
             next_send_messages: VecDeque::new(),
+            expired_messages: Vec::new(),
         }
     }
 
+    fn push_message(&mut self, message: P, expires_at: Option<Instant>) {
+        self.sending_messages.push_back(Some(SendingMessage {
+            message_id: self.next_send_message_id,
+            last_sent: None,
+            expires_at,
+            message: Some(message),
+        }));
+        self.next_send_message_id = self.next_send_message_id.wrapping_add(1);
+    }
+
     fn write_outgoing_message(
         &self,
         channel_writer: &dyn ChannelWriter<P>,
         bit_writer: &mut dyn BitWrite,
         last_written_id: &Option<MessageId>,
         message_id: &MessageId,
-        message: &P,
+        message_opt: &Option<P>,
     ) {
         if let Some(last_id) = last_written_id {
             // write message id diff
@@ -48,7 +71,43 @@ impl<P: Send + Sync> ReliableSender<P> {
             message_id.ser(bit_writer);
         }
 
-        channel_writer.write(bit_writer, message);
+        // write whether the message still has a payload, expired messages do not
+        message_opt.is_some().ser(bit_writer);
+
+        if let Some(message) = message_opt {
+            channel_writer.write(bit_writer, message);
+        }
+    }
+
+    // Drops the payload of every message whose deadline has passed, keeping an
+    // empty placeholder in the queue until the remote host acknowledges it
+    fn collect_expired_messages(&mut self, now: &Instant) {
+        for sending_message in self.sending_messages.iter_mut().flatten() {
+            if sending_message.message.is_none() {
+                continue;
+            }
+            if let Some(expires_at) = &sending_message.expires_at {
+                if now < expires_at {
+                    continue;
+                }
+
+                let message = sending_message.message.take().unwrap();
+                self.expired_messages.push(message);
+
+                // don't send out a stale copy which has already been queued
+                for (next_message_id, next_message) in self.next_send_messages.iter_mut() {
+                    if *next_message_id == sending_message.message_id {
+                        *next_message = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns all Messages which have expired before being delivered since
+    /// the last call
+    pub fn take_expired_messages(&mut self) -> Vec<P> {
+        mem::take(&mut self.expired_messages)
     }
 
     pub fn cleanup_sent_messages(&mut self) {
@@ -68,8 +127,16 @@ impl<P: Send + Sync> ReliableSender<P> {
         }
     }
 
+    /// Takes all messages which are ready to be written. Messages which have
+    /// expired are left out, so this should not be combined with
+    /// `send_message_with_expiry()`
     pub fn take_next_messages(&mut self) -> VecDeque<(MessageId, P)> {
         mem::take(&mut self.next_send_messages)
+            .into_iter()
+            .filter_map(|(message_id, message_opt)| {
+                message_opt.map(|message| (message_id, message))
+            })
+            .collect()
     }
 
     // Called when a message has been delivered
@@ -84,8 +151,8 @@ impl<P: Send + Sync> ReliableSender<P> {
                 return None;
             }
 
-            if let Some(Some((old_message_id, _, _))) = self.sending_messages.get(index) {
-                if *message_id == *old_message_id {
+            if let Some(Some(sending_message)) = self.sending_messages.get(index) {
+                if *message_id == sending_message.message_id {
                     found = true;
                 }
             }
@@ -98,7 +165,7 @@ impl<P: Send + Sync> ReliableSender<P> {
                 self.cleanup_sent_messages();
 
                 // stop loop
-                return output.map(|(_, _, message)| message);
+                return output.and_then(|sending_message| sending_message.message);
             }
 
             index += 1;
@@ -108,17 +175,21 @@ impl<P: Send + Sync> ReliableSender<P> {
 
 impl<P: Clone + Send + Sync> ChannelSender<P> for ReliableSender<P> {
     fn send_message(&mut self, message: P) {
-        self.sending_messages
-            .push_back(Some((self.next_send_message_id, None, message)));
-        self.next_send_message_id = self.next_send_message_id.wrapping_add(1);
+        self.push_message(message, None);
+    }
+
+    fn send_message_with_expiry(&mut self, message: P, expires_at: Instant) {
+        self.push_message(message, Some(expires_at));
     }
 
     fn collect_messages(&mut self, now: &Instant, rtt_millis: &f32) {
+        self.collect_expired_messages(now);
+
         let resend_duration = Duration::from_millis((self.rtt_resend_factor * rtt_millis) as u64);
 
-        for (message_id, last_sent_opt, message) in self.sending_messages.iter_mut().flatten() {
+        for sending_message in self.sending_messages.iter_mut().flatten() {
             let mut should_send = false;
-            if let Some(last_sent) = last_sent_opt {
+            if let Some(last_sent) = &sending_message.last_sent {
                 if last_sent.elapsed() >= resend_duration {
                     should_send = true;
                 }
@@ -127,12 +198,16 @@ impl<P: Clone + Send + Sync> ChannelSender<P> for ReliableSender<P> {
             }
             if should_send {
                 self.next_send_messages
-                    .push_back((*message_id, message.clone()));
-                *last_sent_opt = Some(now.clone());
+                    .push_back((sending_message.message_id, sending_message.message.clone()));
+                sending_message.last_sent = Some(now.clone());
             }
         }
     }
 
+    fn take_expired_messages(&mut self) -> Vec<P> {
+        ReliableSender::take_expired_messages(self)
+    }
+
     fn has_messages(&self) -> bool {
         !self.next_send_messages.is_empty()
     }
@@ -148,7 +223,7 @@ impl<P: Clone + Send + Sync> ChannelSender<P> for ReliableSender<P> {
         {
             // Measure
             let current_packet_size = bit_writer.bit_count();
-            if current_packet_size > MTU_SIZE_BITS {
+            if current_packet_size > bit_writer.max_bits() {
                 message_list_header::write(bit_writer, 0);
                 return None;
             }
@@ -160,7 +235,7 @@ impl<P: Clone + Send + Sync> ChannelSender<P> for ReliableSender<P> {
             message_list_header::write(&mut counter, 123);
 
             // Check for overflow
-            if current_packet_size + counter.bit_count() > MTU_SIZE_BITS {
+            if current_packet_size + counter.bit_count() > bit_writer.max_bits() {
                 message_list_header::write(bit_writer, 0);
                 return None;
             }
@@ -173,16 +248,16 @@ impl<P: Clone + Send + Sync> ChannelSender<P> for ReliableSender<P> {
                     break;
                 }
 
-                let (message_id, message) = self.next_send_messages.get(index).unwrap();
+                let (message_id, message_opt) = self.next_send_messages.get(index).unwrap();
                 self.write_outgoing_message(
                     channel_writer,
                     &mut counter,
                     &last_written_id,
                     message_id,
-                    message,
+                    message_opt,
                 );
                 last_written_id = Some(*message_id);
-                if current_packet_size + counter.bit_count() <= MTU_SIZE_BITS {
+                if current_packet_size + counter.bit_count() <= bit_writer.max_bits() {
                     message_count += 1;
                 } else {
                     break;
@@ -202,13 +277,13 @@ impl<P: Clone + Send + Sync> ChannelSender<P> for ReliableSender<P> {
 
             for _ in 0..message_count {
                 // Pop and write message
-                let (message_id, message) = self.next_send_messages.pop_front().unwrap();
+                let (message_id, message_opt) = self.next_send_messages.pop_front().unwrap();
                 self.write_outgoing_message(
                     channel_writer,
                     bit_writer,
                     &last_written_id,
                     &message_id,
-                    &message,
+                    &message_opt,
                 );
 
                 message_ids.push(message_id);
@@ -28,7 +28,9 @@ impl<P> Default for UnorderedReliableReceiver<P> {
 impl<P> UnorderedReliableReceiver<P> {
     // Private methods
 
-    pub fn buffer_message(&mut self, message_id: MessageId, message: P) {
+    /// Buffers an incoming message. A `None` message is one which expired
+    /// before it could be delivered, and is only recorded as received.
+    pub fn buffer_message(&mut self, message_id: MessageId, message: Option<P>) {
         // moving from oldest incoming message to newest
         // compare existing slots and see if the message_id has been instantiated
         // already if it has, put the message into the slot
@@ -49,7 +51,9 @@ impl<P> UnorderedReliableReceiver<P> {
                     if *old_message_id == message_id {
                         if !(*old_message) {
                             *old_message = true;
-                            self.received_messages.push((*old_message_id, message));
+                            if let Some(message) = message {
+                                self.received_messages.push((*old_message_id, message));
+                            }
                             return;
                         } else {
                             // already received this message
@@ -62,7 +66,9 @@ impl<P> UnorderedReliableReceiver<P> {
 
                 if next_message_id == message_id {
                     self.record.push_back((next_message_id, true));
-                    self.received_messages.push((message_id, message));
+                    if let Some(message) = message {
+                        self.received_messages.push((message_id, message));
+                    }
                     return;
                 } else {
                     self.record.push_back((next_message_id, false));
@@ -3,7 +3,7 @@ use std::collections::VecDeque;
 use naia_serde::{BitCounter, BitWrite, BitWriter};
 use naia_socket_shared::Instant;
 
-use crate::{constants::MTU_SIZE_BITS, types::MessageId};
+use crate::types::MessageId;
 
 use super::{
     message_channel::{ChannelSender, ChannelWriter},
@@ -36,6 +36,11 @@ impl<P: Send + Sync> ChannelSender<P> for UnorderedUnreliableSender<P> {
         self.outgoing_messages.push_back(message);
     }
 
+    fn send_message_with_expiry(&mut self, message: P, _: Instant) {
+        // unreliable messages are only ever sent once, so there is nothing to expire
+        self.send_message(message);
+    }
+
     fn collect_messages(&mut self, _: &Instant, _: &f32) {
         // not necessary for an unreliable channel
     }
@@ -55,7 +60,7 @@ impl<P: Send + Sync> ChannelSender<P> for UnorderedUnreliableSender<P> {
         {
             // Measure
             let current_packet_size = bit_writer.bit_count();
-            if current_packet_size > MTU_SIZE_BITS {
+            if current_packet_size > bit_writer.max_bits() {
                 write(bit_writer, 0);
                 return None;
             }
@@ -67,7 +72,7 @@ impl<P: Send + Sync> ChannelSender<P> for UnorderedUnreliableSender<P> {
             write(&mut counter, 123);
 
             // Check for overflow
-            if current_packet_size + counter.bit_count() > MTU_SIZE_BITS {
+            if current_packet_size + counter.bit_count() > bit_writer.max_bits() {
                 write(bit_writer, 0);
                 return None;
             }
@@ -81,7 +86,7 @@ impl<P: Send + Sync> ChannelSender<P> for UnorderedUnreliableSender<P> {
 
                 let message = self.outgoing_messages.get(index).unwrap();
                 self.write_message(channel_writer, &mut counter, message);
-                if current_packet_size + counter.bit_count() <= MTU_SIZE_BITS {
+                if current_packet_size + counter.bit_count() <= bit_writer.max_bits() {
                     message_count += 1;
                 } else {
                     break;
@@ -108,4 +113,9 @@ impl<P: Send + Sync> ChannelSender<P> for UnorderedUnreliableSender<P> {
     fn notify_message_delivered(&mut self, _: &MessageId) {
         // not necessary for an unreliable channel
     }
+
+    fn take_expired_messages(&mut self) -> Vec<P> {
+        // not necessary for an unreliable channel
+        Vec::new()
+    }
 }
@@ -25,7 +25,7 @@ impl<E: Copy + Hash + Eq, K: ProtocolKindType> Default for EntityActionReceiver<
 
 impl<E: Copy + Hash + Eq, K: ProtocolKindType> EntityActionReceiver<E, K> {
     pub fn buffer_action(&mut self, action_id: ActionId, action: EntityAction<E, K>) {
-        self.receiver.buffer_message(action_id, action)
+        self.receiver.buffer_message(action_id, Some(action))
     }
 
     pub fn receive_actions(&mut self) -> Vec<EntityAction<E, K>> {
@@ -5,7 +5,9 @@ use naia_serde::{BitReader, BitWrite, BitWriter, Serde, SerdeErr};
 use crate::{
     bigmap::BigMapKey,
     protocol::{
-        entity_handle::EntityHandle, net_entity::NetEntity, property::Property,
+        entity_handle::EntityHandle,
+        net_entity::{NetEntity, OwnedNetEntity},
+        property::Property,
         property_mutate::PropertyMutator,
     },
 };
@@ -32,9 +34,10 @@ impl EntityProperty {
 
     // Serialization / deserialization
 
+    /// Entities which the remote host does not know of are written as None
     pub fn write(&self, writer: &mut dyn BitWrite, converter: &dyn NetEntityHandleConverter) {
         (*self.handle_prop)
-            .map(|handle| converter.handle_to_net_entity(&handle))
+            .and_then(|handle| converter.handle_to_net_entity(&handle))
             .ser(writer);
     }
 
@@ -43,8 +46,10 @@ impl EntityProperty {
         mutator_index: u8,
         converter: &dyn NetEntityHandleConverter,
     ) -> Result<Self, SerdeErr> {
-        if let Some(net_entity) = Option::<NetEntity>::de(reader)? {
-            let handle = converter.net_entity_to_handle(&net_entity);
+        if let Some(net_entity) = Option::<OwnedNetEntity>::de(reader)? {
+            let handle = converter
+                .net_entity_to_handle(&net_entity.to_reversed())
+                .ok_or(SerdeErr)?;
             let mut new_prop = Self::new(mutator_index);
             *new_prop.handle_prop = Some(handle);
             Ok(new_prop)
@@ -56,7 +61,7 @@ impl EntityProperty {
     }
 
     pub fn read_write(reader: &mut BitReader, writer: &mut BitWriter) -> Result<(), SerdeErr> {
-        Option::<NetEntity>::de(reader)?.ser(writer);
+        Option::<OwnedNetEntity>::de(reader)?.ser(writer);
         Ok(())
     }
 
@@ -65,8 +70,10 @@ impl EntityProperty {
         reader: &mut BitReader,
         converter: &dyn NetEntityHandleConverter,
     ) -> Result<(), SerdeErr> {
-        if let Some(net_entity) = Option::<NetEntity>::de(reader)? {
-            let handle = converter.net_entity_to_handle(&net_entity);
+        if let Some(net_entity) = Option::<OwnedNetEntity>::de(reader)? {
+            let handle = converter
+                .net_entity_to_handle(&net_entity.to_reversed())
+                .ok_or(SerdeErr)?;
             *self.handle_prop = Some(handle);
         } else {
             *self.handle_prop = None;
@@ -107,53 +114,166 @@ pub trait EntityHandleConverter<E: Copy + Eq + Hash> {
     fn entity_to_handle(&self, entity: &E) -> EntityHandle;
 }
 
+/// Converts between EntityHandles & the NetEntities they are written as. An
+/// Entity unknown to the connection converts to None
 pub trait NetEntityHandleConverter {
-    fn handle_to_net_entity(&self, entity_handle: &EntityHandle) -> NetEntity;
-    fn net_entity_to_handle(&self, net_entity: &NetEntity) -> EntityHandle;
+    fn handle_to_net_entity(&self, entity_handle: &EntityHandle) -> Option<OwnedNetEntity>;
+    fn net_entity_to_handle(&self, net_entity: &OwnedNetEntity) -> Option<EntityHandle>;
 }
 
+/// Converts between Entities & the NetEntities assigned to them by one side
+/// of a connection. An Entity unknown to that side converts to None
 pub trait NetEntityConverter<E: Copy + Eq + Hash> {
-    fn entity_to_net_entity(&self, entity: &E) -> NetEntity;
-    fn net_entity_to_entity(&self, net_entity: &NetEntity) -> E;
+    fn entity_to_net_entity(&self, entity: &E) -> Option<NetEntity>;
+    fn net_entity_to_entity(&self, net_entity: &NetEntity) -> Option<E>;
 }
 
 pub struct FakeEntityConverter;
 
 impl NetEntityHandleConverter for FakeEntityConverter {
-    fn handle_to_net_entity(&self, _: &EntityHandle) -> NetEntity {
-        NetEntity::from(0)
+    fn handle_to_net_entity(&self, _: &EntityHandle) -> Option<OwnedNetEntity> {
+        Some(OwnedNetEntity::Host(NetEntity::from(0)))
     }
 
-    fn net_entity_to_handle(&self, _: &NetEntity) -> EntityHandle {
-        EntityHandle::from_u64(0)
+    fn net_entity_to_handle(&self, _: &OwnedNetEntity) -> Option<EntityHandle> {
+        Some(EntityHandle::from_u64(0))
     }
 }
 
+/// Converts EntityHandles to the NetEntities of the Entities spawned by this
+/// host, and, if given, to those of the Entities spawned by the remote host
 pub struct EntityConverter<'a, 'b, E: Eq + Copy + Hash> {
     handle_converter: &'a dyn EntityHandleConverter<E>,
-    net_entity_converter: &'b dyn NetEntityConverter<E>,
+    host_converter: &'b dyn NetEntityConverter<E>,
+    remote_converter: Option<&'b dyn NetEntityConverter<E>>,
 }
 
 impl<'a, 'b, E: Eq + Copy + Hash> EntityConverter<'a, 'b, E> {
     pub fn new(
         handle_converter: &'a dyn EntityHandleConverter<E>,
-        net_entity_converter: &'b dyn NetEntityConverter<E>,
+        host_converter: &'b dyn NetEntityConverter<E>,
     ) -> Self {
         Self {
             handle_converter,
-            net_entity_converter,
+            host_converter,
+            remote_converter: None,
         }
     }
+
+    pub fn with_remote(mut self, remote_converter: &'b dyn NetEntityConverter<E>) -> Self {
+        self.remote_converter = Some(remote_converter);
+        self
+    }
 }
 
 impl<'a, 'b, E: Copy + Eq + Hash> NetEntityHandleConverter for EntityConverter<'a, 'b, E> {
-    fn handle_to_net_entity(&self, entity_handle: &EntityHandle) -> NetEntity {
+    fn handle_to_net_entity(&self, entity_handle: &EntityHandle) -> Option<OwnedNetEntity> {
         let entity = self.handle_converter.handle_to_entity(entity_handle);
-        self.net_entity_converter.entity_to_net_entity(&entity)
+        if let Some(net_entity) = self.host_converter.entity_to_net_entity(&entity) {
+            return Some(OwnedNetEntity::Host(net_entity));
+        }
+        self.remote_converter?
+            .entity_to_net_entity(&entity)
+            .map(OwnedNetEntity::Remote)
+    }
+
+    fn net_entity_to_handle(&self, net_entity: &OwnedNetEntity) -> Option<EntityHandle> {
+        let entity = match net_entity {
+            OwnedNetEntity::Host(net_entity) => {
+                self.host_converter.net_entity_to_entity(net_entity)?
+            }
+            OwnedNetEntity::Remote(net_entity) => {
+                self.remote_converter?.net_entity_to_entity(net_entity)?
+            }
+        };
+        Some(self.handle_converter.entity_to_handle(&entity))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use naia_serde::{BitReader, BitWriter, Serde};
+
+    use crate::{
+        bigmap::BigMapKey,
+        protocol::{
+            entity_handle::EntityHandle,
+            net_entity::{NetEntity, OwnedNetEntity},
+        },
+    };
+
+    use super::{
+        EntityConverter, EntityHandleConverter, EntityProperty, NetEntityConverter,
+        NetEntityHandleConverter,
+    };
+
+    struct Handles;
+
+    impl EntityHandleConverter<u32> for Handles {
+        fn handle_to_entity(&self, entity_handle: &EntityHandle) -> u32 {
+            entity_handle.to_u64() as u32
+        }
+
+        fn entity_to_handle(&self, entity: &u32) -> EntityHandle {
+            EntityHandle::from_u64(*entity as u64)
+        }
+    }
+
+    struct NetEntities(HashMap<u32, NetEntity>);
+
+    impl NetEntityConverter<u32> for NetEntities {
+        fn entity_to_net_entity(&self, entity: &u32) -> Option<NetEntity> {
+            self.0.get(entity).copied()
+        }
+
+        fn net_entity_to_entity(&self, net_entity: &NetEntity) -> Option<u32> {
+            self.0
+                .iter()
+                .find(|(_, other)| *other == net_entity)
+                .map(|(entity, _)| *entity)
+        }
+    }
+
+    #[test]
+    fn entities_of_either_host_are_converted() {
+        let host = NetEntities(HashMap::from([(10, NetEntity::from(0))]));
+        let remote = NetEntities(HashMap::from([(20, NetEntity::from(0))]));
+        let converter = EntityConverter::new(&Handles, &host).with_remote(&remote);
+
+        let host_handle = EntityHandle::from_u64(10);
+        let remote_handle = EntityHandle::from_u64(20);
+        assert!(
+            converter.handle_to_net_entity(&host_handle)
+                == Some(OwnedNetEntity::Host(NetEntity::from(0)))
+        );
+        assert!(
+            converter.handle_to_net_entity(&remote_handle)
+                == Some(OwnedNetEntity::Remote(NetEntity::from(0)))
+        );
+        assert!(
+            converter.net_entity_to_handle(&OwnedNetEntity::Remote(NetEntity::from(0)))
+                == Some(remote_handle)
+        );
+        assert!(converter
+            .net_entity_to_handle(&OwnedNetEntity::Host(NetEntity::from(1)))
+            .is_none());
+
+        // without a remote converter, only the host's Entities are known
+        let host_only = EntityConverter::new(&Handles, &host);
+        assert!(host_only.handle_to_net_entity(&remote_handle).is_none());
+    }
+
+    #[test]
+    fn unknown_entities_are_rejected() {
+        let mut writer = BitWriter::new();
+        Some(OwnedNetEntity::Host(NetEntity::from(5))).ser(&mut writer);
+        let (length, buffer) = writer.flush();
 
-    fn net_entity_to_handle(&self, net_entity: &NetEntity) -> EntityHandle {
-        let entity = self.net_entity_converter.net_entity_to_entity(net_entity);
-        self.handle_converter.entity_to_handle(&entity)
+        let known = NetEntities(HashMap::new());
+        let converter = EntityConverter::new(&Handles, &known).with_remote(&known);
+        let mut reader = BitReader::new(&buffer[..length]);
+        assert!(EntityProperty::new_read(&mut reader, 0, &converter).is_err());
     }
 }
@@ -5,10 +5,15 @@ pub mod entity_action_receiver;
 pub mod entity_action_type;
 pub mod entity_handle;
 pub mod entity_property;
+pub mod nested_property;
 pub mod net_entity;
 pub mod property;
+pub mod property_map;
 pub mod property_mutate;
+pub mod property_serde;
+pub mod property_vec;
 pub mod protocol_io;
 pub mod protocolize;
 pub mod replica_ref;
 pub mod replicate;
+pub mod replicate_nested;
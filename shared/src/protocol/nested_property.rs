@@ -0,0 +1,140 @@
+use std::ops::{Deref, DerefMut};
+
+use naia_serde::{BitReader, BitWrite, BitWriter, SerdeErr};
+
+use crate::protocol::{
+    diff_mask::DiffMask, property_mutate::PropertyMutator, replicate_nested::ReplicateNested,
+};
+
+/// A Property of a Component which is itself a struct of Properties. Each of
+/// the inner Properties is tracked and synced on its own, so changing one of
+/// them does not resend the whole struct
+#[derive(Clone)]
+pub struct NestedProperty<T: ReplicateNested> {
+    inner: T,
+}
+
+impl<T: ReplicateNested> NestedProperty<T> {
+    /// Create a new NestedProperty
+    pub fn new(value: T) -> NestedProperty<T> {
+        NestedProperty::<T> { inner: value }
+    }
+
+    /// Set every inner Property to the value of the given struct, queueing
+    /// those which change for update. Use this rather than assigning a new
+    /// struct through a mutable reference, which would stop its changes from
+    /// being tracked
+    pub fn set(&mut self, value: &T) {
+        self.inner.mirror(value);
+    }
+
+    /// Set value to the value of another NestedProperty, queues for update
+    /// the inner Properties which change
+    pub fn mirror(&mut self, other: &NestedProperty<T>) {
+        self.inner.mirror(&other.inner);
+    }
+
+    // Serialization / deserialization
+
+    /// Writes contained value into outgoing byte stream
+    pub fn write(&self, writer: &mut dyn BitWrite) {
+        self.inner.write(writer);
+    }
+
+    /// Given a cursor into incoming packet data, initializes the
+    /// NestedProperty with the synced value
+    pub fn new_read(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        Ok(NestedProperty::<T> {
+            inner: T::new_read(reader)?,
+        })
+    }
+
+    /// Writes the inner Properties marked in the DiffMask into outgoing byte
+    /// stream
+    pub fn write_update(&self, diff_mask: &DiffMask, first_index: u8, writer: &mut dyn BitWrite) {
+        self.inner.write_update(diff_mask, first_index, writer);
+    }
+
+    /// Given a cursor into incoming packet data, updates the inner Properties
+    /// which were written by `write_update`
+    pub fn read_apply_update(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.inner.read_apply_update(reader)
+    }
+
+    /// Reads an update from a stream and immediately writes it to a stream
+    /// Used to buffer updates for later
+    pub fn read_write_update(
+        reader: &mut BitReader,
+        writer: &mut BitWriter,
+    ) -> Result<(), SerdeErr> {
+        T::read_write_update(reader, writer)
+    }
+
+    /// Reads an update from a stream, marking which inner Properties it
+    /// contains in the DiffMask
+    pub fn read_update_diff_mask(
+        reader: &mut BitReader,
+        diff_mask: &mut DiffMask,
+        first_index: u8,
+    ) -> Result<(), SerdeErr> {
+        T::read_update_diff_mask(reader, diff_mask, first_index)
+    }
+
+    /// Writes the inner Properties which differ from those of a baseline
+    /// NestedProperty into outgoing byte stream
+    pub fn write_update_delta(&self, baseline: &NestedProperty<T>, writer: &mut dyn BitWrite) {
+        self.inner.write_update_delta(&baseline.inner, writer);
+    }
+
+    /// Returns the value the reader has once it reads the changes written by
+    /// `write_update_delta` against the given baseline
+    pub fn delta_sent_state(&self, baseline: &NestedProperty<T>) -> NestedProperty<T> {
+        NestedProperty::<T> {
+            inner: self.inner.delta_sent_state(&baseline.inner),
+        }
+    }
+
+    /// Given a cursor into incoming packet data, initializes the
+    /// NestedProperty from a baseline and the changes written by
+    /// `write_update_delta`
+    pub fn new_read_update_delta(
+        baseline: &NestedProperty<T>,
+        reader: &mut BitReader,
+        diff_mask: &mut DiffMask,
+        first_index: u8,
+    ) -> Result<Self, SerdeErr> {
+        Ok(NestedProperty::<T> {
+            inner: T::new_read_update_delta(&baseline.inner, reader, diff_mask, first_index)?,
+        })
+    }
+
+    // Comparison
+
+    /// Compare to another property
+    pub fn equals(&self, other: &NestedProperty<T>) -> bool {
+        self.inner.equals(&other.inner)
+    }
+
+    // Internal
+
+    /// Set an PropertyMutator to track changes to the inner Properties, which
+    /// take the bits of the DiffMask starting at the given index
+    pub fn set_mutator(&mut self, mutator: &PropertyMutator, first_index: u8) {
+        self.inner.set_mutator(mutator, first_index);
+    }
+}
+
+impl<T: ReplicateNested> Deref for NestedProperty<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+// The inner Properties queue their own changes for update
+impl<T: ReplicateNested> DerefMut for NestedProperty<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
@@ -4,18 +4,19 @@ use crate::serde;
 use naia_serde::{BitReader, BitWrite, SerdeErr, UnsignedVariableInteger};
 
 // An Entity in the Client's scope, that is being
-// synced to the Client
+// synced to the Client. Written as a variable-length integer, so that the
+// small ids most Entities are given stay cheap
 #[derive(Copy, Eq, Hash, Clone, PartialEq)]
-pub struct NetEntity(u16);
+pub struct NetEntity(u32);
 
-impl From<NetEntity> for u16 {
-    fn from(entity: NetEntity) -> u16 {
+impl From<NetEntity> for u32 {
+    fn from(entity: NetEntity) -> u32 {
         entity.0
     }
 }
 
-impl From<u16> for NetEntity {
-    fn from(value: u16) -> Self {
+impl From<u32> for NetEntity {
+    fn from(value: u32) -> Self {
         NetEntity(value)
     }
 }
@@ -27,6 +28,72 @@ impl serde::Serde for NetEntity {
 
     fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
         let value = UnsignedVariableInteger::<7>::de(reader)?.get();
-        Ok(NetEntity(value as u16))
+        let value = u32::try_from(value).map_err(|_| SerdeErr)?;
+        Ok(NetEntity(value))
+    }
+}
+
+// A NetEntity, along with which side of the connection spawned it. Each side
+// assigns the NetEntities of the Entities it spawns, so the same NetEntity can
+// refer to two different Entities. Seen from the host holding it
+#[derive(Copy, Eq, Hash, Clone, PartialEq)]
+pub enum OwnedNetEntity {
+    // Spawned by this host
+    Host(NetEntity),
+    // Spawned by the remote host
+    Remote(NetEntity),
+}
+
+impl OwnedNetEntity {
+    /// The same NetEntity, as seen from the other side of the connection
+    pub fn to_reversed(self) -> Self {
+        match self {
+            OwnedNetEntity::Host(net_entity) => OwnedNetEntity::Remote(net_entity),
+            OwnedNetEntity::Remote(net_entity) => OwnedNetEntity::Host(net_entity),
+        }
+    }
+}
+
+impl serde::Serde for OwnedNetEntity {
+    fn ser(&self, writer: &mut dyn BitWrite) {
+        match self {
+            OwnedNetEntity::Host(net_entity) => {
+                true.ser(writer);
+                net_entity.ser(writer);
+            }
+            OwnedNetEntity::Remote(net_entity) => {
+                false.ser(writer);
+                net_entity.ser(writer);
+            }
+        }
+    }
+
+    fn de(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        let is_host = bool::de(reader)?;
+        let net_entity = NetEntity::de(reader)?;
+        if is_host {
+            Ok(OwnedNetEntity::Host(net_entity))
+        } else {
+            Ok(OwnedNetEntity::Remote(net_entity))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use naia_serde::{BitReader, BitWriter, Serde, UnsignedVariableInteger};
+
+    use super::NetEntity;
+
+    #[test]
+    fn values_past_u32_are_rejected() {
+        let mut writer = BitWriter::new();
+        UnsignedVariableInteger::<7>::new(u32::MAX).ser(&mut writer);
+        UnsignedVariableInteger::<7>::new(u32::MAX as u64 + 1).ser(&mut writer);
+        let (length, buffer) = writer.flush();
+
+        let mut reader = BitReader::new(&buffer[..length]);
+        assert!(u32::from(NetEntity::de(&mut reader).unwrap()) == u32::MAX);
+        assert!(NetEntity::de(&mut reader).is_err());
     }
 }
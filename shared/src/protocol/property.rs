@@ -1,20 +1,20 @@
 use std::ops::{Deref, DerefMut};
 
-use naia_serde::{BitReader, BitWrite, BitWriter, Serde, SerdeErr};
+use naia_serde::{BitReader, BitWrite, BitWriter, SerdeErr};
 
-use crate::protocol::property_mutate::PropertyMutator;
+use crate::protocol::{property_mutate::PropertyMutator, property_serde::PropertySerde};
 
 /// A Property of an Component/Message, that contains data
 /// which must be tracked for updates
 #[derive(Clone)]
-pub struct Property<T: Serde> {
+pub struct Property<T: PropertySerde> {
     inner: T,
     mutator: Option<PropertyMutator>,
     mutator_index: u8,
 }
 
 // should be shared
-impl<T: Serde> Property<T> {
+impl<T: PropertySerde> Property<T> {
     /// Create a new Property
     pub fn new(value: T, mutator_index: u8) -> Property<T> {
         Property::<T> {
@@ -34,7 +34,13 @@ impl<T: Serde> Property<T> {
 
     /// Writes contained value into outgoing byte stream
     pub fn write(&self, writer: &mut dyn BitWrite) {
-        self.inner.ser(writer);
+        self.inner.write(writer);
+    }
+
+    /// Writes contained value into outgoing byte stream, as a change from the
+    /// value of a baseline Property which the remote host already has
+    pub fn write_delta(&self, baseline: &Property<T>, writer: &mut dyn BitWrite) {
+        self.inner.write_delta(&baseline.inner, writer);
     }
 
     /// Given a cursor into incoming packet data, initializes the Property with
@@ -49,29 +55,50 @@ impl<T: Serde> Property<T> {
         })
     }
 
+    /// Given a cursor into incoming packet data, initializes the Property with
+    /// the synced value, which was written as a change from the given baseline
+    pub fn new_read_delta(
+        baseline: &Property<T>,
+        reader: &mut BitReader,
+        mutator_index: u8,
+    ) -> Result<Self, SerdeErr> {
+        let inner = T::read_delta(&baseline.inner, reader)?;
+
+        Ok(Property::<T> {
+            inner,
+            mutator: None,
+            mutator_index,
+        })
+    }
+
     /// Reads from a stream and immediately writes to a stream
     /// Used to buffer updates for later
     pub fn read_write(reader: &mut BitReader, writer: &mut BitWriter) -> Result<(), SerdeErr> {
-        T::de(reader)?.ser(writer);
+        T::read(reader)?.write(writer);
         Ok(())
     }
 
     /// Given a cursor into incoming packet data, updates the Property with the
-    /// synced value
+    /// synced value. If the Property is itself being replicated onwards, the
+    /// new value is marked to be sent
     pub fn read(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
         self.inner = Self::read_inner(reader)?;
+        if let Some(mutator) = &mut self.mutator {
+            mutator.mutate(self.mutator_index);
+        }
         Ok(())
     }
 
     fn read_inner(reader: &mut BitReader) -> Result<T, SerdeErr> {
-        T::de(reader)
+        T::read(reader)
     }
 
     // Comparison
 
-    /// Compare to another property
+    /// Compare to another property, where values which do not differ enough
+    /// to be synced are considered equal
     pub fn equals(&self, other: &Property<T>) -> bool {
-        self.inner == other.inner
+        !self.inner.has_changed(&other.inner)
     }
 
     // Internal
@@ -80,11 +107,18 @@ impl<T: Serde> Property<T> {
     pub fn set_mutator(&mut self, mutator: &PropertyMutator) {
         self.mutator = Some(mutator.clone_new());
     }
+
+    /// Set the index of the Property in the DiffMask of its Component, for
+    /// Properties nested within another struct whose position is only known
+    /// once it is part of a Component
+    pub fn set_mutator_index(&mut self, mutator_index: u8) {
+        self.mutator_index = mutator_index;
+    }
 }
 
 // It could be argued that Property here is a type of smart-pointer,
 // but honestly this is mainly for the convenience of type coercion
-impl<T: Serde> Deref for Property<T> {
+impl<T: PropertySerde> Deref for Property<T> {
     type Target = T;
 
     fn deref(&self) -> &Self::Target {
@@ -92,7 +126,7 @@ impl<T: Serde> Deref for Property<T> {
     }
 }
 
-impl<T: Serde> DerefMut for Property<T> {
+impl<T: PropertySerde> DerefMut for Property<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
         // Just assume inner value will be changed, queue for update
         if let Some(mutator) = &mut self.mutator {
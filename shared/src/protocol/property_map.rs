@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Deref, DerefMut},
+};
+
+use naia_serde::{BitReader, BitWrite, BitWriter, Serde, SerdeErr, UnsignedVariableInteger};
+
+use crate::protocol::property_mutate::PropertyMutator;
+
+/// A Property of an Component/Message, that contains a map of values which
+/// must be tracked for updates. When delta compression is enabled, an update
+/// contains only the entries which were inserted, removed or changed since the
+/// state the remote host last acknowledged, rather than the whole map
+///
+/// Only delta compression sends changes to single entries. Without it, every
+/// update resends the whole map, however little of it changed
+#[derive(Clone)]
+pub struct PropertyMap<K: Serde + Eq + Hash, V: Serde> {
+    inner: HashMap<K, V>,
+    mutator: Option<PropertyMutator>,
+    mutator_index: u8,
+}
+
+impl<K: Serde + Eq + Hash, V: Serde> PropertyMap<K, V> {
+    /// Create a new PropertyMap
+    pub fn new(value: HashMap<K, V>, mutator_index: u8) -> PropertyMap<K, V> {
+        PropertyMap::<K, V> {
+            inner: value,
+            mutator: None,
+            mutator_index,
+        }
+    }
+
+    /// Set value to the value of another PropertyMap, queues for update if
+    /// value changes
+    pub fn mirror(&mut self, other: &PropertyMap<K, V>) {
+        **self = (**other).clone();
+    }
+
+    // Serialization / deserialization
+
+    /// Writes contained value into outgoing byte stream
+    pub fn write(&self, writer: &mut dyn BitWrite) {
+        self.inner.ser(writer);
+    }
+
+    /// Writes contained value into outgoing byte stream, as a change from the
+    /// value of a baseline PropertyMap which the remote host already has.
+    /// Only the keys which were removed, and the entries which were inserted
+    /// or changed, are written
+    pub fn write_delta(&self, baseline: &PropertyMap<K, V>, writer: &mut dyn BitWrite) {
+        let current = &self.inner;
+        let baseline = &baseline.inner;
+
+        let removed: Vec<&K> = baseline
+            .keys()
+            .filter(|key| !current.contains_key(key))
+            .collect();
+        UnsignedVariableInteger::<5>::new(removed.len() as u64).ser(writer);
+        for key in removed {
+            key.ser(writer);
+        }
+
+        let changed: Vec<(&K, &V)> = current
+            .iter()
+            .filter(|(key, value)| baseline.get(key) != Some(value))
+            .collect();
+        UnsignedVariableInteger::<5>::new(changed.len() as u64).ser(writer);
+        for (key, value) in changed {
+            key.ser(writer);
+            match baseline.get(key) {
+                Some(baseline_value) => value.ser_delta(baseline_value, writer),
+                None => value.ser(writer),
+            }
+        }
+    }
+
+    /// Given a cursor into incoming packet data, initializes the PropertyMap
+    /// with the synced value
+    pub fn new_read(reader: &mut BitReader, mutator_index: u8) -> Result<Self, SerdeErr> {
+        let inner = HashMap::<K, V>::de(reader)?;
+
+        Ok(PropertyMap::<K, V> {
+            inner,
+            mutator: None,
+            mutator_index,
+        })
+    }
+
+    /// Given a cursor into incoming packet data, initializes the PropertyMap
+    /// with the synced value, which was written as a change from the given
+    /// baseline
+    pub fn new_read_delta(
+        baseline: &PropertyMap<K, V>,
+        reader: &mut BitReader,
+        mutator_index: u8,
+    ) -> Result<Self, SerdeErr> {
+        let mut inner = baseline.inner.clone();
+
+        let removed_length = UnsignedVariableInteger::<5>::de(reader)?.get();
+        for _ in 0..removed_length {
+            inner.remove(&K::de(reader)?);
+        }
+
+        let changed_length = UnsignedVariableInteger::<5>::de(reader)?.get();
+        for _ in 0..changed_length {
+            let key = K::de(reader)?;
+            let value = match baseline.inner.get(&key) {
+                Some(baseline_value) => V::de_delta(baseline_value, reader)?,
+                None => V::de(reader)?,
+            };
+            inner.insert(key, value);
+        }
+
+        Ok(PropertyMap::<K, V> {
+            inner,
+            mutator: None,
+            mutator_index,
+        })
+    }
+
+    /// Reads from a stream and immediately writes to a stream
+    /// Used to buffer updates for later
+    pub fn read_write(reader: &mut BitReader, writer: &mut BitWriter) -> Result<(), SerdeErr> {
+        HashMap::<K, V>::de(reader)?.ser(writer);
+        Ok(())
+    }
+
+    /// Given a cursor into incoming packet data, updates the PropertyMap with
+    /// the synced value. If the PropertyMap is itself being replicated
+    /// onwards, the new value is marked to be sent
+    pub fn read(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.inner = HashMap::<K, V>::de(reader)?;
+        if let Some(mutator) = &mut self.mutator {
+            mutator.mutate(self.mutator_index);
+        }
+        Ok(())
+    }
+
+    // Comparison
+
+    /// Compare to another property
+    pub fn equals(&self, other: &PropertyMap<K, V>) -> bool {
+        self.inner == other.inner
+    }
+
+    // Internal
+
+    /// Set an PropertyMutator to track changes to the PropertyMap
+    pub fn set_mutator(&mut self, mutator: &PropertyMutator) {
+        self.mutator = Some(mutator.clone_new());
+    }
+}
+
+impl<K: Serde + Eq + Hash, V: Serde> Deref for PropertyMap<K, V> {
+    type Target = HashMap<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<K: Serde + Eq + Hash, V: Serde> DerefMut for PropertyMap<K, V> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Just assume inner value will be changed, queue for update
+        if let Some(mutator) = &mut self.mutator {
+            mutator.mutate(self.mutator_index);
+        }
+        &mut self.inner
+    }
+}
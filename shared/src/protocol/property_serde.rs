@@ -0,0 +1,55 @@
+use naia_serde::{BitReader, BitWrite, Serde, SerdeErr};
+
+/// Controls how the value of a Property is written to and read from the
+/// network, and how far it must change before the change is synced. Every
+/// type which implements Serde implements this by using its Serde methods and
+/// PartialEq, so it is only implemented directly by types which need custom
+/// behavior, and which do not implement Serde themselves
+pub trait PropertySerde: Clone + Sized {
+    /// Writes the whole value into an outgoing byte stream
+    fn write(&self, writer: &mut dyn BitWrite);
+
+    /// Reads a value written by `write` from an incoming byte stream
+    fn read(reader: &mut BitReader) -> Result<Self, SerdeErr>;
+
+    /// Writes the value into an outgoing byte stream, as a change from a
+    /// baseline value which the reader already has. Unless overridden, the
+    /// whole value is written
+    fn write_delta(&self, _baseline: &Self, writer: &mut dyn BitWrite) {
+        self.write(writer);
+    }
+
+    /// Reads a value written by `write_delta`, given the baseline value it was
+    /// written against
+    fn read_delta(_baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        Self::read(reader)
+    }
+
+    /// Whether the value differs enough from a previous value for the change
+    /// to be synced. When updates are written against the last state the
+    /// remote host acknowledged, changes which this rejects are not sent, and
+    /// instead accumulate until they are large enough
+    fn has_changed(&self, previous: &Self) -> bool;
+}
+
+impl<T: Serde> PropertySerde for T {
+    fn write(&self, writer: &mut dyn BitWrite) {
+        self.ser(writer);
+    }
+
+    fn read(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        T::de(reader)
+    }
+
+    fn write_delta(&self, baseline: &Self, writer: &mut dyn BitWrite) {
+        self.ser_delta(baseline, writer);
+    }
+
+    fn read_delta(baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        T::de_delta(baseline, reader)
+    }
+
+    fn has_changed(&self, previous: &Self) -> bool {
+        self != previous
+    }
+}
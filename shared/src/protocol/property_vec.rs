@@ -0,0 +1,278 @@
+use std::ops::{Deref, DerefMut};
+
+use naia_serde::{BitReader, BitWrite, BitWriter, Serde, SerdeErr, UnsignedVariableInteger};
+
+use crate::protocol::property_mutate::PropertyMutator;
+
+/// A Property of an Component/Message, that contains a list of values which
+/// must be tracked for updates. When delta compression is enabled, an update
+/// contains only the elements which were inserted, removed or changed since
+/// the state the remote host last acknowledged, rather than the whole list
+///
+/// Only delta compression sends changes to single elements. Without it, every
+/// update resends the whole list, however little of it changed
+#[derive(Clone)]
+pub struct PropertyVec<T: Serde> {
+    inner: Vec<T>,
+    mutator: Option<PropertyMutator>,
+    mutator_index: u8,
+}
+
+impl<T: Serde> PropertyVec<T> {
+    /// Create a new PropertyVec
+    pub fn new(value: Vec<T>, mutator_index: u8) -> PropertyVec<T> {
+        PropertyVec::<T> {
+            inner: value,
+            mutator: None,
+            mutator_index,
+        }
+    }
+
+    /// Set value to the value of another PropertyVec, queues for update if
+    /// value changes
+    pub fn mirror(&mut self, other: &PropertyVec<T>) {
+        **self = (**other).clone();
+    }
+
+    // Serialization / deserialization
+
+    /// Writes contained value into outgoing byte stream
+    pub fn write(&self, writer: &mut dyn BitWrite) {
+        self.inner.ser(writer);
+    }
+
+    /// Writes contained value into outgoing byte stream, as a change from the
+    /// value of a baseline PropertyVec which the remote host already has.
+    /// The elements before and after the changed range of the list are
+    /// skipped, and within it only the elements which differ are written
+    pub fn write_delta(&self, baseline: &PropertyVec<T>, writer: &mut dyn BitWrite) {
+        let current = &self.inner;
+        let baseline = &baseline.inner;
+        let shortest = current.len().min(baseline.len());
+
+        let prefix = current
+            .iter()
+            .zip(baseline.iter())
+            .take_while(|(item, baseline_item)| item == baseline_item)
+            .count();
+        let suffix = current
+            .iter()
+            .rev()
+            .zip(baseline.iter().rev())
+            .take(shortest - prefix)
+            .take_while(|(item, baseline_item)| item == baseline_item)
+            .count();
+
+        UnsignedVariableInteger::<5>::new(prefix as u64).ser(writer);
+        UnsignedVariableInteger::<5>::new(suffix as u64).ser(writer);
+
+        let changed = &current[prefix..current.len() - suffix];
+        let baseline_changed = &baseline[prefix..baseline.len() - suffix];
+        UnsignedVariableInteger::<5>::new(changed.len() as u64).ser(writer);
+        for (index, item) in changed.iter().enumerate() {
+            match baseline_changed.get(index) {
+                // an element in place of one of the baseline's
+                Some(baseline_item) => {
+                    if item == baseline_item {
+                        false.ser(writer);
+                    } else {
+                        true.ser(writer);
+                        item.ser_delta(baseline_item, writer);
+                    }
+                }
+                // an inserted element
+                None => {
+                    item.ser(writer);
+                }
+            }
+        }
+    }
+
+    /// Given a cursor into incoming packet data, initializes the PropertyVec
+    /// with the synced value
+    pub fn new_read(reader: &mut BitReader, mutator_index: u8) -> Result<Self, SerdeErr> {
+        let inner = Vec::<T>::de(reader)?;
+
+        Ok(PropertyVec::<T> {
+            inner,
+            mutator: None,
+            mutator_index,
+        })
+    }
+
+    /// Given a cursor into incoming packet data, initializes the PropertyVec
+    /// with the synced value, which was written as a change from the given
+    /// baseline
+    pub fn new_read_delta(
+        baseline: &PropertyVec<T>,
+        reader: &mut BitReader,
+        mutator_index: u8,
+    ) -> Result<Self, SerdeErr> {
+        let baseline = &baseline.inner;
+
+        let prefix = UnsignedVariableInteger::<5>::de(reader)?.get() as usize;
+        let suffix = UnsignedVariableInteger::<5>::de(reader)?.get() as usize;
+        match prefix.checked_add(suffix) {
+            Some(unchanged_length) if unchanged_length <= baseline.len() => {}
+            _ => return Err(SerdeErr),
+        }
+        let baseline_changed = &baseline[prefix..baseline.len() - suffix];
+        let changed_length = UnsignedVariableInteger::<5>::de(reader)?.get() as usize;
+
+        // the changed length comes from the remote host, so the list only
+        // grows as its elements are actually read
+        let mut inner = Vec::with_capacity(prefix + suffix);
+        inner.extend_from_slice(&baseline[..prefix]);
+        for index in 0..changed_length {
+            let item = match baseline_changed.get(index) {
+                Some(baseline_item) => {
+                    if bool::de(reader)? {
+                        T::de_delta(baseline_item, reader)?
+                    } else {
+                        baseline_item.clone()
+                    }
+                }
+                None => T::de(reader)?,
+            };
+            inner.push(item);
+        }
+        inner.extend_from_slice(&baseline[baseline.len() - suffix..]);
+
+        Ok(PropertyVec::<T> {
+            inner,
+            mutator: None,
+            mutator_index,
+        })
+    }
+
+    /// Reads from a stream and immediately writes to a stream
+    /// Used to buffer updates for later
+    pub fn read_write(reader: &mut BitReader, writer: &mut BitWriter) -> Result<(), SerdeErr> {
+        Vec::<T>::de(reader)?.ser(writer);
+        Ok(())
+    }
+
+    /// Given a cursor into incoming packet data, updates the PropertyVec with
+    /// the synced value. If the PropertyVec is itself being replicated
+    /// onwards, the new value is marked to be sent
+    pub fn read(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.inner = Vec::<T>::de(reader)?;
+        if let Some(mutator) = &mut self.mutator {
+            mutator.mutate(self.mutator_index);
+        }
+        Ok(())
+    }
+
+    // Comparison
+
+    /// Compare to another property
+    pub fn equals(&self, other: &PropertyVec<T>) -> bool {
+        self.inner == other.inner
+    }
+
+    // Internal
+
+    /// Set an PropertyMutator to track changes to the PropertyVec
+    pub fn set_mutator(&mut self, mutator: &PropertyMutator) {
+        self.mutator = Some(mutator.clone_new());
+    }
+}
+
+impl<T: Serde> Deref for PropertyVec<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl<T: Serde> DerefMut for PropertyVec<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // Just assume inner value will be changed, queue for update
+        if let Some(mutator) = &mut self.mutator {
+            mutator.mutate(self.mutator_index);
+        }
+        &mut self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use naia_serde::{BitReader, BitWrite, BitWriter, Serde, UnsignedVariableInteger};
+
+    use super::PropertyVec;
+
+    fn delta(baseline: Vec<u16>, current: Vec<u16>) -> (Vec<u16>, u16) {
+        let baseline = PropertyVec::new(baseline, 0);
+        let current = PropertyVec::new(current, 0);
+
+        let mut writer = BitWriter::new();
+        current.write_delta(&baseline, &mut writer);
+        let bit_count = writer.bit_count();
+        let (length, buffer) = writer.flush();
+
+        let received =
+            PropertyVec::new_read_delta(&baseline, &mut BitReader::new(&buffer[..length]), 0)
+                .expect("unable to read delta");
+        ((*received).clone(), bit_count)
+    }
+
+    #[test]
+    fn inserted_and_removed_elements_are_sent_alone() {
+        let baseline = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let (_, full_bits) = delta(vec![], baseline.clone());
+
+        let (received, bits) = delta(baseline.clone(), vec![1, 2, 3, 9, 4, 5, 6, 7, 8]);
+        assert_eq!(received, vec![1, 2, 3, 9, 4, 5, 6, 7, 8]);
+        assert!(bits < full_bits / 2);
+
+        let (received, bits) = delta(baseline.clone(), vec![2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(received, vec![2, 3, 4, 5, 6, 7, 8]);
+        assert!(bits < full_bits / 2);
+
+        let (received, _) = delta(baseline.clone(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(received, vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+
+        let (received, _) = delta(baseline, vec![]);
+        assert!(received.is_empty());
+    }
+
+    #[test]
+    fn changed_elements_are_sent_alone() {
+        let baseline = vec![1, 2, 3, 4, 5, 6, 7, 8];
+        let (_, full_bits) = delta(vec![], baseline.clone());
+
+        let (received, bits) = delta(baseline.clone(), vec![1, 20, 3, 4, 5, 6, 70, 8]);
+        assert_eq!(received, vec![1, 20, 3, 4, 5, 6, 70, 8]);
+        assert!(bits < full_bits / 2);
+
+        let (received, _) = delta(baseline.clone(), baseline.clone());
+        assert_eq!(received, baseline);
+    }
+
+    #[test]
+    fn oversized_lengths_are_rejected() {
+        let baseline = PropertyVec::new(vec![1u16, 2, 3], 0);
+
+        // a changed length far past what the packet holds
+        let mut writer = BitWriter::new();
+        UnsignedVariableInteger::<5>::new(0u64).ser(&mut writer);
+        UnsignedVariableInteger::<5>::new(0u64).ser(&mut writer);
+        UnsignedVariableInteger::<5>::new(u64::MAX).ser(&mut writer);
+        let (length, buffer) = writer.flush();
+        assert!(
+            PropertyVec::new_read_delta(&baseline, &mut BitReader::new(&buffer[..length]), 0)
+                .is_err()
+        );
+
+        // unchanged lengths which overflow when added
+        let mut writer = BitWriter::new();
+        UnsignedVariableInteger::<5>::new(u64::MAX).ser(&mut writer);
+        UnsignedVariableInteger::<5>::new(u64::MAX).ser(&mut writer);
+        let (length, buffer) = writer.flush();
+        assert!(
+            PropertyVec::new_read_delta(&baseline, &mut BitReader::new(&buffer[..length]), 0)
+                .is_err()
+        );
+    }
+}
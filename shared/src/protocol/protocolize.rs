@@ -18,6 +18,9 @@ pub trait Protocolize: Clone + Sized + Sync + Send + 'static {
     fn kind_of<R: ReplicateSafe<Self>>() -> Self::Kind;
     /// Get kind from a type_id
     fn type_to_kind(type_id: TypeId) -> Option<Self::Kind>;
+    /// Get a hash of the Protocol's variants and the types they hold, used
+    /// to tell whether a remote host was built with the same Protocol
+    fn protocol_fingerprint() -> u64;
     /// Read from a bit stream to create a new Replica
     fn read(
         reader: &mut BitReader,
@@ -1,4 +1,4 @@
-use naia_serde::{BitWrite, SerdeErr};
+use naia_serde::{BitReader, BitWrite, SerdeErr};
 
 use super::{
     component_update::ComponentUpdate,
@@ -42,6 +42,15 @@ pub trait ReplicateSafe<P: Protocolize>: ReplicateInner {
     /// Writes data into an outgoing byte stream, sufficient to completely
     /// recreate the Message/Component on the client
     fn write(&self, bit_writer: &mut dyn BitWrite, converter: &dyn NetEntityHandleConverter);
+    /// Writes data into an outgoing byte stream, sufficient to completely
+    /// recreate the Component on the client, but with default values in place
+    /// of the given hidden Properties
+    fn write_masked(
+        &self,
+        hidden_properties: &DiffMask,
+        bit_writer: &mut dyn BitWrite,
+        converter: &dyn NetEntityHandleConverter,
+    );
     /// Write data into an outgoing byte stream, sufficient only to update the
     /// mutated Properties of the Message/Component on the client
     fn write_update(
@@ -50,6 +59,28 @@ pub trait ReplicateSafe<P: Protocolize>: ReplicateInner {
         bit_writer: &mut dyn BitWrite,
         converter: &dyn NetEntityHandleConverter,
     );
+    /// Write data into an outgoing byte stream, sufficient to update the
+    /// Component on the client from the given baseline state of it, which the
+    /// client is known to have. Properties which differ from the baseline are
+    /// written as changes from their baseline values
+    fn write_update_delta(
+        &self,
+        baseline: &P,
+        bit_writer: &mut dyn BitWrite,
+        converter: &dyn NetEntityHandleConverter,
+    );
+    /// Returns the state the client has once it reads an update written by
+    /// `write_update_delta` against the given baseline. Properties which did
+    /// not change enough from the baseline to be written keep its values
+    fn delta_sent_state(&self, baseline: &P) -> P;
+    /// Reads data written by `write_update_delta`, where self is the baseline
+    /// state it was written against. Returns the resulting state, along with
+    /// which Properties differ from the baseline
+    fn read_update_delta(
+        &self,
+        bit_reader: &mut BitReader,
+        converter: &dyn NetEntityHandleConverter,
+    ) -> Result<(P, DiffMask), SerdeErr>;
     /// Reads data from an incoming packet, sufficient to sync the in-memory
     /// Component with it's replica on the Server
     fn read_apply_update(
@@ -57,6 +88,19 @@ pub trait ReplicateSafe<P: Protocolize>: ReplicateInner {
         converter: &dyn NetEntityHandleConverter,
         update: ComponentUpdate<P::Kind>,
     ) -> Result<(), SerdeErr>;
+    /// Reads which Properties an update from the remote host changes, without
+    /// applying it
+    fn read_update_diff_mask(
+        &self,
+        converter: &dyn NetEntityHandleConverter,
+        update: &ComponentUpdate<P::Kind>,
+    ) -> Result<DiffMask, SerdeErr>;
+    /// Returns the Properties marked with `#[replicate(owner_only)]`, which
+    /// are only replicated to the owner of the Entity
+    fn owner_only_properties(&self) -> DiffMask;
+    /// Returns the Properties marked with `#[replicate(conditional)]`, which
+    /// are only replicated to Users which the Server's property scope allows
+    fn conditional_properties(&self) -> DiffMask;
     /// Returns whether has any EntityProperties
     fn has_entity_properties(&self) -> bool;
     /// Returns a list of Entities contained within the Replica's properties
@@ -0,0 +1,56 @@
+use naia_serde::{BitReader, BitWrite, BitWriter, SerdeErr};
+
+use super::{diff_mask::DiffMask, property_mutate::PropertyMutator};
+
+/// A struct that implements ReplicateNested is a container of Properties which
+/// can itself be a Property of a Component, via a NestedProperty. Each of its
+/// Properties takes a bit of the containing Component's DiffMask, starting at
+/// the given first index, so only the Properties which have changed are synced
+pub trait ReplicateNested: Clone + Send + Sync + 'static {
+    /// The number of Properties, and so of DiffMask bits, of the struct
+    const PROPERTY_COUNT: u8;
+
+    /// Set the PropertyMutator of the containing Component, which the
+    /// Properties report changes to, starting at the given index
+    fn set_mutator(&mut self, mutator: &PropertyMutator, first_index: u8);
+    /// Sets each Property to the value of the same Property of another
+    /// instance, queueing those which change for update
+    fn mirror(&mut self, other: &Self);
+    /// Compare every Property to another instance
+    fn equals(&self, other: &Self) -> bool;
+    /// Writes the value of every Property into an outgoing byte stream
+    fn write(&self, writer: &mut dyn BitWrite);
+    /// Given a cursor into incoming packet data, initializes the struct with
+    /// the values written by `write`
+    fn new_read(reader: &mut BitReader) -> Result<Self, SerdeErr>;
+    /// Writes the Properties marked in the DiffMask into an outgoing byte
+    /// stream, each preceded by whether it was written
+    fn write_update(&self, diff_mask: &DiffMask, first_index: u8, writer: &mut dyn BitWrite);
+    /// Reads data written by `write_update`, updating the Properties which
+    /// were written
+    fn read_apply_update(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr>;
+    /// Reads data written by `write_update` and immediately writes it to
+    /// another stream. Used to buffer updates for later
+    fn read_write_update(reader: &mut BitReader, writer: &mut BitWriter) -> Result<(), SerdeErr>;
+    /// Reads data written by `write_update`, marking which Properties were
+    /// written in the DiffMask without applying them
+    fn read_update_diff_mask(
+        reader: &mut BitReader,
+        diff_mask: &mut DiffMask,
+        first_index: u8,
+    ) -> Result<(), SerdeErr>;
+    /// Writes the Properties which differ from the given baseline into an
+    /// outgoing byte stream, as changes from their baseline values
+    fn write_update_delta(&self, baseline: &Self, writer: &mut dyn BitWrite);
+    /// Returns the state the reader has once it reads data written by
+    /// `write_update_delta` against the given baseline
+    fn delta_sent_state(&self, baseline: &Self) -> Self;
+    /// Reads data written by `write_update_delta` against the given baseline,
+    /// marking which Properties differ from it in the DiffMask
+    fn new_read_update_delta(
+        baseline: &Self,
+        reader: &mut BitReader,
+        diff_mask: &mut DiffMask,
+        first_index: u8,
+    ) -> Result<Self, SerdeErr>;
+}
@@ -3,7 +3,7 @@ use std::{default::Default, time::Duration};
 use naia_socket_shared::SocketConfig;
 
 use crate::{
-    connection::compression_config::CompressionConfig,
+    connection::{compression_config::CompressionConfig, mtu_config::MtuConfig},
     messages::channel_config::{ChannelConfig, ChannelIndex, DefaultChannels},
     Channel,
 };
@@ -19,6 +19,12 @@ pub struct SharedConfig<C: ChannelIndex> {
     pub tick_interval: Option<Duration>,
     /// Configuration used to control compression parameters
     pub compression: Option<CompressionConfig>,
+    /// Configuration used to control the maximum size of packets
+    pub mtu: MtuConfig,
+    /// Whether the Server writes Component updates as changes from the last
+    /// state of the Component which each Client acknowledged receiving,
+    /// rather than resending whole Property values
+    pub delta_compression: bool,
 }
 
 impl<C: ChannelIndex> SharedConfig<C> {
@@ -35,6 +41,8 @@ impl<C: ChannelIndex> SharedConfig<C> {
             channel: channel_config,
             tick_interval,
             compression,
+            mtu: MtuConfig::default(),
+            delta_compression: false,
         }
     }
 }
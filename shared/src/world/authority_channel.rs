@@ -0,0 +1,116 @@
+use std::{collections::HashMap, mem};
+
+use naia_serde::{BitReader, BitWrite, BitWriter, Serde, SerdeErr};
+use naia_socket_shared::Instant;
+
+use crate::{
+    derive_serde, serde, ChannelReader, ChannelReceiver, ChannelSender, ChannelWriter, MessageId,
+    NetEntity, OrderedReliableReceiver, PacketIndex, PacketNotifiable, ReliableSender,
+};
+
+const RESEND_AUTHORITY_RTT_FACTOR: f32 = 1.5;
+
+// Enum used as a shared network protocol, representing changes to which host
+// has authority over an Entity spawned by the Server
+#[derive(Copy)]
+#[derive_serde]
+pub enum AuthorityAction {
+    // Client asks for authority over an Entity
+    Request,
+    // Client hands authority over an Entity back to the Server
+    Release,
+    // Server gives a Client authority over an Entity
+    Grant,
+    // Server takes authority over an Entity back from a Client
+    Revoke,
+}
+
+/// Reliably sends and receives changes of authority over Entities, in order
+pub struct AuthorityChannel {
+    sender: ReliableSender<(NetEntity, AuthorityAction)>,
+    receiver: OrderedReliableReceiver<(NetEntity, AuthorityAction)>,
+    sent_packets: HashMap<PacketIndex, Vec<MessageId>>,
+    delivered_actions: Vec<(NetEntity, AuthorityAction)>,
+}
+
+impl Default for AuthorityChannel {
+    fn default() -> Self {
+        Self {
+            sender: ReliableSender::new(RESEND_AUTHORITY_RTT_FACTOR),
+            receiver: OrderedReliableReceiver::default(),
+            sent_packets: HashMap::new(),
+            delivered_actions: Vec::new(),
+        }
+    }
+}
+
+impl AuthorityChannel {
+    // Outgoing
+
+    pub fn send_action(&mut self, net_entity: NetEntity, action: AuthorityAction) {
+        self.sender.send_message((net_entity, action));
+    }
+
+    pub fn collect_outgoing_actions(&mut self, now: &Instant, rtt_millis: &f32) {
+        self.sender.collect_messages(now, rtt_millis);
+    }
+
+    pub fn has_outgoing_actions(&self) -> bool {
+        self.sender.has_messages()
+    }
+
+    pub fn write_actions(&mut self, writer: &mut BitWriter, packet_index: &PacketIndex) {
+        if let Some(action_ids) = self.sender.write_messages(&AuthorityActionIo, writer) {
+            if !action_ids.is_empty() {
+                self.sent_packets.insert(*packet_index, action_ids);
+            }
+        }
+    }
+
+    /// Returns all sent actions which the remote host has received since the
+    /// last call
+    pub fn take_delivered_actions(&mut self) -> Vec<(NetEntity, AuthorityAction)> {
+        mem::take(&mut self.delivered_actions)
+    }
+
+    // Incoming
+
+    pub fn read_actions(&mut self, reader: &mut BitReader) -> Result<(), SerdeErr> {
+        self.receiver.read_messages(&AuthorityActionIo, reader)
+    }
+
+    pub fn receive_actions(&mut self) -> Vec<(NetEntity, AuthorityAction)> {
+        self.receiver.receive_messages()
+    }
+}
+
+impl PacketNotifiable for AuthorityChannel {
+    fn notify_packet_delivered(&mut self, packet_index: PacketIndex) {
+        if let Some(action_ids) = self.sent_packets.remove(&packet_index) {
+            for action_id in action_ids {
+                if let Some(action) = self.sender.deliver_message(&action_id) {
+                    self.delivered_actions.push(action);
+                }
+            }
+        }
+    }
+}
+
+// AuthorityActionIo
+struct AuthorityActionIo;
+
+impl ChannelWriter<(NetEntity, AuthorityAction)> for AuthorityActionIo {
+    fn write(&self, writer: &mut dyn BitWrite, data: &(NetEntity, AuthorityAction)) {
+        let (net_entity, action) = data;
+        net_entity.ser(writer);
+        action.ser(writer);
+    }
+}
+
+impl ChannelReader<(NetEntity, AuthorityAction)> for AuthorityActionIo {
+    fn read(&self, reader: &mut BitReader) -> Result<(NetEntity, AuthorityAction), SerdeErr> {
+        let net_entity = NetEntity::de(reader)?;
+        let action = AuthorityAction::de(reader)?;
+        Ok((net_entity, action))
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+use crate::{sequence_greater_than, PacketIndex, ProtocolKindType};
+
+// How many received states of each Component are kept, for the sender to
+// write updates against
+const RECEIVED_BASELINES_LENGTH: usize = 32;
+
+/// Keeps track of the states of Components written to the remote host, and
+/// the most recent of those which the remote host is known to have received,
+/// which further updates can be written as changes from
+pub struct SentBaselines<E: Copy + Eq + Hash, K: ProtocolKindType, S> {
+    acked: HashMap<(E, K), (PacketIndex, S)>,
+    #[allow(clippy::type_complexity)]
+    sent: HashMap<PacketIndex, Vec<((E, K), S)>>,
+    // the packets each Component was sent in since its baseline, which the
+    // remote host may have received, pushing the baseline out of those it
+    // keeps
+    sent_since_acked: HashMap<(E, K), VecDeque<PacketIndex>>,
+}
+
+impl<E: Copy + Eq + Hash, K: ProtocolKindType, S> Default for SentBaselines<E, K, S> {
+    fn default() -> Self {
+        Self {
+            acked: HashMap::new(),
+            sent: HashMap::new(),
+            sent_since_acked: HashMap::new(),
+        }
+    }
+}
+
+impl<E: Copy + Eq + Hash, K: ProtocolKindType, S> SentBaselines<E, K, S> {
+    /// Returns the most recent state of a Component the remote host has
+    /// received, along with the index of the packet it was sent in. Returns
+    /// None if so many states have been sent since that the remote host may
+    /// no longer keep it
+    pub fn baseline(&self, entity: &E, component_kind: &K) -> Option<(PacketIndex, &S)> {
+        let key = (*entity, *component_kind);
+        if let Some(sent_since) = self.sent_since_acked.get(&key) {
+            if sent_since.len() >= RECEIVED_BASELINES_LENGTH {
+                return None;
+            }
+        }
+        self.acked
+            .get(&key)
+            .map(|(packet_index, state)| (*packet_index, state))
+    }
+
+    pub fn record_sent(
+        &mut self,
+        packet_index: &PacketIndex,
+        entity: &E,
+        component_kind: &K,
+        state: S,
+    ) {
+        let key = (*entity, *component_kind);
+        self.sent
+            .entry(*packet_index)
+            .or_default()
+            .push((key, state));
+
+        let sent_since = self.sent_since_acked.entry(key).or_default();
+        if sent_since.len() >= RECEIVED_BASELINES_LENGTH {
+            sent_since.pop_front();
+        }
+        sent_since.push_back(*packet_index);
+    }
+
+    pub fn notify_packet_delivered(&mut self, packet_index: &PacketIndex) {
+        if let Some(states) = self.sent.remove(packet_index) {
+            for (key, state) in states {
+                let is_newer = match self.acked.get(&key) {
+                    Some((acked_index, _)) => sequence_greater_than(*packet_index, *acked_index),
+                    None => true,
+                };
+                if is_newer {
+                    self.acked.insert(key, (*packet_index, state));
+                    if let Some(sent_since) = self.sent_since_acked.get_mut(&key) {
+                        sent_since
+                            .retain(|sent_index| sequence_greater_than(*sent_index, *packet_index));
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn notify_packet_dropped(&mut self, packet_index: &PacketIndex) {
+        self.sent.remove(packet_index);
+    }
+
+    pub fn remove_component(&mut self, entity: &E, component_kind: &K) {
+        let key = (*entity, *component_kind);
+        self.acked.remove(&key);
+        self.sent_since_acked.remove(&key);
+        for states in self.sent.values_mut() {
+            states.retain(|(sent_key, _)| *sent_key != key);
+        }
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        self.acked
+            .retain(|(acked_entity, _), _| acked_entity != entity);
+        self.sent_since_acked
+            .retain(|(sent_entity, _), _| sent_entity != entity);
+        for states in self.sent.values_mut() {
+            states.retain(|((sent_entity, _), _)| sent_entity != entity);
+        }
+    }
+}
+
+/// Keeps the recent states of each Component received from the remote host,
+/// which it may write further updates against
+pub struct ReceivedBaselines<E: Copy + Eq + Hash, K: ProtocolKindType, S> {
+    received: HashMap<(E, K), VecDeque<(PacketIndex, S)>>,
+}
+
+impl<E: Copy + Eq + Hash, K: ProtocolKindType, S> Default for ReceivedBaselines<E, K, S> {
+    fn default() -> Self {
+        Self {
+            received: HashMap::new(),
+        }
+    }
+}
+
+impl<E: Copy + Eq + Hash, K: ProtocolKindType, S> ReceivedBaselines<E, K, S> {
+    /// Returns the state of a Component which was received in the given
+    /// packet, if it has been kept
+    pub fn baseline(
+        &self,
+        entity: &E,
+        component_kind: &K,
+        packet_index: &PacketIndex,
+    ) -> Option<&S> {
+        self.received
+            .get(&(*entity, *component_kind))?
+            .iter()
+            .find(|(received_index, _)| received_index == packet_index)
+            .map(|(_, state)| state)
+    }
+
+    pub fn insert(&mut self, entity: &E, component_kind: &K, packet_index: &PacketIndex, state: S) {
+        let states = self.received.entry((*entity, *component_kind)).or_default();
+        states.retain(|(received_index, _)| received_index != packet_index);
+        if states.len() >= RECEIVED_BASELINES_LENGTH {
+            states.pop_front();
+        }
+        states.push_back((*packet_index, state));
+    }
+
+    pub fn remove_component(&mut self, entity: &E, component_kind: &K) {
+        self.received.remove(&(*entity, *component_kind));
+    }
+
+    pub fn remove_entity(&mut self, entity: &E) {
+        self.received
+            .retain(|(received_entity, _), _| received_entity != entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::any::TypeId;
+
+    use crate::{derive_serde, serde, ProtocolKindType};
+
+    use super::{ReceivedBaselines, SentBaselines, RECEIVED_BASELINES_LENGTH};
+
+    #[derive(Copy, Eq, Hash)]
+    #[derive_serde]
+    enum TestKind {
+        Position,
+    }
+
+    impl ProtocolKindType for TestKind {
+        fn to_type_id(&self) -> TypeId {
+            TypeId::of::<()>()
+        }
+    }
+
+    #[test]
+    fn baseline_advances_to_newest_delivered_state() {
+        let mut baselines = SentBaselines::<u32, TestKind, u16>::default();
+
+        baselines.record_sent(&1, &7, &TestKind::Position, 10);
+        baselines.record_sent(&2, &7, &TestKind::Position, 20);
+        assert!(baselines.baseline(&7, &TestKind::Position).is_none());
+
+        // packets may be acknowledged out of order
+        baselines.notify_packet_delivered(&2);
+        baselines.notify_packet_delivered(&1);
+        assert_eq!(baselines.baseline(&7, &TestKind::Position), Some((2, &20)));
+
+        // dropped states never become the baseline
+        baselines.record_sent(&3, &7, &TestKind::Position, 30);
+        baselines.notify_packet_dropped(&3);
+        baselines.notify_packet_delivered(&3);
+        assert_eq!(baselines.baseline(&7, &TestKind::Position), Some((2, &20)));
+
+        // states sent before a Component was removed are forgotten
+        baselines.record_sent(&4, &7, &TestKind::Position, 40);
+        baselines.remove_component(&7, &TestKind::Position);
+        baselines.notify_packet_delivered(&4);
+        assert!(baselines.baseline(&7, &TestKind::Position).is_none());
+    }
+
+    #[test]
+    fn baselines_the_remote_host_may_have_forgotten_are_not_used() {
+        let mut baselines = SentBaselines::<u32, TestKind, u16>::default();
+
+        baselines.record_sent(&1, &7, &TestKind::Position, 10);
+        baselines.notify_packet_delivered(&1);
+        assert_eq!(baselines.baseline(&7, &TestKind::Position), Some((1, &10)));
+
+        // the remote host keeps only so many newer states, acknowledged or not
+        let newer_states = RECEIVED_BASELINES_LENGTH as u16;
+        for packet_index in 2..(newer_states + 1) {
+            baselines.record_sent(&packet_index, &7, &TestKind::Position, packet_index);
+        }
+        assert_eq!(baselines.baseline(&7, &TestKind::Position), Some((1, &10)));
+        baselines.record_sent(&(newer_states + 1), &7, &TestKind::Position, 0);
+        assert!(baselines.baseline(&7, &TestKind::Position).is_none());
+
+        // until a newer state is acknowledged
+        baselines.notify_packet_delivered(&30);
+        assert_eq!(baselines.baseline(&7, &TestKind::Position), Some((30, &30)));
+    }
+
+    #[test]
+    fn received_baselines_are_kept_per_packet() {
+        let mut baselines = ReceivedBaselines::<u32, TestKind, u16>::default();
+
+        baselines.insert(&7, &TestKind::Position, &1, 10);
+        baselines.insert(&7, &TestKind::Position, &2, 20);
+        assert_eq!(baselines.baseline(&7, &TestKind::Position, &1), Some(&10));
+        assert_eq!(baselines.baseline(&7, &TestKind::Position, &2), Some(&20));
+        assert!(baselines.baseline(&7, &TestKind::Position, &3).is_none());
+
+        baselines.remove_entity(&7);
+        assert!(baselines.baseline(&7, &TestKind::Position, &1).is_none());
+    }
+}
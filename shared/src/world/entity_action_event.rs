@@ -1,4 +1,4 @@
-use naia_shared::ProtocolKindType;
+use crate::protocol::protocolize::ProtocolKindType;
 
 #[derive(Clone, PartialEq, Eq)]
 pub enum EntityActionEvent<E: Copy, K: ProtocolKindType> {
@@ -1,17 +1,20 @@
-use naia_shared::{ChannelIndex, KeyGenerator, MessageManager, Protocolize};
 use std::{
     collections::{HashMap, HashSet},
     hash::Hash,
 };
 
-type MessageHandle = u16;
+use naia_socket_shared::Instant;
+
+use crate::{ChannelIndex, KeyGenerator, MessageManager, Protocolize};
+
+type MessageHandle = u32;
 
 pub struct EntityMessageWaitlist<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> {
     message_handle_store: KeyGenerator<MessageHandle>,
-    messages: HashMap<MessageHandle, (Vec<E>, C, P)>,
+    messages: HashMap<MessageHandle, (Vec<E>, C, P, Option<Instant>)>,
     waiting_entities: HashMap<E, HashSet<MessageHandle>>,
     in_scope_entities: HashSet<E>,
-    ready_messages: Vec<(C, P)>,
+    ready_messages: Vec<(C, P, Option<Instant>)>,
 }
 
 impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Default
@@ -29,7 +32,13 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> Default
 }
 
 impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> EntityMessageWaitlist<P, E, C> {
-    pub fn queue_message(&mut self, entities: Vec<E>, channel: C, message: P) {
+    pub fn queue_message(
+        &mut self,
+        entities: Vec<E>,
+        channel: C,
+        message: P,
+        expires_at: Option<Instant>,
+    ) {
         let new_handle = self.message_handle_store.generate();
 
         for entity in &entities {
@@ -42,7 +51,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> EntityMessageWaitlist
         }
 
         self.messages
-            .insert(new_handle, (entities, channel, message));
+            .insert(new_handle, (entities, channel, message, expires_at));
     }
 
     pub fn add_entity(&mut self, entity: &E) {
@@ -54,7 +63,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> EntityMessageWaitlist
 
         if let Some(message_set) = self.waiting_entities.get_mut(entity) {
             for message_handle in message_set.iter() {
-                if let Some((entities, _, _)) = self.messages.get(message_handle) {
+                if let Some((entities, _, _, _)) = self.messages.get(message_handle) {
                     if entities
                         .iter()
                         .all(|entity| self.in_scope_entities.contains(entity))
@@ -67,11 +76,11 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> EntityMessageWaitlist
 
         // get the messages ready to send, also clean up
         for outgoing_message_handle in outgoing_message_handles {
-            let (entities, channel, message) =
+            let (entities, channel, message, expires_at) =
                 self.messages.remove(&outgoing_message_handle).unwrap();
 
             // push outgoing message
-            self.ready_messages.push((channel, message));
+            self.ready_messages.push((channel, message, expires_at));
 
             // recycle message handle
             self.message_handle_store
@@ -98,8 +107,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash, C: ChannelIndex> EntityMessageWaitlist
     }
 
     pub fn collect_ready_messages(&mut self, message_manager: &mut MessageManager<P, C>) {
-        for (channel, message) in self.ready_messages.drain(..) {
-            message_manager.send_message(channel, message);
+        for (channel, message, expires_at) in self.ready_messages.drain(..) {
+            message_manager.send_message_with_expiry_opt(channel, message, expires_at);
         }
     }
 }
@@ -7,58 +7,76 @@ use std::{
     time::Duration,
 };
 
-use naia_shared::{
-    message_list_header,
-    serde::{BitCounter, BitWrite, BitWriter, Serde, UnsignedVariableInteger},
-    wrapping_diff, ChannelIndex, DiffMask, EntityAction, EntityActionType, EntityConverter,
-    Instant, MessageId, MessageManager, NetEntity, NetEntityConverter, PacketIndex,
-    PacketNotifiable, Protocolize, ReplicateSafe, WorldRefType, MTU_SIZE_BITS,
-};
+use naia_serde::{BitCounter, BitWrite, BitWriter, Serde, UnsignedVariableInteger};
+use naia_socket_shared::Instant;
 
-use crate::sequence_list::SequenceList;
+use crate::{
+    message_list_header, wrapping_diff, ChannelIndex, DiffMask, EntityAction, EntityActionType,
+    EntityConverter, EntityHandleConverter, MessageId, MessageManager, NetEntity,
+    NetEntityConverter, PacketIndex, PacketNotifiable, Protocolize, ReplicateSafe, WorldRefType,
+};
 
 use super::{
-    entity_action_event::EntityActionEvent, global_diff_handler::GlobalDiffHandler,
-    world_channel::WorldChannel, world_record::WorldRecord,
+    delta_baselines::SentBaselines, entity_action_event::EntityActionEvent,
+    global_diff_handler::GlobalDiffHandler, sequence_list::SequenceList,
+    world_channel::WorldChannel,
 };
 
 const DROP_UPDATE_RTT_FACTOR: f32 = 1.5;
-const ACTION_RECORD_TTL: Duration = Duration::from_secs(60);
+// How long records of sent actions are kept, waiting to hear whether the
+// packets which carried them were delivered
+pub(crate) const ACTION_RECORD_TTL: Duration = Duration::from_secs(60);
 
 pub type ActionId = MessageId;
 
-/// Manages Entities for a given Client connection and keeps them in
-/// sync on the Client
-pub struct EntityManager<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> {
+/// Manages the Entities which are replicated over a given connection, and
+/// keeps them in sync on the remote host
+pub struct HostWorldManager<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> {
     // World
     world_channel: WorldChannel<P, E, C>,
     next_send_actions: VecDeque<(ActionId, EntityActionEvent<E, P::Kind>)>,
     #[allow(clippy::type_complexity)]
     sent_action_packets: SequenceList<(Instant, Vec<(ActionId, EntityAction<E, P::Kind>)>)>,
 
+    entity_parents: HashMap<E, E>,
+    // Properties which must not be replicated to the remote host
+    hidden_properties: HashMap<(E, P::Kind), DiffMask>,
+
     // Updates
     next_send_updates: HashMap<E, HashSet<P::Kind>>,
     #[allow(clippy::type_complexity)]
     sent_updates: HashMap<PacketIndex, (Instant, HashMap<(E, P::Kind), DiffMask>)>,
     last_update_packet_index: PacketIndex,
+    // States of Components which updates are written as changes from, if
+    // delta compression is enabled
+    baselines: Option<SentBaselines<E, P::Kind, P>>,
 }
 
-impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityManager<P, E, C> {
-    /// Create a new NewEntityManager, given the client's address
+impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> HostWorldManager<P, E, C> {
+    /// Create a new HostWorldManager, given the remote host's address, and
+    /// whether updates should be delta-compressed
     pub fn new(
         address: SocketAddr,
         diff_handler: &Arc<RwLock<GlobalDiffHandler<E, P::Kind>>>,
+        delta_compression: bool,
     ) -> Self {
-        EntityManager {
+        HostWorldManager {
             // World
             world_channel: WorldChannel::new(address, diff_handler),
             next_send_actions: VecDeque::new(),
             sent_action_packets: SequenceList::new(),
+            entity_parents: HashMap::new(),
+            hidden_properties: HashMap::new(),
 
             // Update
             next_send_updates: HashMap::new(),
             sent_updates: HashMap::new(),
             last_update_packet_index: 0,
+            baselines: if delta_compression {
+                Some(SentBaselines::default())
+            } else {
+                None
+            },
         }
     }
 
@@ -68,7 +86,20 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         self.world_channel.host_spawn_entity(entity);
     }
 
+    /// Spawns an Entity as the child of another, which the remote host
+    /// should already have
+    pub fn spawn_child_entity(&mut self, entity: &E, parent: &E) {
+        self.entity_parents.insert(*entity, *parent);
+        self.world_channel.host_spawn_entity(entity);
+    }
+
     pub fn despawn_entity(&mut self, entity: &E) {
+        self.entity_parents.remove(entity);
+        self.hidden_properties
+            .retain(|(hidden_entity, _), _| hidden_entity != entity);
+        if let Some(baselines) = &mut self.baselines {
+            baselines.remove_entity(entity);
+        }
         self.world_channel.host_despawn_entity(entity);
     }
 
@@ -77,17 +108,95 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
     }
 
     pub fn remove_component(&mut self, entity: &E, component: &P::Kind) {
+        self.hidden_properties.remove(&(*entity, *component));
+        if let Some(baselines) = &mut self.baselines {
+            baselines.remove_component(entity, component);
+        }
         self.world_channel.host_remove_component(entity, component);
     }
 
+    /// Sets which Properties of a Component are hidden from the remote host.
+    /// Hidden Properties are written with default values when the Component
+    /// is inserted, and are never updated. Properties which become visible
+    /// again are queued to be updated with their current values
+    pub fn set_hidden_properties(
+        &mut self,
+        entity: &E,
+        component: &P::Kind,
+        hidden_properties: Option<DiffMask>,
+    ) {
+        let key = (*entity, *component);
+        let old_hidden = self.hidden_properties.remove(&key);
+
+        if let Some(old_hidden) = old_hidden {
+            if self
+                .world_channel
+                .diff_handler
+                .has_component(entity, component)
+            {
+                let mut revealed = old_hidden;
+                if let Some(new_hidden) = &hidden_properties {
+                    revealed.nand(new_hidden);
+                }
+                if !revealed.is_clear() {
+                    self.world_channel
+                        .diff_handler
+                        .or_diff_mask(entity, component, &revealed);
+                }
+            }
+        }
+
+        if let Some(new_hidden) = hidden_properties {
+            if !new_hidden.is_clear() {
+                self.hidden_properties.insert(key, new_hidden);
+            }
+        }
+    }
+
     pub fn scope_has_entity(&self, entity: &E) -> bool {
         self.world_channel.host_has_entity(entity)
     }
 
+    pub fn scope_has_component(&self, entity: &E, component: &P::Kind) -> bool {
+        self.world_channel.host_has_component(entity, component)
+    }
+
     pub fn entity_channel_is_open(&self, entity: &E) -> bool {
         self.world_channel.entity_channel_is_open(entity)
     }
 
+    /// Returns the Entity associated with a NetEntity, if the Entity is in
+    /// scope for the remote host
+    pub fn net_entity_to_host_entity(&self, net_entity: &NetEntity) -> Option<E> {
+        self.world_channel.net_entity_to_entity(net_entity).copied()
+    }
+
+    /// Returns the NetEntity associated with an Entity, if the Entity is in
+    /// scope for the remote host
+    pub fn host_entity_to_net_entity(&self, entity: &E) -> Option<NetEntity> {
+        self.world_channel.entity_to_net_entity(entity).copied()
+    }
+
+    /// Marks the given Properties of a Component as being in sync with the
+    /// remote host, for example because the remote host is the one which
+    /// changed them. Other changed Properties are still sent
+    pub fn clear_diff_mask_bits(
+        &mut self,
+        entity: &E,
+        component_kind: &P::Kind,
+        diff_mask: &DiffMask,
+    ) {
+        if self
+            .world_channel
+            .diff_handler
+            .has_component(entity, component_kind)
+        {
+            self.world_channel
+                .diff_handler
+                .nand_diff_mask(entity, component_kind, diff_mask);
+        }
+    }
+
     // Messages
 
     pub fn queue_entity_message<R: ReplicateSafe<P>>(
@@ -95,11 +204,13 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         entities: Vec<E>,
         channel: C,
         message: &R,
+        expires_at: Option<Instant>,
     ) {
         self.world_channel.delayed_entity_messages.queue_message(
             entities,
             channel,
             message.protocol_copy(),
+            expires_at,
         );
     }
 
@@ -127,16 +238,34 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         !self.next_send_actions.is_empty() || !self.next_send_updates.is_empty()
     }
 
+    /// Writes the queued Entity updates & actions. Entity Properties can refer
+    /// to Entities spawned by either host, the ones spawned by the remote host
+    /// being found through `remote_converter`
     pub fn write_all<W: WorldRefType<P, E>>(
         &mut self,
         now: &Instant,
         writer: &mut BitWriter,
         packet_index: &PacketIndex,
         world: &W,
-        world_record: &WorldRecord<E, <P as Protocolize>::Kind>,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        remote_converter: &dyn NetEntityConverter<E>,
     ) {
-        self.write_updates(now, writer, packet_index, world, world_record);
-        self.write_actions(now, writer, packet_index, world, world_record);
+        self.write_updates(
+            now,
+            writer,
+            packet_index,
+            world,
+            handle_converter,
+            remote_converter,
+        );
+        self.write_actions(
+            now,
+            writer,
+            packet_index,
+            world,
+            handle_converter,
+            remote_converter,
+        );
     }
 
     // Collecting
@@ -182,6 +311,10 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
     }
 
     fn dropped_update_cleanup(&mut self, dropped_packet_index: PacketIndex) {
+        if let Some(baselines) = &mut self.baselines {
+            baselines.notify_packet_dropped(&dropped_packet_index);
+        }
+
         if let Some((_, diff_mask_map)) = self.sent_updates.remove(&dropped_packet_index) {
             for (component_index, diff_mask) in &diff_mask_map {
                 let (entity, component) = component_index;
@@ -218,6 +351,19 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
     }
 
     fn collect_component_updates(&mut self) {
+        // changes to hidden Properties are never sent
+        for ((entity, component), hidden) in &self.hidden_properties {
+            if self
+                .world_channel
+                .diff_handler
+                .has_component(entity, component)
+            {
+                self.world_channel
+                    .diff_handler
+                    .nand_diff_mask(entity, component, hidden);
+            }
+        }
+
         self.next_send_updates = self.world_channel.collect_next_updates();
     }
 
@@ -246,7 +392,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         writer: &mut BitWriter,
         packet_index: &PacketIndex,
         world: &W,
-        world_record: &WorldRecord<E, <P as Protocolize>::Kind>,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        remote_converter: &dyn NetEntityConverter<E>,
     ) {
         let mut message_count = 0;
 
@@ -254,7 +401,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         {
             // Measure
             let current_packet_size = writer.bit_count();
-            if current_packet_size > MTU_SIZE_BITS {
+            if current_packet_size > writer.max_bits() {
                 message_list_header::write(writer, 0);
                 return;
             }
@@ -263,7 +410,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
             message_list_header::write(&mut counter, 123);
 
             // Check for overflow
-            if current_packet_size + counter.bit_count() > MTU_SIZE_BITS {
+            if current_packet_size + counter.bit_count() > writer.max_bits() {
                 message_list_header::write(writer, 0);
                 return;
             }
@@ -275,14 +422,15 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
             for action_index in 0..next_send_actions_len {
                 self.write_action(
                     world,
-                    world_record,
+                    handle_converter,
+                    remote_converter,
                     packet_index,
                     &mut counter,
                     action_index,
                     &mut last_written_id,
                     false,
                 );
-                if current_packet_size + counter.bit_count() <= MTU_SIZE_BITS {
+                if current_packet_size + counter.bit_count() <= writer.max_bits() {
                     message_count += 1;
                 } else {
                     break;
@@ -309,7 +457,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
             for action_index in 0..message_count {
                 self.write_action(
                     world,
-                    world_record,
+                    handle_converter,
+                    remote_converter,
                     packet_index,
                     writer,
                     action_index,
@@ -327,7 +476,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
     fn write_action<W: WorldRefType<P, E>>(
         &mut self,
         world: &W,
-        world_record: &WorldRecord<E, <P as Protocolize>::Kind>,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        remote_converter: &dyn NetEntityConverter<E>,
         packet_index: &PacketIndex,
         bit_writer: &mut dyn BitWrite,
         action_index: usize,
@@ -349,11 +499,15 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
                     .unwrap()
                     .ser(bit_writer);
 
+                // write parent net entity
+                self.entity_parents
+                    .get(entity)
+                    .and_then(|parent| self.world_channel.entity_to_net_entity(parent))
+                    .copied()
+                    .ser(bit_writer);
+
                 // get component list
-                let component_kinds = match world_record.component_kinds(entity) {
-                    Some(kind_list) => kind_list,
-                    None => Vec::new(),
-                };
+                let component_kinds = self.world_channel.host_component_kinds(entity);
 
                 // write number of components
                 let components_num =
@@ -361,13 +515,27 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
                 components_num.ser(bit_writer);
 
                 for component_kind in &component_kinds {
-                    let converter = EntityConverter::new(world_record, self);
+                    let converter =
+                        EntityConverter::new(handle_converter, self).with_remote(remote_converter);
 
                     // write component payload
-                    world
+                    let component = world
                         .component_of_kind(entity, component_kind)
-                        .expect("Component does not exist in World")
-                        .write(bit_writer, &converter);
+                        .expect("Component does not exist in World");
+                    match self.hidden_properties.get(&(*entity, *component_kind)) {
+                        Some(hidden) => {
+                            component.write_masked(hidden, bit_writer, &converter);
+
+                            // the remote host does not have the whole state
+                            // of the Component to write changes against
+                            if is_writing {
+                                if let Some(baselines) = &mut self.baselines {
+                                    baselines.remove_component(entity, component_kind);
+                                }
+                            }
+                        }
+                        None => component.write(bit_writer, &converter),
+                    }
                 }
 
                 // if we are writing to this packet, add it to record
@@ -430,13 +598,27 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
                         .unwrap()
                         .ser(bit_writer);
 
-                    let converter = EntityConverter::new(world_record, self);
+                    let converter =
+                        EntityConverter::new(handle_converter, self).with_remote(remote_converter);
 
                     // write component payload
-                    world
+                    let component_ref = world
                         .component_of_kind(entity, component)
-                        .expect("Component does not exist in World")
-                        .write(bit_writer, &converter);
+                        .expect("Component does not exist in World");
+                    match self.hidden_properties.get(&(*entity, *component)) {
+                        Some(hidden) => {
+                            component_ref.write_masked(hidden, bit_writer, &converter);
+
+                            // the remote host does not have the whole state
+                            // of the Component to write changes against
+                            if is_writing {
+                                if let Some(baselines) = &mut self.baselines {
+                                    baselines.remove_component(entity, component);
+                                }
+                            }
+                        }
+                        None => component_ref.write(bit_writer, &converter),
+                    }
 
                     // if we are actually writing this packet
                     if is_writing {
@@ -513,7 +695,8 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         writer: &mut BitWriter,
         packet_index: &PacketIndex,
         world: &W,
-        world_record: &WorldRecord<E, <P as Protocolize>::Kind>,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        remote_converter: &dyn NetEntityConverter<E>,
     ) {
         let mut update_entities: Vec<E> = Vec::new();
 
@@ -521,16 +704,19 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         {
             // Measure
             let current_packet_size = writer.bit_count();
-            if current_packet_size > MTU_SIZE_BITS {
+            if current_packet_size > writer.max_bits() {
                 message_list_header::write(writer, 0);
                 return;
             }
 
             let mut counter = BitCounter::new();
             message_list_header::write(&mut counter, 123);
+            if self.baselines.is_some() {
+                packet_index.ser(&mut counter);
+            }
 
             // Check for overflow
-            if current_packet_size + counter.bit_count() > MTU_SIZE_BITS {
+            if current_packet_size + counter.bit_count() > writer.max_bits() {
                 message_list_header::write(writer, 0);
                 return;
             }
@@ -541,13 +727,14 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
             for update_entity in all_update_entities {
                 self.write_update(
                     world,
-                    world_record,
+                    handle_converter,
+                    remote_converter,
                     packet_index,
                     &mut counter,
                     &update_entity,
                     false,
                 );
-                if current_packet_size + counter.bit_count() <= MTU_SIZE_BITS {
+                if current_packet_size + counter.bit_count() <= writer.max_bits() {
                     update_entities.push(update_entity);
                 } else {
                     break;
@@ -558,6 +745,12 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         // Write header
         message_list_header::write(writer, update_entities.len() as u16);
 
+        // delta-compressed updates are remembered by the packet they were
+        // sent in, for later updates to be written against
+        if self.baselines.is_some() && !update_entities.is_empty() {
+            packet_index.ser(writer);
+        }
+
         if !self.sent_updates.contains_key(packet_index) {
             self.sent_updates
                 .insert(*packet_index, (now.clone(), HashMap::new()));
@@ -569,15 +762,25 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
                 // Pop message
 
                 // Write message
-                self.write_update(world, world_record, packet_index, writer, &entity, true);
+                self.write_update(
+                    world,
+                    handle_converter,
+                    remote_converter,
+                    packet_index,
+                    writer,
+                    &entity,
+                    true,
+                );
             }
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn write_update<W: WorldRefType<P, E>>(
         &mut self,
         world: &W,
-        world_record: &WorldRecord<E, <P as Protocolize>::Kind>,
+        handle_converter: &dyn EntityHandleConverter<E>,
+        remote_converter: &dyn NetEntityConverter<E>,
         packet_index: &PacketIndex,
         bit_writer: &mut dyn BitWrite,
         entity: &E,
@@ -609,6 +812,19 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
         UnsignedVariableInteger::<3>::new(component_set.len() as u64).ser(bit_writer);
 
         for component_kind in component_set {
+            // the remote host does not have the whole state of Components with
+            // hidden Properties, so those are never delta-compressed
+            let delta_compressed = match &self.baselines {
+                Some(_) => {
+                    let delta_compressed = !self
+                        .hidden_properties
+                        .contains_key(&(*entity, *component_kind));
+                    delta_compressed.ser(bit_writer);
+                    delta_compressed
+                }
+                None => false,
+            };
+
             // write component kind
             component_kind.ser(bit_writer);
 
@@ -621,12 +837,53 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
                 .clone();
 
             // write payload
+            let mut sent_state = None;
             {
-                let converter = EntityConverter::new(world_record, self);
-                world
+                let converter =
+                    EntityConverter::new(handle_converter, self).with_remote(remote_converter);
+                let component = world
                     .component_of_kind(entity, component_kind)
-                    .expect("Component does not exist in World")
-                    .write_update(&diff_mask, bit_writer, &converter);
+                    .expect("Component does not exist in World");
+                if delta_compressed {
+                    // write the changes from the last state the remote host
+                    // acknowledged, or the whole Component if there is none
+                    let baselines = self.baselines.as_ref().unwrap();
+                    match baselines.baseline(entity, component_kind) {
+                        Some((baseline_index, baseline)) => {
+                            Some(baseline_index).ser(bit_writer);
+
+                            // the length lets the remote host skip the
+                            // update, should it no longer have the baseline
+                            let mut counter = BitCounter::new();
+                            component.write_update_delta(baseline, &mut counter, &converter);
+                            UnsignedVariableInteger::<5>::new(counter.bit_count()).ser(bit_writer);
+                            component.write_update_delta(baseline, bit_writer, &converter);
+
+                            // changes too small to be written accumulate
+                            // against the baseline, rather than being lost
+                            if is_writing {
+                                sent_state = Some(component.delta_sent_state(baseline));
+                            }
+                        }
+                        None => {
+                            None::<PacketIndex>.ser(bit_writer);
+                            component.write(bit_writer, &converter);
+                            if is_writing {
+                                sent_state = Some(component.protocol_copy());
+                            }
+                        }
+                    }
+                } else {
+                    component.write_update(&diff_mask, bit_writer, &converter);
+                }
+            }
+
+            // an update written without delta compression moves the remote
+            // host past its baseline, which must not be written against later
+            if is_writing && !delta_compressed {
+                if let Some(baselines) = &mut self.baselines {
+                    baselines.remove_component(entity, component_kind);
+                }
             }
 
             ////////
@@ -639,6 +896,10 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
                 let (_, sent_updates_map) = self.sent_updates.get_mut(packet_index).unwrap();
                 sent_updates_map.insert((*entity, *component_kind), diff_mask);
 
+                if let (Some(baselines), Some(sent_state)) = (&mut self.baselines, sent_state) {
+                    baselines.record_sent(packet_index, entity, component_kind, sent_state);
+                }
+
                 // having copied the diff mask for this update, clear the component
                 self.world_channel
                     .diff_handler
@@ -650,11 +911,14 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> EntityM
 
 // PacketNotifiable
 impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> PacketNotifiable
-    for EntityManager<P, E, C>
+    for HostWorldManager<P, E, C>
 {
     fn notify_packet_delivered(&mut self, packet_index: PacketIndex) {
         // Updates
         self.sent_updates.remove(&packet_index);
+        if let Some(baselines) = &mut self.baselines {
+            baselines.notify_packet_delivered(&packet_index);
+        }
 
         // Actions
         if let Some((_, action_list)) = self
@@ -670,19 +934,13 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> PacketN
 
 // NetEntityConverter
 impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> NetEntityConverter<E>
-    for EntityManager<P, E, C>
+    for HostWorldManager<P, E, C>
 {
-    fn entity_to_net_entity(&self, entity: &E) -> NetEntity {
-        return *self
-            .world_channel
-            .entity_to_net_entity(entity)
-            .expect("entity does not exist for this connection!");
+    fn entity_to_net_entity(&self, entity: &E) -> Option<NetEntity> {
+        self.host_entity_to_net_entity(entity)
     }
 
-    fn net_entity_to_entity(&self, net_entity: &NetEntity) -> E {
-        return *self
-            .world_channel
-            .net_entity_to_entity(net_entity)
-            .expect("entity does not exist for this connection!");
+    fn net_entity_to_entity(&self, net_entity: &NetEntity) -> Option<E> {
+        self.net_entity_to_host_entity(net_entity)
     }
 }
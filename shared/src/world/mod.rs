@@ -0,0 +1,10 @@
+pub mod authority_channel;
+pub mod delta_baselines;
+pub mod entity_action_event;
+pub mod entity_message_waitlist;
+pub mod global_diff_handler;
+pub mod host_world_manager;
+pub mod mut_channel;
+pub mod sequence_list;
+pub mod user_diff_handler;
+pub mod world_channel;
@@ -4,7 +4,7 @@ use std::{
     sync::{Arc, RwLock, RwLockReadGuard},
 };
 
-use naia_shared::{DiffMask, PropertyMutate};
+use crate::{DiffMask, PropertyMutate};
 
 // MutChannel
 #[derive(Clone)]
@@ -116,6 +116,12 @@ impl MutReceiver {
         }
     }
 
+    pub fn nand_mask(&self, other_mask: &DiffMask) {
+        if let Ok(mut mask) = self.mask.as_ref().write() {
+            mask.nand(other_mask);
+        }
+    }
+
     pub fn clear_mask(&self) {
         if let Ok(mut mask) = self.mask.as_ref().write() {
             mask.clear();
@@ -5,7 +5,7 @@ use std::{
     sync::{Arc, RwLock, RwLockReadGuard},
 };
 
-use naia_shared::{DiffMask, ProtocolKindType};
+use crate::{DiffMask, ProtocolKindType};
 
 use super::{global_diff_handler::GlobalDiffHandler, mut_channel::MutReceiver};
 
@@ -65,6 +65,11 @@ impl<E: Copy + Eq + Hash, K: ProtocolKindType> UserDiffHandler<E, K> {
         current_diff_mask.or_mask(other_mask);
     }
 
+    pub fn nand_diff_mask(&mut self, entity: &E, component_kind: &K, other_mask: &DiffMask) {
+        let current_diff_mask = self.receivers.get_mut(&(*entity, *component_kind)).unwrap();
+        current_diff_mask.nand_mask(other_mask);
+    }
+
     pub fn clear_diff_mask(&mut self, entity: &E, component_kind: &K) {
         let receiver = self.receivers.get_mut(&(*entity, *component_kind)).unwrap();
         receiver.clear_mask();
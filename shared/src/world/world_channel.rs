@@ -3,23 +3,31 @@ use std::{
     hash::Hash,
     net::SocketAddr,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
-use naia_shared::{
+use naia_socket_shared::Instant;
+
+use crate::{
     ChannelIndex, ChannelSender, EntityAction, EntityActionReceiver, KeyGenerator, NetEntity,
     ProtocolKindType, Protocolize, ReliableSender,
 };
 
-use crate::{
-    protocol::{
-        entity_action_event::EntityActionEvent, entity_manager::ActionId,
-        entity_message_waitlist::EntityMessageWaitlist, global_diff_handler::GlobalDiffHandler,
-        user_diff_handler::UserDiffHandler,
-    },
-    server::Instant,
+use super::{
+    entity_action_event::EntityActionEvent,
+    entity_message_waitlist::EntityMessageWaitlist,
+    global_diff_handler::GlobalDiffHandler,
+    host_world_manager::{ActionId, ACTION_RECORD_TTL},
+    user_diff_handler::UserDiffHandler,
 };
 
 const RESEND_ACTION_RTT_FACTOR: f32 = 1.5;
+// A despawned Entity's NetEntity is only recycled once the remote host has
+// acknowledged the despawn, so every action which references it has been
+// received. It is then held back from reuse for as long as records of sent
+// actions are kept, so that no update packet still in flight which references
+// it can be mistaken for the new Entity
+const NET_ENTITY_RECYCLE_TIMEOUT: Duration = ACTION_RECORD_TTL;
 
 // ComponentChannel
 
@@ -68,7 +76,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> WorldCh
 
             address,
             diff_handler: UserDiffHandler::new(diff_handler),
-            net_entity_generator: KeyGenerator::default(),
+            net_entity_generator: KeyGenerator::new(NET_ENTITY_RECYCLE_TIMEOUT),
             net_entity_to_entity_map: HashMap::new(),
             entity_to_net_entity_map: HashMap::new(),
             delayed_entity_messages: EntityMessageWaitlist::default(),
@@ -81,6 +89,20 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> WorldCh
         self.host_world.contains_key(entity)
     }
 
+    pub fn host_has_component(&self, entity: &E, component: &P::Kind) -> bool {
+        match self.host_world.get(entity) {
+            Some(components) => components.contains(component),
+            None => false,
+        }
+    }
+
+    pub fn host_component_kinds(&self, entity: &E) -> Vec<P::Kind> {
+        match self.host_world.get(entity) {
+            Some(components) => components.inner.iter().copied().collect(),
+            None => Vec::new(),
+        }
+    }
+
     pub fn entity_channel_is_open(&self, entity: &E) -> bool {
         matches!(
             self.entity_channels.get(entity),
@@ -369,6 +391,7 @@ impl<P: Protocolize, E: Copy + Eq + Hash + Send + Sync, C: ChannelIndex> WorldCh
         self.delayed_entity_messages.remove_entity(entity);
     }
 
+    // only called once the remote host has acknowledged the despawn
     fn on_entity_channel_closed(&mut self, entity: &E) {
         // cleanup net entity
         let net_entity = self.entity_to_net_entity_map.remove(entity).unwrap();
@@ -7,7 +7,7 @@ use crate::{
         replica_ref::{ReplicaDynRefWrapper, ReplicaMutWrapper, ReplicaRefWrapper},
         replicate::ReplicateSafe,
     },
-    ComponentUpdate, Replicate,
+    ComponentUpdate, PropertyMutator, Replicate,
 };
 
 /// Structures that implement the WorldMutType trait will be able to be loaded
@@ -68,6 +68,14 @@ pub trait WorldMutType<P: Protocolize, E>: WorldRefType<P, E> + ProtocolInserter
         component_kind: &P::Kind,
         update: ComponentUpdate<P::Kind>,
     ) -> Result<(), SerdeErr>;
+    /// sets the mutator of a component, so that changes to its Properties are
+    /// tracked for replication
+    fn component_set_mutator(
+        &mut self,
+        entity: &E,
+        component_kind: &P::Kind,
+        mutator: &PropertyMutator,
+    );
     /// mirrors the whole state of two different entities
     /// (setting 1st entity's component to 2nd entity's component's state)
     fn mirror_entities(&mut self, mutable_entity: &E, immutable_entity: &E);
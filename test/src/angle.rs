@@ -0,0 +1,37 @@
+use naia_shared::{
+    serde::{BitReader, BitWrite, Serde, SerdeErr, SignedVariableInteger},
+    PropertySerde,
+};
+
+/// An angle in degrees, which is synced to a tenth of a degree
+#[derive(Clone, Copy, Debug)]
+pub struct Angle(pub f32);
+
+impl Angle {
+    fn tenths(&self) -> i16 {
+        (self.0 * 10.0).round() as i16
+    }
+}
+
+impl PropertySerde for Angle {
+    fn write(&self, writer: &mut dyn BitWrite) {
+        self.tenths().ser(writer);
+    }
+
+    fn read(reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        Ok(Angle(i16::de(reader)? as f32 / 10.0))
+    }
+
+    fn write_delta(&self, baseline: &Self, writer: &mut dyn BitWrite) {
+        SignedVariableInteger::<4>::new(self.tenths() - baseline.tenths()).ser(writer);
+    }
+
+    fn read_delta(baseline: &Self, reader: &mut BitReader) -> Result<Self, SerdeErr> {
+        let change = SignedVariableInteger::<4>::de(reader)?.get() as i16;
+        Ok(Angle((baseline.tenths() + change) as f32 / 10.0))
+    }
+
+    fn has_changed(&self, previous: &Self) -> bool {
+        self.tenths() != previous.tenths()
+    }
+}
@@ -0,0 +1,15 @@
+use naia_shared::{Property, Replicate};
+
+use crate::angle::Angle;
+
+#[derive(Replicate)]
+#[protocol_path = "crate::protocol::Protocol"]
+pub struct Heading {
+    pub angle: Property<Angle>,
+}
+
+impl Heading {
+    pub fn new(angle: f32) -> Self {
+        Heading::new_complete(Angle(angle))
+    }
+}
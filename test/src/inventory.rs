@@ -0,0 +1,15 @@
+use naia_shared::{PropertyMap, PropertyVec, Replicate};
+
+#[derive(Replicate)]
+#[protocol_path = "crate::protocol::Protocol"]
+pub struct Inventory {
+    pub items: PropertyVec<u16>,
+    pub counts: PropertyMap<u16, u8>,
+}
+
+impl Inventory {
+    pub fn new(items: Vec<u16>) -> Self {
+        let counts = items.iter().map(|item| (*item, 1)).collect();
+        Inventory::new_complete(items, counts)
+    }
+}
@@ -1,5 +1,17 @@
+mod angle;
 mod auth;
+mod heading;
+mod inventory;
+mod position;
 mod protocol;
+mod stats;
+mod transform;
 
+pub use angle::Angle;
 pub use auth::Auth;
+pub use heading::Heading;
+pub use inventory::{Inventory, InventoryProperty};
+pub use position::Position;
 pub use protocol::{Protocol, ProtocolKind};
+pub use stats::{Stats, StatsProperty};
+pub use transform::{Transform, TransformProperty, Vector};
@@ -0,0 +1,27 @@
+use naia_client::{Interpolate, Predict};
+use naia_shared::{Property, Replicate};
+
+#[derive(Replicate)]
+#[protocol_path = "crate::protocol::Protocol"]
+pub struct Position {
+    pub x: Property<i16>,
+}
+
+impl Position {
+    pub fn new(x: i16) -> Self {
+        Position::new_complete(x)
+    }
+}
+
+impl Interpolate<Protocol> for Position {
+    fn interpolate(&self, next: &Self, fraction: f32) -> Self {
+        let x = *self.x as f32 + (*next.x - *self.x) as f32 * fraction;
+        Position::new(x.round() as i16)
+    }
+}
+
+impl Predict<Protocol> for Position {
+    fn mispredicted(&self, authoritative: &Self) -> bool {
+        *self.x != *authoritative.x
+    }
+}
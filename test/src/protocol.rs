@@ -1,8 +1,16 @@
 use naia_shared::Protocolize;
 
-use super::auth::Auth;
+use super::{
+    auth::Auth, heading::Heading, inventory::Inventory, position::Position, stats::Stats,
+    transform::Transform,
+};
 
 #[derive(Protocolize)]
 pub enum Protocol {
     Auth(Auth),
+    Stats(Stats),
+    Position(Position),
+    Transform(Transform),
+    Inventory(Inventory),
+    Heading(Heading),
 }
@@ -0,0 +1,17 @@
+use naia_shared::{Property, Replicate};
+
+#[derive(Replicate)]
+#[protocol_path = "crate::protocol::Protocol"]
+pub struct Stats {
+    pub health: Property<u16>,
+    #[replicate(owner_only)]
+    pub mana: Property<u16>,
+    #[replicate(conditional)]
+    pub target: Property<u16>,
+}
+
+impl Stats {
+    pub fn new(health: u16, mana: u16, target: u16) -> Self {
+        Stats::new_complete(health, mana, target)
+    }
+}
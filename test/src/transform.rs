@@ -0,0 +1,26 @@
+use naia_shared::{NestedProperty, Property, Replicate, ReplicateNested};
+
+#[derive(ReplicateNested)]
+pub struct Vector {
+    pub x: Property<i16>,
+    pub y: Property<i16>,
+}
+
+impl Vector {
+    pub fn new(x: i16, y: i16) -> Self {
+        Vector::new_complete(x, y)
+    }
+}
+
+#[derive(Replicate)]
+#[protocol_path = "crate::protocol::Protocol"]
+pub struct Transform {
+    pub translation: NestedProperty<Vector>,
+    pub rotation: Property<i16>,
+}
+
+impl Transform {
+    pub fn new(translation: Vector, rotation: i16) -> Self {
+        Transform::new_complete(translation, rotation)
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use naia_shared::{
+    serde::{BitReader, BitWriter, OwnedBitReader},
+    AuthorityAction, AuthorityChannel, ComponentUpdate, DiffMask, FakeEntityConverter,
+    GlobalDiffHandler, Instant, NetEntity, PacketNotifiable, PropertyMutator, Protocolize,
+    ReplicateSafe, UserDiffHandler,
+};
+use naia_test::{Protocol, Stats, StatsProperty};
+
+#[test]
+fn authority_actions_are_received_in_order_and_reported_on_delivery() {
+    let mut server_channel = AuthorityChannel::default();
+    let mut client_channel = AuthorityChannel::default();
+    let net_entity = NetEntity::from(7);
+    let packet_index = 3;
+
+    // Server gives authority, then takes it back
+    server_channel.send_action(net_entity, AuthorityAction::Grant);
+    server_channel.send_action(net_entity, AuthorityAction::Revoke);
+    server_channel.collect_outgoing_actions(&Instant::now(), &0.0);
+    assert!(server_channel.has_outgoing_actions());
+
+    let mut writer = BitWriter::new();
+    server_channel.write_actions(&mut writer, &packet_index);
+    let (length, buffer) = writer.flush();
+
+    // Client receives both actions
+    let mut reader = BitReader::new(&buffer[..length]);
+    client_channel
+        .read_actions(&mut reader)
+        .expect("unable to read authority actions");
+    let received = client_channel.receive_actions();
+    assert_eq!(received.len(), 2);
+    assert!(received[0] == (net_entity, AuthorityAction::Grant));
+    assert!(received[1] == (net_entity, AuthorityAction::Revoke));
+
+    // Server learns that both were delivered
+    assert!(server_channel.take_delivered_actions().is_empty());
+    server_channel.notify_packet_delivered(packet_index);
+    let delivered = server_channel.take_delivered_actions();
+    assert_eq!(delivered.len(), 2);
+    assert!(delivered[0] == (net_entity, AuthorityAction::Grant));
+    assert!(!server_channel.has_outgoing_actions());
+}
+
+#[test]
+fn update_from_authority_only_clears_the_properties_it_changed() {
+    let address: SocketAddr = "127.0.0.1:14192".parse().unwrap();
+    let entity: u32 = 1;
+    let component_kind = Protocol::kind_of::<Stats>();
+
+    let global_diff_handler = Arc::new(RwLock::new(GlobalDiffHandler::default()));
+    let mut_sender =
+        global_diff_handler
+            .write()
+            .unwrap()
+            .register_component(&entity, &component_kind, 1);
+    let mut user_diff_handler = UserDiffHandler::new(&global_diff_handler);
+    user_diff_handler.register_component(&address, &entity, &component_kind);
+
+    let mut server_stats = Stats::new(100, 50, 0);
+    server_stats.set_mutator(&PropertyMutator::new(mut_sender));
+
+    // Client with authority changes health
+    let client_stats = Stats::new(90, 50, 0);
+    let mut client_mask = DiffMask::new(1);
+    client_mask.set_bit(StatsProperty::HEALTH as u8, true);
+    let mut writer = BitWriter::new();
+    client_stats.write_update(&client_mask, &mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+    let update = ComponentUpdate::new(component_kind, OwnedBitReader::new(&buffer[..length]));
+
+    // Server changes mana in the same tick
+    *server_stats.mana = 40;
+
+    let changed = server_stats
+        .read_update_diff_mask(&FakeEntityConverter, &update)
+        .expect("unable to read update");
+    server_stats
+        .read_apply_update(&FakeEntityConverter, update)
+        .expect("unable to apply update");
+    user_diff_handler.nand_diff_mask(&entity, &component_kind, &changed);
+
+    // the Client already has its health, but not the Server's mana
+    let diff_mask = user_diff_handler
+        .diff_mask(&entity, &component_kind)
+        .unwrap()
+        .clone();
+    assert_eq!(*server_stats.health, 90);
+    assert_eq!(diff_mask.bit(StatsProperty::HEALTH as u8), Some(false));
+    assert_eq!(diff_mask.bit(StatsProperty::MANA as u8), Some(true));
+}
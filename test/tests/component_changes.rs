@@ -0,0 +1,38 @@
+use naia_shared::{
+    serde::{BitWriter, OwnedBitReader},
+    ComponentUpdate, DiffMask, FakeEntityConverter, Protocolize, ReplicateSafe,
+};
+use naia_test::{Protocol, Stats, StatsProperty};
+
+#[test]
+fn update_reports_which_properties_it_changes() {
+    let stats = Stats::new(98, 50, 7);
+
+    let mut diff_mask = DiffMask::new(1);
+    diff_mask.set_bit(StatsProperty::HEALTH as u8, true);
+    diff_mask.set_bit(StatsProperty::TARGET as u8, true);
+
+    let mut writer = BitWriter::new();
+    stats.write_update(&diff_mask, &mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+    let update = ComponentUpdate::new(
+        Protocol::kind_of::<Stats>(),
+        OwnedBitReader::new(&buffer[..length]),
+    );
+
+    let receiver = Stats::new(100, 50, 0);
+    let changed = receiver
+        .read_update_diff_mask(&FakeEntityConverter, &update)
+        .expect("unable to read update");
+    assert_eq!(changed.bit(StatsProperty::HEALTH as u8), Some(true));
+    assert_eq!(changed.bit(StatsProperty::MANA as u8), Some(false));
+    assert_eq!(changed.bit(StatsProperty::TARGET as u8), Some(true));
+
+    // the update can still be applied afterwards
+    let mut receiver = receiver;
+    receiver
+        .read_apply_update(&FakeEntityConverter, update)
+        .expect("unable to apply update");
+    assert_eq!(*receiver.health, 98);
+    assert_eq!(*receiver.target, 7);
+}
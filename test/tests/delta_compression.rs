@@ -0,0 +1,56 @@
+use naia_shared::{
+    serde::{BitReader, BitWrite, BitWriter},
+    DiffMask, FakeEntityConverter, Protocolize, ReplicateSafe,
+};
+use naia_test::Stats;
+
+#[test]
+fn updates_are_written_as_changes_from_baseline() {
+    let baseline = Stats::new(100, 50, 7).into_protocol();
+    let stats = Stats::new(98, 50, 7);
+
+    let mut writer = BitWriter::new();
+    stats.write_update_delta(&baseline, &mut writer, &FakeEntityConverter);
+
+    // a whole update of the changed Property is larger
+    let mut diff_mask = DiffMask::new(1);
+    diff_mask.set_bit(0, true);
+    let mut full_writer = BitWriter::new();
+    stats.write_update(&diff_mask, &mut full_writer, &FakeEntityConverter);
+    assert!(writer.bit_count() < full_writer.bit_count());
+
+    let (length, buffer) = writer.flush();
+    let (received, changed) = baseline
+        .dyn_ref()
+        .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read update");
+
+    assert_eq!(changed.bit(0), Some(true));
+    assert_eq!(changed.bit(1), Some(false));
+    assert_eq!(changed.bit(2), Some(false));
+
+    let received = received
+        .cast::<Stats>()
+        .expect("should be a Stats component");
+    assert_eq!(*received.health, 98);
+    assert_eq!(*received.mana, 50);
+    assert_eq!(*received.target, 7);
+}
+
+#[test]
+fn unchanged_component_writes_one_bit_per_property() {
+    let baseline = Stats::new(100, 50, 7).into_protocol();
+    let stats = Stats::new(100, 50, 7);
+
+    let mut writer = BitWriter::new();
+    stats.write_update_delta(&baseline, &mut writer, &FakeEntityConverter);
+    assert_eq!(writer.bit_count(), 3);
+
+    let (length, buffer) = writer.flush();
+    let (received, changed) = baseline
+        .dyn_ref()
+        .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .unwrap();
+    assert!(changed.is_clear());
+    assert_eq!(*received.cast::<Stats>().unwrap().health, 100);
+}
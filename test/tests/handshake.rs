@@ -1,19 +1,22 @@
 use std::time::Duration;
 
-use naia_client::internal::{HandshakeManager as ClientHandshakeManager, HandshakeState};
+use naia_client::internal::{
+    HandshakeManager as ClientHandshakeManager, HandshakeResult as ClientHandshakeResult,
+    HandshakeState,
+};
 use naia_server::internal::{HandshakeManager as ServerHandshakeManager, HandshakeResult};
 use naia_shared::{
     serde::{BitReader, BitWriter, Serde},
-    PacketType, Protocolize, StandardHeader,
+    CompressionDictionary, PacketType, Protocolize, RejectReason, StandardHeader, PROTOCOL_VERSION,
 };
 use naia_test::{Auth, Protocol};
 
 #[test]
 fn end_to_end_handshake_w_auth() {
-    let mut client = ClientHandshakeManager::<Protocol>::new(Duration::new(0, 0));
+    let mut client = ClientHandshakeManager::<Protocol>::new(Duration::new(0, 0), None, None);
     let mut server = ServerHandshakeManager::<Protocol>::new(true);
     let mut message_length: usize;
-    let mut message_buffer: [u8; 508];
+    let mut message_buffer: Vec<u8>;
     let mut writer: BitWriter;
     let mut reader: BitReader;
 
@@ -34,7 +37,7 @@ fn end_to_end_handshake_w_auth() {
     {
         reader = BitReader::new(&message_buffer[..message_length]);
         StandardHeader::de(&mut reader).expect("unable to read standard header from stream");
-        writer = server.recv_challenge_request(&mut reader).unwrap();
+        writer = server.recv_challenge_request(&mut reader, 0).unwrap();
     }
 
     // 3. Server send challenge response
@@ -105,3 +108,89 @@ fn end_to_end_handshake_w_auth() {
         client.recv_connect_response();
     }
 }
+
+#[test]
+fn handshake_fetches_compression_dictionary() {
+    let mut client = ClientHandshakeManager::<Protocol>::new(Duration::new(0, 0), None, None);
+    let mut server = ServerHandshakeManager::<Protocol>::new(false);
+    let dictionary = CompressionDictionary::new(5, (0..1000).map(|i| (i % 251) as u8).collect());
+    let mut writer: BitWriter;
+    let mut reader: BitReader;
+
+    // 1. Client send challenge request, Server respond with dictionary version
+    {
+        writer = client.write_challenge_request();
+        let (length, buffer) = writer.flush();
+        reader = BitReader::new(&buffer[..length]);
+        StandardHeader::de(&mut reader).expect("unable to read standard header from stream");
+        writer = server
+            .recv_challenge_request(&mut reader, dictionary.version)
+            .unwrap();
+    }
+
+    // 2. Client receive challenge response, must now fetch the dictionary
+    {
+        let (length, buffer) = writer.flush();
+        reader = BitReader::new(&buffer[..length]);
+        StandardHeader::de(&mut reader).expect("unable to read standard header from stream");
+        client.recv_challenge_response(&mut reader);
+        assert_eq!(client.connection_state, HandshakeState::AwaitingDictionary);
+    }
+
+    // 3. Client request chunks until the dictionary is complete
+    let mut received = None;
+    for _ in 0..dictionary.chunk_count() {
+        writer = client.write_dictionary_request();
+        let (length, buffer) = writer.flush();
+        reader = BitReader::new(&buffer[..length]);
+        StandardHeader::de(&mut reader).expect("unable to read standard header from stream");
+        let (version, next_chunk) = server
+            .recv_dictionary_request(&mut reader)
+            .expect("dictionary request should be valid");
+        assert_eq!(version, dictionary.version);
+
+        writer = dictionary.write_chunk(next_chunk);
+        let (length, buffer) = writer.flush();
+        reader = BitReader::new(&buffer[..length]);
+        StandardHeader::de(&mut reader).expect("unable to read standard header from stream");
+        received = client
+            .recv_dictionary_chunk(&mut reader)
+            .expect("dictionary chunk should be valid");
+    }
+
+    let received = received.expect("dictionary should be complete");
+    assert_eq!(received.version, dictionary.version);
+    assert_eq!(received.bytes, dictionary.bytes);
+    assert_eq!(
+        client.connection_state,
+        HandshakeState::AwaitingConnectResponse
+    );
+}
+
+#[test]
+fn server_rejects_mismatched_protocol_version() {
+    let mut client = ClientHandshakeManager::<Protocol>::new(Duration::new(0, 0), None, None);
+    let mut server = ServerHandshakeManager::<Protocol>::new(false);
+
+    // 1. Client with a different protocol version sends challenge request
+    let mut writer = BitWriter::new();
+    StandardHeader::new(PacketType::ClientChallengeRequest, 0, 0, 0).ser(&mut writer);
+    0_u64.ser(&mut writer);
+    (PROTOCOL_VERSION + 1).ser(&mut writer);
+    let (length, buffer) = writer.flush();
+
+    // 2. Server responds with a rejection
+    let mut reader = BitReader::new(&buffer[..length]);
+    StandardHeader::de(&mut reader).expect("unable to read standard header from stream");
+    let mut writer = server.recv_challenge_request(&mut reader, 0).unwrap();
+    let (length, buffer) = writer.flush();
+
+    // 3. Client receives the reason for the rejection
+    let mut reader = BitReader::new(&buffer[..length]);
+    match client.recv(&mut reader) {
+        Some(ClientHandshakeResult::Rejected(reason)) => {
+            assert_eq!(reason, RejectReason::VersionMismatch)
+        }
+        _ => panic!("expected the Server to reject the Client"),
+    }
+}
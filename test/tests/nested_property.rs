@@ -0,0 +1,116 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use naia_shared::{
+    serde::{BitReader, BitWrite, BitWriter, OwnedBitReader},
+    ComponentUpdate, DiffMask, FakeEntityConverter, GlobalDiffHandler, PropertyMutator,
+    Protocolize, ReplicateSafe, UserDiffHandler,
+};
+use naia_test::{Protocol, ProtocolKind, Transform, TransformProperty, Vector};
+
+// the nested Properties take the bits after every top-level Property
+const TRANSLATION_X: u8 = 1;
+const TRANSLATION_Y: u8 = 2;
+
+#[test]
+fn mutating_nested_property_marks_only_its_own_bit() {
+    let address: SocketAddr = "127.0.0.1:14192".parse().unwrap();
+    let entity: u32 = 1;
+    let component_kind = ProtocolKind::Transform;
+
+    let mut transform = Transform::new(Vector::new(1, 2), 90);
+    assert_eq!(transform.diff_mask_size(), 1);
+
+    let global_diff_handler = Arc::new(RwLock::new(GlobalDiffHandler::default()));
+    let mut_sender = global_diff_handler.write().unwrap().register_component(
+        &entity,
+        &component_kind,
+        transform.diff_mask_size(),
+    );
+    let mut user_diff_handler = UserDiffHandler::new(&global_diff_handler);
+    user_diff_handler.register_component(&address, &entity, &component_kind);
+    transform.set_mutator(&PropertyMutator::new(mut_sender));
+
+    *transform.translation.y = 5;
+
+    let diff_mask = user_diff_handler
+        .diff_mask(&entity, &component_kind)
+        .unwrap()
+        .clone();
+    assert_eq!(
+        diff_mask.bit(TransformProperty::ROTATION as u8),
+        Some(false)
+    );
+    assert_eq!(diff_mask.bit(TRANSLATION_X), Some(false));
+    assert_eq!(diff_mask.bit(TRANSLATION_Y), Some(true));
+}
+
+#[test]
+fn update_sends_only_changed_nested_properties() {
+    let transform = Transform::new(Vector::new(1, 5), 90);
+
+    let mut diff_mask = DiffMask::new(1);
+    diff_mask.set_bit(TRANSLATION_Y, true);
+
+    let mut writer = BitWriter::new();
+    transform.write_update(&diff_mask, &mut writer, &FakeEntityConverter);
+    // one bit per Property, and the value of the changed one
+    assert_eq!(writer.bit_count(), 3 + 16);
+
+    let (length, buffer) = writer.flush();
+    let update = ComponentUpdate::new(
+        Protocol::kind_of::<Transform>(),
+        OwnedBitReader::new(&buffer[..length]),
+    );
+
+    let mut receiver = Transform::new(Vector::new(1, 2), 0);
+    let changed = receiver
+        .read_update_diff_mask(&FakeEntityConverter, &update)
+        .expect("unable to read update");
+    assert_eq!(changed.bit(TransformProperty::ROTATION as u8), Some(false));
+    assert_eq!(changed.bit(TRANSLATION_X), Some(false));
+    assert_eq!(changed.bit(TRANSLATION_Y), Some(true));
+
+    receiver
+        .read_apply_update(&FakeEntityConverter, update)
+        .expect("unable to apply update");
+    assert_eq!(*receiver.translation.x, 1);
+    assert_eq!(*receiver.translation.y, 5);
+    assert_eq!(*receiver.rotation, 0);
+}
+
+#[test]
+fn nested_properties_round_trip_and_delta_compress() {
+    let baseline = Transform::new(Vector::new(1, 2), 90).into_protocol();
+    let transform = Transform::new(Vector::new(3, 2), 90);
+
+    // a Component with nested Properties is written and read whole
+    let mut writer = BitWriter::new();
+    transform.write(&mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+    let received = Protocol::read(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read component")
+        .cast::<Transform>()
+        .unwrap();
+    assert_eq!(*received.translation.x, 3);
+    assert_eq!(*received.translation.y, 2);
+    assert_eq!(*received.rotation, 90);
+
+    // and only the changed nested Property differs from the baseline
+    let mut writer = BitWriter::new();
+    transform.write_update_delta(&baseline, &mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+    let (received, changed) = baseline
+        .dyn_ref()
+        .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read update");
+    assert_eq!(changed.bit(TransformProperty::ROTATION as u8), Some(false));
+    assert_eq!(changed.bit(TRANSLATION_X), Some(true));
+    assert_eq!(changed.bit(TRANSLATION_Y), Some(false));
+
+    let received = received.cast::<Transform>().unwrap();
+    assert_eq!(*received.translation.x, 3);
+    assert_eq!(*received.translation.y, 2);
+}
@@ -0,0 +1,50 @@
+use naia_client::{Prediction, PredictionConfig};
+use naia_test::{Position, Protocol};
+
+// each command moves the Entity by its amount
+fn step(_entity: &u32, position: &mut Position, command: &i16) {
+    *position.x += *command;
+}
+
+#[test]
+fn correct_predictions_are_kept() {
+    let mut prediction =
+        Prediction::<Protocol, u32, Position, i16>::new(&PredictionConfig::default());
+    prediction.start_predicting(&7, Position::new(0));
+
+    prediction.predict(1, 2, step);
+    prediction.predict(2, 3, step);
+    assert_eq!(*prediction.predicted(&7).unwrap().x, 5);
+
+    // the Server agrees with what was predicted for tick 1
+    let rolled_back = prediction.reconcile(&7, 1, &Position::new(2), step);
+    assert!(!rolled_back);
+    assert_eq!(*prediction.predicted(&7).unwrap().x, 5);
+}
+
+#[test]
+fn mispredictions_are_rolled_back_and_replayed() {
+    let mut prediction =
+        Prediction::<Protocol, u32, Position, i16>::new(&PredictionConfig { smoothing_ticks: 2 });
+    prediction.start_predicting(&7, Position::new(0));
+
+    prediction.predict(1, 2, step);
+    prediction.predict(2, 3, step);
+    prediction.predict(3, 1, step);
+
+    // the Server was blocked on tick 1, so the commands since are replayed
+    // from where it ended up
+    let rolled_back = prediction.reconcile(&7, 1, &Position::new(0), step);
+    assert!(rolled_back);
+    assert_eq!(*prediction.predicted(&7).unwrap().x, 4);
+
+    // the correction is blended in over the following ticks
+    assert_eq!(*prediction.rendered(&7).unwrap().x, 6);
+    prediction.predict(4, 0, step);
+    assert_eq!(*prediction.rendered(&7).unwrap().x, 5);
+    prediction.predict(5, 0, step);
+    assert_eq!(*prediction.rendered(&7).unwrap().x, 4);
+
+    // the replayed predictions are checked against later updates
+    assert!(!prediction.reconcile(&7, 2, &Position::new(3), step));
+}
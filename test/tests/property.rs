@@ -0,0 +1,47 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use naia_shared::{
+    serde::{BitReader, BitWriter, Serde},
+    GlobalDiffHandler, Property, PropertyMutator, UserDiffHandler,
+};
+use naia_test::ProtocolKind;
+
+#[test]
+fn reading_replicated_property_marks_it_mutated() {
+    let address: SocketAddr = "127.0.0.1:14191".parse().unwrap();
+    let entity: u32 = 1;
+    let component_kind = ProtocolKind::Auth;
+
+    let global_diff_handler = Arc::new(RwLock::new(GlobalDiffHandler::default()));
+    let mut_sender =
+        global_diff_handler
+            .write()
+            .unwrap()
+            .register_component(&entity, &component_kind, 1);
+    let mut user_diff_handler = UserDiffHandler::new(&global_diff_handler);
+    user_diff_handler.register_component(&address, &entity, &component_kind);
+
+    let mut property = Property::<u16>::new(0, 0);
+    property.set_mutator(&PropertyMutator::new(mut_sender));
+    assert_eq!(
+        user_diff_handler.diff_mask_is_clear(&entity, &component_kind),
+        Some(true)
+    );
+
+    // a value received from a Client must be passed on to other Clients
+    let mut writer = BitWriter::new();
+    42u16.ser(&mut writer);
+    let (length, buffer) = writer.flush();
+    property
+        .read(&mut BitReader::new(&buffer[..length]))
+        .unwrap();
+
+    assert_eq!(*property, 42);
+    assert_eq!(
+        user_diff_handler.diff_mask_is_clear(&entity, &component_kind),
+        Some(false)
+    );
+}
@@ -0,0 +1,76 @@
+use std::{
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+};
+
+use naia_shared::{
+    serde::{BitReader, BitWrite, BitWriter},
+    DiffMask, FakeEntityConverter, GlobalDiffHandler, PropertyMutator, Protocolize, ReplicateSafe,
+    UserDiffHandler,
+};
+use naia_test::{Inventory, InventoryProperty, ProtocolKind};
+
+#[test]
+fn mutating_collection_marks_it_mutated() {
+    let address: SocketAddr = "127.0.0.1:14193".parse().unwrap();
+    let entity: u32 = 1;
+    let component_kind = ProtocolKind::Inventory;
+
+    let mut inventory = Inventory::new(vec![1, 2, 3]);
+
+    let global_diff_handler = Arc::new(RwLock::new(GlobalDiffHandler::default()));
+    let mut_sender = global_diff_handler.write().unwrap().register_component(
+        &entity,
+        &component_kind,
+        inventory.diff_mask_size(),
+    );
+    let mut user_diff_handler = UserDiffHandler::new(&global_diff_handler);
+    user_diff_handler.register_component(&address, &entity, &component_kind);
+    inventory.set_mutator(&PropertyMutator::new(mut_sender));
+
+    inventory.items.push(4);
+
+    let diff_mask = user_diff_handler
+        .diff_mask(&entity, &component_kind)
+        .unwrap()
+        .clone();
+    assert_eq!(diff_mask.bit(InventoryProperty::ITEMS as u8), Some(true));
+    assert_eq!(diff_mask.bit(InventoryProperty::COUNTS as u8), Some(false));
+}
+
+#[test]
+fn delta_update_sends_only_changed_elements() {
+    let baseline = Inventory::new((0..20).collect()).into_protocol();
+
+    let mut inventory = Inventory::new((0..20).collect());
+    inventory.items.remove(5);
+    inventory.items.push(100);
+    inventory.counts.remove(&5);
+    inventory.counts.insert(100, 1);
+    *inventory.counts.get_mut(&7).unwrap() = 3;
+
+    let mut writer = BitWriter::new();
+    inventory.write_update_delta(&baseline, &mut writer, &FakeEntityConverter);
+
+    // a whole update of the changed collections is much larger
+    let mut diff_mask = DiffMask::new(1);
+    diff_mask.set_bit(InventoryProperty::ITEMS as u8, true);
+    diff_mask.set_bit(InventoryProperty::COUNTS as u8, true);
+    let mut full_writer = BitWriter::new();
+    inventory.write_update(&diff_mask, &mut full_writer, &FakeEntityConverter);
+    assert!(writer.bit_count() * 2 < full_writer.bit_count());
+
+    let (length, buffer) = writer.flush();
+    let (received, changed) = baseline
+        .dyn_ref()
+        .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read update");
+    assert_eq!(changed.bit(InventoryProperty::ITEMS as u8), Some(true));
+    assert_eq!(changed.bit(InventoryProperty::COUNTS as u8), Some(true));
+
+    let received = received
+        .cast::<Inventory>()
+        .expect("should be an Inventory component");
+    assert_eq!(*received.items, *inventory.items);
+    assert_eq!(*received.counts, *inventory.counts);
+}
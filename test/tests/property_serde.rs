@@ -0,0 +1,95 @@
+use naia_shared::{
+    serde::{BitReader, BitWrite, BitWriter},
+    FakeEntityConverter, Protocolize, ReplicateSafe,
+};
+use naia_test::{Heading, Protocol};
+
+#[test]
+fn custom_property_controls_full_writes() {
+    let heading = Heading::new(90.04);
+
+    let mut writer = BitWriter::new();
+    heading.write(&mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+    let received = Protocol::read(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read component")
+        .cast::<Heading>()
+        .unwrap();
+
+    // the angle is synced to a tenth of a degree
+    assert_eq!(received.angle.0, 90.0);
+}
+
+#[test]
+fn changes_below_threshold_are_not_sent() {
+    let baseline = Heading::new(90.0).into_protocol();
+
+    let heading = Heading::new(90.02);
+    let mut writer = BitWriter::new();
+    heading.write_update_delta(&baseline, &mut writer, &FakeEntityConverter);
+    assert_eq!(writer.bit_count(), 1);
+
+    let (length, buffer) = writer.flush();
+    let (received, changed) = baseline
+        .dyn_ref()
+        .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read update");
+    assert!(changed.is_clear());
+    assert_eq!(received.cast::<Heading>().unwrap().angle.0, 90.0);
+}
+
+#[test]
+fn custom_property_controls_delta_writes() {
+    let baseline = Heading::new(90.0).into_protocol();
+
+    let heading = Heading::new(90.5);
+    let mut writer = BitWriter::new();
+    heading.write_update_delta(&baseline, &mut writer, &FakeEntityConverter);
+    // smaller than the 16 bits of a whole value
+    assert!(writer.bit_count() < 1 + 16);
+
+    let (length, buffer) = writer.flush();
+    let (received, changed) = baseline
+        .dyn_ref()
+        .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read update");
+    assert_eq!(changed.bit(0), Some(true));
+    assert_eq!(received.cast::<Heading>().unwrap().angle.0, 90.5);
+}
+
+#[test]
+fn changes_below_threshold_accumulate_over_ticks() {
+    let mut server_baseline = Heading::new(90.0).into_protocol();
+    let mut client_baseline = Heading::new(90.0).into_protocol();
+
+    let mut heading = Heading::new(90.0);
+    for _ in 0..10 {
+        // each change alone is too small to be sent
+        heading.angle.0 += 0.04;
+
+        let mut writer = BitWriter::new();
+        heading.write_update_delta(&server_baseline, &mut writer, &FakeEntityConverter);
+        let (length, buffer) = writer.flush();
+        let (received, changed) = client_baseline
+            .dyn_ref()
+            .read_update_delta(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+            .expect("unable to read update");
+
+        // the state recorded as sent is the one the client has, so unsent
+        // changes are still written against the value the client kept
+        let previous_angle = server_baseline.cast_ref::<Heading>().unwrap().angle.0;
+        server_baseline = heading.delta_sent_state(&server_baseline);
+        let server_angle = server_baseline.cast_ref::<Heading>().unwrap().angle.0;
+        let client_angle = received.cast_ref::<Heading>().unwrap().angle.0;
+        if changed.is_clear() {
+            assert_eq!(server_angle, previous_angle);
+        }
+        assert_eq!((server_angle * 10.0).round(), (client_angle * 10.0).round());
+
+        client_baseline = received;
+    }
+
+    // the client followed the whole change
+    let client_angle = client_baseline.cast::<Heading>().unwrap().angle.0;
+    assert!((client_angle - 90.4).abs() < 0.001);
+}
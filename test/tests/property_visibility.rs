@@ -0,0 +1,52 @@
+use naia_shared::{
+    serde::{BitReader, BitWriter},
+    DiffMask, FakeEntityConverter, Protocolize, ReplicateSafe,
+};
+use naia_test::{Protocol, Stats};
+
+#[test]
+fn marked_properties_are_reported_by_visibility() {
+    let stats = Stats::new(100, 50, 7);
+
+    let owner_only = stats.owner_only_properties();
+    assert_eq!(owner_only.bit(0), Some(false));
+    assert_eq!(owner_only.bit(1), Some(true));
+    assert_eq!(owner_only.bit(2), Some(false));
+
+    let conditional = stats.conditional_properties();
+    assert_eq!(conditional.bit(0), Some(false));
+    assert_eq!(conditional.bit(1), Some(false));
+    assert_eq!(conditional.bit(2), Some(true));
+}
+
+#[test]
+fn hidden_properties_are_written_as_default_values() {
+    let stats = Stats::new(100, 50, 7);
+
+    let mut hidden = stats.owner_only_properties();
+    hidden.or(&stats.conditional_properties());
+
+    let mut writer = BitWriter::new();
+    stats.write_masked(&hidden, &mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+
+    let received = Protocol::read(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .expect("unable to read component")
+        .cast::<Stats>()
+        .expect("should be a Stats component");
+    assert_eq!(*received.health, 100);
+    assert_eq!(*received.mana, 0);
+    assert_eq!(*received.target, 0);
+
+    // with nothing hidden, the whole Component is written
+    let mut writer = BitWriter::new();
+    stats.write_masked(&DiffMask::new(1), &mut writer, &FakeEntityConverter);
+    let (length, buffer) = writer.flush();
+
+    let received = Protocol::read(&mut BitReader::new(&buffer[..length]), &FakeEntityConverter)
+        .unwrap()
+        .cast::<Stats>()
+        .unwrap();
+    assert_eq!(*received.mana, 50);
+    assert_eq!(*received.target, 7);
+}
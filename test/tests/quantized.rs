@@ -0,0 +1,52 @@
+use naia_shared::{
+    derive_serde,
+    serde::{
+        self, BitReader, BitWriter, QuantizedFloat, QuantizedQuaternion, QuantizedUnitVector, Serde,
+    },
+    Property,
+};
+
+type Coordinate = QuantizedFloat<-1000, 1000, 16>;
+
+#[derive_serde]
+struct Transform {
+    x: Coordinate,
+    y: Coordinate,
+    rotation: QuantizedQuaternion<9>,
+    facing: QuantizedUnitVector<8>,
+}
+
+#[test]
+fn quantized_types_round_trip_in_derived_structs() {
+    let transform = Transform {
+        x: Coordinate::new(-512.25),
+        y: Coordinate::new(3.5),
+        rotation: QuantizedQuaternion::new([0.0, 0.0, 0.0, 1.0]),
+        facing: QuantizedUnitVector::new([1.0, 0.0, 0.0]),
+    };
+
+    let mut writer = BitWriter::new();
+    transform.ser(&mut writer);
+    let (length, buffer) = writer.flush();
+
+    let received = Transform::de(&mut BitReader::new(&buffer[..length])).unwrap();
+    assert!(received == transform);
+    assert!((received.x.get() + 512.25).abs() <= Coordinate::max_error());
+    assert!((received.y.get() - 3.5).abs() <= Coordinate::max_error());
+    assert!((received.rotation.get()[3] - 1.0).abs() <= 0.01);
+    assert!((received.facing.get()[0] - 1.0).abs() <= 0.01);
+}
+
+#[test]
+fn quantized_properties_round_trip() {
+    let property = Property::<Coordinate>::new(Coordinate::new(42.0), 0);
+
+    let mut writer = BitWriter::new();
+    property.write(&mut writer);
+    let (length, buffer) = writer.flush();
+
+    let received = Property::<Coordinate>::new_read(&mut BitReader::new(&buffer[..length]), 0)
+        .expect("unable to read property");
+    assert!(property.equals(&received));
+    assert!((received.get() - 42.0).abs() <= Coordinate::max_error());
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use naia_shared::{
+    serde::{BitReader, Serde},
+    PacketType, Protocolize, ServerInfo, StandardHeader, MTU_SIZE_BYTES,
+};
+use naia_test::{Auth, Protocol};
+
+#[test]
+fn server_info_response_round_trip() {
+    let server_info = ServerInfo::<Protocol> {
+        user_count: 3,
+        max_users: Some(16),
+        tick_interval: Some(Duration::from_millis(50)),
+        protocol_fingerprint: Protocol::protocol_fingerprint(),
+        info: Some(Protocol::Auth(Auth::new("lobby", "eu-west"))),
+    };
+
+    let mut writer = server_info.write_response(42);
+    let (length, buffer) = writer.flush();
+
+    let mut reader = BitReader::new(&buffer[..length]);
+    let header = StandardHeader::de(&mut reader).expect("unable to read standard header");
+    assert_eq!(header.packet_type, PacketType::ServerInfoResponse);
+
+    let (query_id, received) = ServerInfo::<Protocol>::read_response(&mut reader).unwrap();
+    assert_eq!(query_id, 42);
+    assert_eq!(received.user_count, 3);
+    assert_eq!(received.max_users, Some(16));
+    assert_eq!(received.tick_interval, Some(Duration::from_millis(50)));
+    assert!(received.is_compatible());
+
+    let info = received.info.expect("info payload should be present");
+    let auth = info.cast_ref::<Auth>().expect("info should be an Auth");
+    assert_eq!(*auth.username, "lobby");
+    assert_eq!(*auth.password, "eu-west");
+}
+
+#[test]
+fn request_is_larger_than_any_response() {
+    let mut writer = ServerInfo::<Protocol>::write_request(42);
+    let (length, buffer) = writer.flush();
+    // leaves room for the compression flag byte
+    assert_eq!(length, MTU_SIZE_BYTES as usize - 1);
+
+    let mut reader = BitReader::new(&buffer[..length]);
+    let header = StandardHeader::de(&mut reader).expect("unable to read standard header");
+    assert_eq!(header.packet_type, PacketType::ClientInfoRequest);
+    assert_eq!(u64::de(&mut reader).unwrap(), 42);
+
+    let oversized = ServerInfo::<Protocol> {
+        user_count: 0,
+        max_users: None,
+        tick_interval: None,
+        protocol_fingerprint: Protocol::protocol_fingerprint(),
+        info: Some(Protocol::Auth(Auth::new(
+            &"a".repeat(300),
+            &"b".repeat(300),
+        ))),
+    };
+    assert!(!oversized.fits_in_response());
+
+    let fitting = ServerInfo::<Protocol> {
+        info: Some(Protocol::Auth(Auth::new(
+            &"a".repeat(200),
+            &"b".repeat(200),
+        ))),
+        ..oversized
+    };
+    assert!(fitting.fits_in_response());
+    let (response_length, _) = fitting.write_response(42).flush();
+    assert!(response_length < length);
+}